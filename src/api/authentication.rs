@@ -11,14 +11,9 @@ use hmac::digest::{InvalidLength, KeyInit};
 use hmac::Hmac;
 use jwt::SignWithKey;
 use sha2::Sha256;
-use crate::api::shared::GETError;
-use crate::database::repositories::SelectRepository;
 use crate::middleware::TokenClaims;
-use crate::migrations::DatabaseMigration;
-use crate::migrations::user_migration::UserMigration;
 use crate::model::states::app_state::AppState;
 use crate::model::user::{SelectUserById, SelectUserByName};
-use crate::utils::version::Version;
 
 #[derive(Debug)]
 pub enum AuthError {
@@ -116,18 +111,3 @@ pub async fn logout_cookie(_: TokenClaims) -> Result<impl Responder, AuthError>
         .finish()
     )
 }
-
-#[get("/migrate")]
-pub async fn migrate(app_state: Data<AppState>) -> Result<impl Responder, GETError> {
-    let user_migration = UserMigration { version: Version { version: 1.0 } };
-
-    match user_migration.migrate(&app_state.db).await {
-        Ok(_) => {}
-        Err(err) => {
-            log::error!("{err:?}");
-            std::process::exit(1);
-        }
-    }
-
-    Ok(HttpResponse::Ok())
-}
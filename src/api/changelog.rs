@@ -1,48 +1,276 @@
 use std::str::FromStr;
-use actix_web::get;
-use actix_web::web::{Json};
-use crate::api::shared::GETError;
+use actix_web::{delete, get, post, put, HttpRequest, HttpResponse};
+use actix_web::web::{Data, Json, Path, Query};
+use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
+use uuid::Uuid;
+use crate::api::shared::{DeleteError, GETError, UploadError};
+use crate::database::database_context::DatabaseContext;
 use crate::middleware::TokenClaims;
-use crate::utils::read_files_in_directory;
+use crate::model::changelog::{ChangelogEntry, ChangelogError, ChangelogInformation, ChangelogStatus, ChangelogVersion, DeleteChangelogDraft, PublishChangelog, SelectChangelog, SelectChangelogs, UnreadChangelog, UpdateChangelog};
+use crate::model::states::app_state::AppState;
+use crate::model::user::{SelectUserByFeedToken, SelectUserById, UpdateFeedToken, UpdateLastSeenChangelog};
+use crate::utils::{atom, read_files_in_directory};
 use crate::utils::version::Version;
 
-#[get("/changelog")]
-pub async fn changelog(claims: TokenClaims) -> Result<Json<Vec<String>>, GETError> {
-    let version = get_highest_version()?;
-    let path = format!("./changelog/Version {}", version.version);
+// only read to seed the database, changelogs are published through the admin endpoints
+const CHANGELOG_DIRECTORY: &str = "./changelog";
+
+#[derive(Debug, Deserialize)]
+pub struct SeenChangelog {
+    // the newest version by default
+    version: Option<Version>
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FeedQuery {
+    token: Option<String>
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeedToken {
+    token: String
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateChangelog {
+    version: Version,
+    #[serde(default)]
+    added: Vec<String>,
+    #[serde(default)]
+    fixed: Vec<String>,
+    #[serde(default)]
+    changed: Vec<String>
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChangelogChanges {
+    added: Option<Vec<String>>,
+    fixed: Option<Vec<String>>,
+    changed: Option<Vec<String>>
+}
 
+#[get("/changelog")]
+pub async fn changelog(state: Data<AppState>, claims: TokenClaims) -> Result<Json<Vec<String>>, GETError> {
     // so it doesnt get optimized out
     // claims is needed for authentication
     let _ = claims.id;
 
-    if let Ok(content) = std::fs::read_to_string(path) {
-        return Ok(Json(content.lines().map(|a| a.to_string()).collect::<Vec<_>>()));
-    }
-
-    Ok(Json(vec![]))
+    Ok(Json(newest_changelog(&state).await?.lines()))
 }
 
 #[get("/changelog/version")]
-pub async fn changelog_version(claims: TokenClaims) -> Result<Json<f32>, GETError> {
+pub async fn changelog_version(state: Data<AppState>, claims: TokenClaims) -> Result<Json<Version>, GETError> {
+    let _ = claims.id;
+
+    Ok(Json(newest_changelog(&state).await?.version))
+}
+
+// every published version, newest first
+#[get("/changelogs")]
+pub async fn changelog_history(state: Data<AppState>, claims: TokenClaims) -> Result<Json<Vec<ChangelogVersion>>, GETError> {
     let _ = claims.id;
-    let version = get_highest_version()?;
 
-    Ok(Json(version.version))
+    Ok(Json(published_changelogs(&state).await?))
+}
+
+// the versions the requesting user has not seen yet. Users who never marked one as seen only get the newest
+#[get("/changelog/unread")]
+pub async fn unread_changelog(state: Data<AppState>, claims: TokenClaims) -> Result<Json<UnreadChangelog>, GETError> {
+    let user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+    let history = published_changelogs(&state).await?;
+
+    let versions = match &user.last_seen_changelog {
+        Some(last_seen) => history.into_iter().filter(|version| version.version > *last_seen).collect(),
+        None => history.into_iter().take(1).collect()
+    };
+
+    Ok(Json(UnreadChangelog { last_seen: user.last_seen_changelog, versions }))
+}
+
+#[post("/changelog/seen")]
+pub async fn mark_changelog_seen(body: Option<Json<SeenChangelog>>, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, UploadError> {
+    let version = match body.and_then(|body| body.into_inner().version) {
+        Some(version) => version,
+        None => newest_changelog(&state).await?.version
+    };
+
+    state.db.user().update(&UpdateLastSeenChangelog { target_id: claims.id, version }).await?;
+
+    Ok(HttpResponse::Ok().into())
+}
+
+// feed readers can not send the cookie, so the feed takes the api key as `X-API-Key` or the feed token of a user
+#[get("/changelog/feed")]
+pub async fn changelog_feed(request: HttpRequest, query: Query<FeedQuery>, state: Data<AppState>) -> Result<HttpResponse, ChangelogError> {
+    let api_key = request.headers().get("X-API-Key").and_then(|key| key.to_str().ok());
+    let authorized = match (api_key, &state.feed_api_key, &query.token) {
+        // in constant time, so the key can not be guessed byte by byte
+        (Some(api_key), Some(feed_api_key), _) => bool::from(api_key.as_bytes().ct_eq(feed_api_key.as_bytes())),
+        (None, _, Some(token)) => state.db.user().select(&SelectUserByFeedToken { token }).await.is_ok(),
+        _ => false
+    };
+    if !authorized {
+        return Err(ChangelogError::Unauthorized);
+    }
+
+    let changelogs = state.db.changelog().select(&SelectChangelogs { status: Some(ChangelogStatus::Published) }).await?;
+    let connection = request.connection_info();
+    let self_url = format!("{}://{}{}", connection.scheme(), connection.host(), request.path());
+
+    Ok(HttpResponse::Ok()
+        .content_type(atom::CONTENT_TYPE)
+        .body(atom::changelog_feed(&self_url, &changelogs)))
+}
+
+// replaces the previous token of the user, if there was one
+#[post("/changelog/feed/token")]
+pub async fn create_feed_token(state: Data<AppState>, claims: TokenClaims) -> Result<Json<FeedToken>, UploadError> {
+    let token = Uuid::new_v4().simple().to_string();
+    state.db.user().update(&UpdateFeedToken { target_id: claims.id, token: Some(token.clone()) }).await?;
+
+    Ok(Json(FeedToken { token }))
+}
+
+#[delete("/changelog/feed/token")]
+pub async fn delete_feed_token(state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, DeleteError> {
+    state.db.user().update(&UpdateFeedToken { target_id: claims.id, token: None }).await?;
+
+    Ok(HttpResponse::Ok().into())
+}
+
+// every version, drafts included
+#[get("/admin/changelogs")]
+pub async fn admin_changelogs(state: Data<AppState>, claims: TokenClaims) -> Result<Json<Vec<ChangelogInformation>>, ChangelogError> {
+    require_admin(&state, &claims).await?;
+
+    let changelogs = state.db.changelog().select(&SelectChangelogs { status: None }).await?;
+
+    Ok(Json(changelogs.into_iter().map(ChangelogInformation::from).collect()))
+}
+
+#[post("/admin/changelogs")]
+pub async fn create_changelog(body: Json<CreateChangelog>, state: Data<AppState>, claims: TokenClaims) -> Result<Json<ChangelogInformation>, ChangelogError> {
+    require_admin(&state, &claims).await?;
+
+    let body = body.into_inner();
+    if state.db.changelog().select(&SelectChangelog { version: body.version.clone() }).await?.is_some() {
+        return Err(ChangelogError::AlreadyExists);
+    }
+
+    let draft = ChangelogEntry::draft(ChangelogVersion {
+        version: body.version,
+        added: body.added,
+        fixed: body.fixed,
+        changed: body.changed,
+    });
+
+    Ok(Json(state.db.changelog().insert(draft).await?.into()))
+}
+
+// published versions can be edited as well, e.g. to fix a typo
+#[put("/admin/changelogs/{version}")]
+pub async fn update_changelog(path: Path<String>, body: Json<ChangelogChanges>, state: Data<AppState>, claims: TokenClaims) -> Result<Json<ChangelogInformation>, ChangelogError> {
+    require_admin(&state, &claims).await?;
+
+    let body = body.into_inner();
+    let update = UpdateChangelog {
+        version: parse_version(&path)?,
+        added: body.added,
+        fixed: body.fixed,
+        changed: body.changed,
+    };
+
+    match state.db.changelog().update(&update).await? {
+        Some(updated) => Ok(Json(updated.into())),
+        None => Err(ChangelogError::NotFound)
+    }
+}
+
+#[post("/admin/changelogs/{version}/publish")]
+pub async fn publish_changelog(path: Path<String>, state: Data<AppState>, claims: TokenClaims) -> Result<Json<ChangelogInformation>, ChangelogError> {
+    require_admin(&state, &claims).await?;
+
+    let version = parse_version(&path)?;
+    if let Some(published) = state.db.changelog().update(&PublishChangelog { version: version.clone() }).await? {
+        return Ok(Json(published.into()));
+    }
+
+    match state.db.changelog().select(&SelectChangelog { version }).await? {
+        Some(_) => Err(ChangelogError::AlreadyPublished),
+        None => Err(ChangelogError::NotFound)
+    }
+}
+
+// published versions stay, users may have seen them already
+#[delete("/admin/changelogs/{version}")]
+pub async fn delete_changelog(path: Path<String>, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, ChangelogError> {
+    require_admin(&state, &claims).await?;
+
+    let version = parse_version(&path)?;
+    if state.db.changelog().delete(&DeleteChangelogDraft { version: version.clone() }).await? {
+        return Ok(HttpResponse::Ok().into());
+    }
+
+    match state.db.changelog().select(&SelectChangelog { version }).await? {
+        Some(_) => Err(ChangelogError::AlreadyPublished),
+        None => Err(ChangelogError::NotFound)
+    }
+}
+
+// imports the versions of `./changelog` the database does not know yet as published changelogs
+pub async fn seed_changelogs(db: &dyn DatabaseContext) -> Result<usize, ChangelogError> {
+    let Ok(files) = changelog_files() else {
+        return Ok(0);
+    };
+
+    let mut seeded = 0;
+    for (version, file_name) in files {
+        if db.changelog().select(&SelectChangelog { version: version.clone() }).await?.is_some() {
+            continue;
+        }
+
+        let content = std::fs::read_to_string(format!("{}/{}", CHANGELOG_DIRECTORY, file_name))
+            .map_err(|_| ChangelogError::Internal)?;
+        db.changelog().insert(ChangelogEntry::published(ChangelogVersion::parse(version, &content))).await?;
+        seeded += 1;
+    }
+
+    Ok(seeded)
+}
+
+async fn require_admin(state: &AppState, claims: &TokenClaims) -> Result<(), ChangelogError> {
+    let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+
+    match requesting_user.is_admin() {
+        true => Ok(()),
+        false => Err(ChangelogError::Unauthorized)
+    }
 }
 
-fn get_highest_version() -> Result<Version, GETError> {
-    let changelog_path = "./changelog";
-    let latest_version = read_files_in_directory(changelog_path, false)?
+fn parse_version(version: &str) -> Result<Version, ChangelogError> {
+    Version::from_str(version).map_err(|_| ChangelogError::InvalidVersion)
+}
+
+async fn published_changelogs(state: &AppState) -> Result<Vec<ChangelogVersion>, GETError> {
+    let changelogs = state.db.changelog().select(&SelectChangelogs { status: Some(ChangelogStatus::Published) }).await?;
+
+    Ok(changelogs.into_iter().map(ChangelogVersion::from).collect())
+}
+
+async fn newest_changelog(state: &AppState) -> Result<ChangelogVersion, GETError> {
+    published_changelogs(state).await?
+        .into_iter()
+        .next()
+        .ok_or(GETError::CantRead)
+}
+
+// the version with the name of its file, `Version 1.2` is 1.2.0
+fn changelog_files() -> Result<Vec<(Version, String)>, std::io::Error> {
+    Ok(read_files_in_directory(CHANGELOG_DIRECTORY, false)?
         .iter()
         .map(|dir| dir.file_name().into_string())
         .filter_map(|f| f.ok())
-        .map(|file_name| Version::from_str(&file_name))
-        .filter_map(|f| f.ok())
-        .max();
-
-    if let Some(last) = latest_version {
-        Ok(last.clone())
-    } else {
-        Err(GETError::CantRead)
-    }
+        .filter_map(|file_name| Version::from_str(&file_name).ok().map(|version| (version, file_name)))
+        .collect())
 }
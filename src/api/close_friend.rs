@@ -0,0 +1,65 @@
+use actix_web::{delete, get, HttpResponse, post};
+use actix_web::web::{Data, Json, Path};
+use crate::api::shared::GETError;
+use crate::api::user::to_friend_list;
+use crate::middleware::TokenClaims;
+use crate::model::close_friend::{CloseFriend, DeleteCloseFriend};
+use crate::model::friend::{Friend, FriendRequestError};
+use crate::model::friendship::{FriendshipStatus, SelectFriendshipBetween};
+use crate::model::states::app_state::AppState;
+use crate::model::user::{SelectUserById, SelectUserByName, User};
+
+#[get("/{user_name}/closeFriends")]
+pub async fn close_friends(user_name: Path<String>, state: Data<AppState>, claims: TokenClaims) -> Result<Json<Vec<Friend>>, GETError> {
+    let user = state.db.user().select(&SelectUserByName { username: &user_name.into_inner() }).await?;
+    let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+
+    if user.id != claims.id && !requesting_user.is_admin() {
+        return Err(GETError::Unauthorized);
+    }
+
+    let ids = state.db.close_friend().select(&SelectUserById { id: user.id })
+        .await?
+        .into_iter()
+        .map(|close_friend| close_friend.friend)
+        .collect::<Vec<_>>();
+
+    Ok(Json(to_friend_list(ids, &state).await?))
+}
+
+#[post("/{user_name}/closeFriends/{friend_name}")]
+pub async fn add_close_friend(users: Path<(String, String)>, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, FriendRequestError> {
+    let (user, friend) = select_close_friend_users(users.into_inner(), &state, &claims).await?;
+
+    let friendship = state.db.friendship().select(&SelectFriendshipBetween { user_a: user.id, user_b: friend.id }).await?;
+    if !matches!(friendship, Some(friendship) if friendship.status == FriendshipStatus::Accepted) {
+        return Err(FriendRequestError::NotFriends);
+    }
+
+    state.db.close_friend().insert(CloseFriend::new(user.id, friend.id)).await?;
+
+    Ok(HttpResponse::Ok().into())
+}
+
+#[delete("/{user_name}/closeFriends/{friend_name}")]
+pub async fn remove_close_friend(users: Path<(String, String)>, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, FriendRequestError> {
+    let (user, friend) = select_close_friend_users(users.into_inner(), &state, &claims).await?;
+
+    if !state.db.close_friend().delete(&DeleteCloseFriend { owner: user.id, friend: friend.id }).await? {
+        return Err(FriendRequestError::NotFriends);
+    }
+
+    Ok(HttpResponse::Ok().into())
+}
+
+async fn select_close_friend_users(users: (String, String), state: &Data<AppState>, claims: &TokenClaims) -> Result<(User, User), FriendRequestError> {
+    let user = state.db.user().select(&SelectUserByName { username: &users.0 }).await?;
+    let friend = state.db.user().select(&SelectUserByName { username: &users.1 }).await?;
+    let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+
+    if user.id != claims.id && !requesting_user.is_admin() {
+        return Err(FriendRequestError::Unauthorized);
+    }
+
+    Ok((user, friend))
+}
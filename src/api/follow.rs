@@ -0,0 +1,79 @@
+use actix_web::{delete, get, HttpResponse, post};
+use actix_web::web::{Data, Json, Path};
+use crate::api::shared::GETError;
+use crate::api::shared::access::is_blocked;
+use crate::api::user::to_friend_list;
+use crate::middleware::TokenClaims;
+use crate::model::follow::{DeleteFollow, Follow, FollowDirection, FollowError, SelectFollows};
+use crate::model::friend::Friend;
+use crate::model::states::app_state::AppState;
+use crate::model::user::{SelectUserById, SelectUserByName, User};
+
+#[post("/{user_a}/follow/{user_b}")]
+pub async fn follow(users: Path<(String, String)>, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, FollowError> {
+    let (follower, followee) = select_follow_users(users.into_inner(), &state, &claims).await?;
+
+    if !followee.is_followable() || follower.id == followee.id || is_blocked(&state, &follower, &followee).await? {
+        return Err(FollowError::NotFollowable);
+    }
+
+    if !state.db.follow().insert(Follow::new(follower.id, followee.id)).await? {
+        return Err(FollowError::AlreadyFollowing);
+    }
+
+    Ok(HttpResponse::Ok().into())
+}
+
+#[delete("/{user_a}/follow/{user_b}")]
+pub async fn unfollow(users: Path<(String, String)>, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, FollowError> {
+    let (follower, followee) = select_follow_users(users.into_inner(), &state, &claims).await?;
+
+    if !state.db.follow().delete(&DeleteFollow { follower: follower.id, followee: followee.id }).await? {
+        return Err(FollowError::NotFollowing);
+    }
+
+    Ok(HttpResponse::Ok().into())
+}
+
+#[get("/{user_name}/followers")]
+pub async fn followers(user_name: Path<String>, state: Data<AppState>, claims: TokenClaims) -> Result<Json<Vec<Friend>>, GETError> {
+    follows(&user_name.into_inner(), FollowDirection::Followers, &state, &claims).await
+}
+
+#[get("/{user_name}/following")]
+pub async fn following(user_name: Path<String>, state: Data<AppState>, claims: TokenClaims) -> Result<Json<Vec<Friend>>, GETError> {
+    follows(&user_name.into_inner(), FollowDirection::Following, &state, &claims).await
+}
+
+async fn follows(user_name: &str, direction: FollowDirection, state: &Data<AppState>, claims: &TokenClaims) -> Result<Json<Vec<Friend>>, GETError> {
+    let user = state.db.user().select(&SelectUserByName { username: user_name }).await?;
+    let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+
+    if user.id != claims.id && !requesting_user.is_admin() {
+        return Err(GETError::Unauthorized);
+    }
+
+    let ids = state.db.follow().select(&SelectFollows { id: user.id, direction })
+        .await?
+        .into_iter()
+        .map(|entry| match direction {
+            FollowDirection::Followers => entry.follower,
+            FollowDirection::Following => entry.followee
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Json(to_friend_list(ids, state).await?))
+}
+
+// user_a is the following user, it must be the requesting one or the requesting one is an admin
+async fn select_follow_users(users: (String, String), state: &Data<AppState>, claims: &TokenClaims) -> Result<(User, User), FollowError> {
+    let user_a = state.db.user().select(&SelectUserByName { username: &users.0 }).await?;
+    let user_b = state.db.user().select(&SelectUserByName { username: &users.1 }).await?;
+    let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+
+    if user_a.id != claims.id && !requesting_user.is_admin() {
+        return Err(FollowError::Unauthorized);
+    }
+
+    Ok((user_a, user_b))
+}
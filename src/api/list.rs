@@ -0,0 +1,702 @@
+use std::collections::HashMap;
+use actix_web::{delete, get, HttpRequest, HttpResponse, post, put};
+use chrono::{Duration, Utc};
+use actix_web::http::header;
+use actix_web::web::{Data, Json, Path, Query};
+use mongodb::bson::oid::ObjectId;
+use serde::Deserialize;
+use crate::api::shared::access::media_access;
+use crate::middleware::TokenClaims;
+use crate::model::friendship::{FriendshipStatus, SelectFriendshipBetween};
+use crate::model::list::{default_list_name, numbered, DeleteListById, DueEntry, EntryChanges, DeleteListEntry, DeleteSubEntry, InsertListEntry, InsertSubEntry, List, ListContent, ListEntry, ListError, ListInformation, ListPermission, ListShare, ListShareInformation, ListSummary, ListTarget, ReplaceListEntries, ReplaceListShares, SelectListById, SelectListByOwner, SelectListsByOwner, SelectSharedLists, SharedListSummary, SubEntry, UpdateListDetails, UpdateListEntry, UpdateSubEntry};
+use crate::model::list_revision::{DeleteRevisions, DeleteRevisionsBefore, ListRevision, SelectRevision};
+use crate::model::states::app_state::AppState;
+use crate::model::user::{SelectUserById, SelectUserByName, SelectUsersByIds, User};
+use crate::utils::list_format;
+use crate::utils::list_format::ListFormat;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateList {
+    name: String
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateList {
+    name: Option<String>,
+    archived: Option<bool>
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListsQuery {
+    // archived lists are only listed, when asked for
+    archived: Option<bool>
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateEntry {
+    title: String,
+    #[serde(flatten)]
+    details: EntryChanges,
+    // appended without a position
+    position: Option<usize>
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateEntry {
+    #[serde(flatten)]
+    changes: EntryChanges,
+    // moves the entry
+    position: Option<usize>
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateSubEntry {
+    title: String
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubEntryChanges {
+    title: Option<String>,
+    checked: Option<bool>
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DueQuery {
+    // entries due within this many days, overdue ones are always included
+    days: Option<u32>
+}
+
+const DEFAULT_DUE_DAYS: u32 = 7;
+// older revisions of a list are removed
+const KEPT_REVISIONS: u64 = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct FormatQuery {
+    format: ListFormat
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportQuery {
+    format: ListFormat,
+    // replaces all entries instead of adding the imported ones
+    replace: Option<bool>
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EntryOrder {
+    ids: Vec<String>
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShareList {
+    permission: ListPermission
+}
+
+#[get("{user_name}/lists")]
+pub async fn lists(user_name: Path<String>, query: Query<ListsQuery>, state: Data<AppState>, claims: TokenClaims) -> Result<Json<Vec<ListSummary>>, ListError> {
+    let user = readable_user(&user_name.into_inner(), &state, &claims).await?;
+    let archived = query.archived.unwrap_or(false);
+    let is_admin = is_admin(&state, &claims).await?;
+
+    Ok(Json(state.db.list().select(&SelectListsByOwner { owner: user.id })
+        .await?
+        .iter()
+        .filter(|list| list.archived == archived && is_readable(list, &claims, is_admin))
+        .map(ListSummary::from)
+        .collect()))
+}
+
+#[post("{user_name}/lists")]
+pub async fn create_list(user_name: Path<String>, body: Json<CreateList>, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, ListError> {
+    let user = writable_user(&user_name.into_inner(), &state, &claims).await?;
+    let name = valid_name(body.into_inner().name)?;
+
+    let list = state.db.list().insert(List::new(user.id, name, vec![])).await?;
+    record_revision(&state, claims.id, &list).await;
+
+    Ok(list_response(list))
+}
+
+#[get("{user_name}/lists/{list_id}")]
+pub async fn get_list_by_id(path: Path<(String, String)>, request: HttpRequest, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, ListError> {
+    let (user_name, list_id) = path.into_inner();
+    let user = readable_user(&user_name, &state, &claims).await?;
+    let list = select_list_by_id(&user, &list_id, &state).await?;
+    readable(&list, &state, &claims).await?;
+
+    negotiated_response(list, &request)
+}
+
+// renames or (un)archives a list
+#[put("{user_name}/lists/{list_id}")]
+pub async fn update_list(path: Path<(String, String)>, body: Json<UpdateList>, request: HttpRequest, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, ListError> {
+    let (user_name, list_id) = path.into_inner();
+    let list = writable_list(&user_name, &list_id, &state, &claims).await?;
+    let target = target(&list, &request)?;
+    let body = body.into_inner();
+    let name = body.name.map(valid_name).transpose()?;
+
+    let result = state.db.list().update(&UpdateListDetails { target, name, archived: body.archived }).await?;
+    updated(&state, claims.id, &list, target, result).await
+}
+
+#[delete("{user_name}/lists/{list_id}")]
+pub async fn delete_list(path: Path<(String, String)>, request: HttpRequest, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, ListError> {
+    let (user_name, list_id) = path.into_inner();
+    let list = writable_list(&user_name, &list_id, &state, &claims).await?;
+    let target = target(&list, &request)?;
+
+    if !state.db.list().delete(&DeleteListById { target }).await? {
+        // the list still exists, so it has another version
+        return match state.db.list().select(&SelectListById { id: list.id, owner: list.owner }).await? {
+            Some(_) => Err(ListError::VersionMismatch),
+            None => Err(ListError::ListNotFound)
+        };
+    }
+    state.list_sync.publish_deletion(&list.id);
+    if let Err(err) = state.db.list_revision().delete(&DeleteRevisions { list_id: list.id }).await {
+        log::warn!("Revisions of list {} not removed: {}", list.id, err);
+    }
+
+    Ok(HttpResponse::Ok().into())
+}
+
+// the oldest list of the user, from the time a user had a single list
+#[get("{user_name}/list")]
+pub async fn get_list(user_name: Path<String>, request: HttpRequest, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, ListError> {
+    let user = readable_user(&user_name.into_inner(), &state, &claims).await?;
+    let list = select_list(&user, &state, &claims).await?;
+    readable(&list, &state, &claims).await?;
+
+    negotiated_response(list, &request)
+}
+
+#[get("{user_name}/lists/{list_id}/export")]
+pub async fn export_list(path: Path<(String, String)>, query: Query<FormatQuery>, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, ListError> {
+    let (user_name, list_id) = path.into_inner();
+    let user = readable_user(&user_name, &state, &claims).await?;
+    let list = select_list_by_id(&user, &list_id, &state).await?;
+    readable(&list, &state, &claims).await?;
+
+    formatted_response(&list, query.format)
+}
+
+// adds the entries of a Markdown task list or CSV to the list, or replaces all entries with them
+#[post("{user_name}/lists/{list_id}/import")]
+pub async fn import_list(path: Path<(String, String)>, query: Query<ImportQuery>, body: String, request: HttpRequest, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, ListError> {
+    let (user_name, list_id) = path.into_inner();
+    let list = editable_list(&user_name, &list_id, &state, &claims).await?;
+    let target = required_target(&list, &request)?;
+
+    let mut imported = list_format::parse(&body, query.format)?;
+    if imported.is_empty() {
+        return Err(ListError::InvalidImport);
+    }
+    // assignees of other lists are not necessarily friends of this owner
+    for entry in imported.iter_mut() {
+        let assignee = entry.assignee.take();
+        if valid_assignee(&list, &Some(assignee.clone()), &state).await.is_ok() {
+            entry.assignee = assignee;
+        }
+    }
+
+    let mut entries = if query.replace.unwrap_or(false) { vec![] } else { list.entries.clone() };
+    entries.extend(imported);
+
+    let result = state.db.list().update(&ReplaceListEntries { target, entries }).await?;
+    updated(&state, claims.id, &list, target, result).await
+}
+
+// replaces all entries of the oldest list, entries without an id get a new one
+#[put("{user_name}/list")]
+pub async fn put_list(user_name: Path<String>, body: Json<ListContent>, request: HttpRequest, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, ListError> {
+    let user = readable_user(&user_name.into_inner(), &state, &claims).await?;
+    let list = select_list(&user, &state, &claims).await?;
+    editable(&list, &state, &claims).await?;
+    let target = required_target(&list, &request)?;
+
+    let result = state.db.list().update(&ReplaceListEntries { target, entries: body.into_inner().entries }).await?;
+    updated(&state, claims.id, &list, target, result).await
+}
+
+#[post("{user_name}/lists/{list_id}/entries")]
+pub async fn add_entry(path: Path<(String, String)>, body: Json<CreateEntry>, request: HttpRequest, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, ListError> {
+    let (user_name, list_id) = path.into_inner();
+    let list = editable_list(&user_name, &list_id, &state, &claims).await?;
+    let target = target(&list, &request)?;
+    let body = body.into_inner();
+    valid_assignee(&list, &body.details.assignee, &state).await?;
+
+    let mut entry = ListEntry::new(body.title);
+    body.details.apply(&mut entry);
+
+    let result = state.db.list().update(&InsertListEntry { target, entry, position: body.position }).await?;
+    updated(&state, claims.id, &list, target, result).await
+}
+
+#[put("{user_name}/lists/{list_id}/entries/{entry_id}")]
+pub async fn update_entry(path: Path<(String, String, String)>, body: Json<UpdateEntry>, request: HttpRequest, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, ListError> {
+    let (user_name, list_id, entry_id) = path.into_inner();
+    let list = editable_list(&user_name, &list_id, &state, &claims).await?;
+    let body = body.into_inner();
+    valid_assignee(&list, &body.changes.assignee, &state).await?;
+
+    let Some(position) = body.position else {
+        let target = target(&list, &request)?;
+        let result = state.db.list().update(&UpdateListEntry { target, entry_id, changes: body.changes }).await?;
+        return updated(&state, claims.id, &list, target, result).await;
+    };
+
+    // moving rewrites the whole list
+    let target = loaded_target(&list, &request)?;
+    let mut entries = list.entries.clone();
+    let index = entries.iter()
+        .position(|entry| entry.id == entry_id)
+        .ok_or(ListError::EntryNotFound)?;
+    let mut entry = entries.remove(index);
+    body.changes.apply(&mut entry);
+    entries.insert(position.min(entries.len()), entry);
+
+    let result = state.db.list().update(&ReplaceListEntries { target, entries }).await?;
+    updated(&state, claims.id, &list, target, result).await
+}
+
+#[delete("{user_name}/lists/{list_id}/entries/{entry_id}")]
+pub async fn delete_entry(path: Path<(String, String, String)>, request: HttpRequest, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, ListError> {
+    let (user_name, list_id, entry_id) = path.into_inner();
+    let list = editable_list(&user_name, &list_id, &state, &claims).await?;
+    let target = target(&list, &request)?;
+
+    let result = state.db.list().update(&DeleteListEntry { target, entry_id }).await?;
+    updated(&state, claims.id, &list, target, result).await
+}
+
+#[put("{user_name}/lists/{list_id}/order")]
+pub async fn reorder_entries(path: Path<(String, String)>, body: Json<EntryOrder>, request: HttpRequest, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, ListError> {
+    let (user_name, list_id) = path.into_inner();
+    let list = editable_list(&user_name, &list_id, &state, &claims).await?;
+    let target = loaded_target(&list, &request)?;
+    let entries = reorder(list.entries.clone(), &body.ids, |entry| &entry.id)?;
+
+    let result = state.db.list().update(&ReplaceListEntries { target, entries }).await?;
+    updated(&state, claims.id, &list, target, result).await
+}
+
+#[post("{user_name}/lists/{list_id}/entries/{entry_id}/subEntries")]
+pub async fn add_sub_entry(path: Path<(String, String, String)>, body: Json<CreateSubEntry>, request: HttpRequest, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, ListError> {
+    let (user_name, list_id, entry_id) = path.into_inner();
+    let list = editable_list(&user_name, &list_id, &state, &claims).await?;
+    let target = target(&list, &request)?;
+
+    let result = state.db.list().update(&InsertSubEntry { target, entry_id, sub_entry: SubEntry::new(body.into_inner().title) }).await?;
+    updated(&state, claims.id, &list, target, result).await
+}
+
+#[put("{user_name}/lists/{list_id}/entries/{entry_id}/subEntries/{sub_entry_id}")]
+pub async fn update_sub_entry(path: Path<(String, String, String, String)>, body: Json<SubEntryChanges>, request: HttpRequest, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, ListError> {
+    let (user_name, list_id, entry_id, sub_entry_id) = path.into_inner();
+    let list = editable_list(&user_name, &list_id, &state, &claims).await?;
+    let target = target(&list, &request)?;
+    let body = body.into_inner();
+
+    let result = state.db.list().update(&UpdateSubEntry { target, entry_id, sub_entry_id, title: body.title, checked: body.checked }).await?;
+    updated(&state, claims.id, &list, target, result).await
+}
+
+#[delete("{user_name}/lists/{list_id}/entries/{entry_id}/subEntries/{sub_entry_id}")]
+pub async fn delete_sub_entry(path: Path<(String, String, String, String)>, request: HttpRequest, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, ListError> {
+    let (user_name, list_id, entry_id, sub_entry_id) = path.into_inner();
+    let list = editable_list(&user_name, &list_id, &state, &claims).await?;
+    let target = target(&list, &request)?;
+
+    let result = state.db.list().update(&DeleteSubEntry { target, entry_id, sub_entry_id }).await?;
+    updated(&state, claims.id, &list, target, result).await
+}
+
+#[put("{user_name}/lists/{list_id}/entries/{entry_id}/order")]
+pub async fn reorder_sub_entries(path: Path<(String, String, String)>, body: Json<EntryOrder>, request: HttpRequest, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, ListError> {
+    let (user_name, list_id, entry_id) = path.into_inner();
+    let list = editable_list(&user_name, &list_id, &state, &claims).await?;
+    let target = loaded_target(&list, &request)?;
+
+    let mut entries = list.entries.clone();
+    let entry = entries.iter_mut()
+        .find(|entry| entry.id == entry_id)
+        .ok_or(ListError::EntryNotFound)?;
+    entry.sub_entries = reorder(std::mem::take(&mut entry.sub_entries), &body.ids, |sub_entry| &sub_entry.id)?;
+
+    let result = state.db.list().update(&ReplaceListEntries { target, entries }).await?;
+    updated(&state, claims.id, &list, target, result).await
+}
+
+// unchecked entries of the lists of `user_name`, which are due within `days` or overdue. The earliest first
+#[get("{user_name}/dueEntries")]
+pub async fn due_entries(user_name: Path<String>, query: Query<DueQuery>, state: Data<AppState>, claims: TokenClaims) -> Result<Json<Vec<DueEntry>>, ListError> {
+    let user = readable_user(&user_name.into_inner(), &state, &claims).await?;
+    let until = Utc::now() + Duration::days(i64::from(query.days.unwrap_or(DEFAULT_DUE_DAYS)));
+    let is_admin = is_admin(&state, &claims).await?;
+
+    let mut due_entries = vec![];
+    for list in state.db.list().select(&SelectListsByOwner { owner: user.id }).await? {
+        if list.archived || !is_readable(&list, &claims, is_admin) {
+            continue;
+        }
+
+        let list_id = list.id.to_hex();
+        due_entries.extend(numbered(list.entries)
+            .into_iter()
+            .filter(|entry| !entry.checked && matches!(entry.due, Some(due) if due <= until))
+            .map(|entry| DueEntry { list_id: list_id.clone(), list_name: list.name.clone(), entry }));
+    }
+    due_entries.sort_by_key(|due_entry| due_entry.entry.due);
+
+    Ok(Json(due_entries))
+}
+
+#[get("{user_name}/lists/{list_id}/shares")]
+pub async fn list_shares(path: Path<(String, String)>, state: Data<AppState>, claims: TokenClaims) -> Result<Json<Vec<ListShareInformation>>, ListError> {
+    let (user_name, list_id) = path.into_inner();
+    let list = writable_list(&user_name, &list_id, &state, &claims).await?;
+
+    Ok(Json(share_information(list.shares, &state).await?))
+}
+
+// shares the list with a friend or changes the permission of an existing share
+#[put("{user_name}/lists/{list_id}/shares/{friend_name}")]
+pub async fn share_list(path: Path<(String, String, String)>, body: Json<ShareList>, request: HttpRequest, state: Data<AppState>, claims: TokenClaims) -> Result<Json<Vec<ListShareInformation>>, ListError> {
+    let (user_name, list_id, friend_name) = path.into_inner();
+    let list = writable_list(&user_name, &list_id, &state, &claims).await?;
+    let friend = state.db.user().select(&SelectUserByName { username: &friend_name }).await?;
+
+    let friendship = state.db.friendship().select(&SelectFriendshipBetween { user_a: list.owner, user_b: friend.id }).await?;
+    if !matches!(friendship, Some(friendship) if friendship.status == FriendshipStatus::Accepted) {
+        return Err(ListError::NotFriends);
+    }
+
+    let mut shares = list.shares.iter()
+        .filter(|share| share.user != friend.id)
+        .cloned()
+        .collect::<Vec<_>>();
+    shares.push(ListShare { user: friend.id, permission: body.permission });
+
+    replace_shares(&list, shares, &request, &state, &claims).await
+}
+
+#[delete("{user_name}/lists/{list_id}/shares/{friend_name}")]
+pub async fn unshare_list(path: Path<(String, String, String)>, request: HttpRequest, state: Data<AppState>, claims: TokenClaims) -> Result<Json<Vec<ListShareInformation>>, ListError> {
+    let (user_name, list_id, friend_name) = path.into_inner();
+    let list = writable_list(&user_name, &list_id, &state, &claims).await?;
+    let friend = state.db.user().select(&SelectUserByName { username: &friend_name }).await?;
+
+    if list.share_of(&friend.id).is_none() {
+        return Err(ListError::NotShared);
+    }
+
+    let shares = list.shares.iter()
+        .filter(|share| share.user != friend.id)
+        .cloned()
+        .collect();
+
+    replace_shares(&list, shares, &request, &state, &claims).await
+}
+
+// lists of other users shared with `user_name`. Shares of former friends are left out
+#[get("{user_name}/sharedLists")]
+pub async fn shared_lists(user_name: Path<String>, state: Data<AppState>, claims: TokenClaims) -> Result<Json<Vec<SharedListSummary>>, ListError> {
+    let user = writable_user(&user_name.into_inner(), &state, &claims).await?;
+    let shared = state.db.list().select(&SelectSharedLists { user: user.id }).await?;
+
+    let owner_ids = shared.iter().map(|list| list.owner).collect();
+    let owners = state.db.user().select(&SelectUsersByIds { ids: owner_ids })
+        .await?
+        .into_iter()
+        .map(|owner| (owner.id, owner))
+        .collect::<HashMap<_, _>>();
+
+    let mut summaries = vec![];
+    for list in shared {
+        let (Some(owner), Some(permission)) = (owners.get(&list.owner), list.share_of(&user.id)) else {
+            continue;
+        };
+
+        if media_access(&state, owner, &user).await?.is_friend() {
+            summaries.push(SharedListSummary { owner: owner.name.clone(), permission, list: ListSummary::from(&list) });
+        }
+    }
+
+    Ok(Json(summaries))
+}
+
+// lists are never public, followers have no access. Friends also need a share of the list, see `readable`
+pub async fn readable_user(user_name: &str, state: &Data<AppState>, claims: &TokenClaims) -> Result<User, ListError> {
+    let user = state.db.user().select(&SelectUserByName { username: user_name }).await?;
+    let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+
+    if !media_access(state, &user, &requesting_user).await?.is_friend() {
+        return Err(ListError::Unauthorized);
+    }
+
+    Ok(user)
+}
+
+// only the owner and admins can create, rename, archive, delete and share lists
+async fn writable_user(user_name: &str, state: &Data<AppState>, claims: &TokenClaims) -> Result<User, ListError> {
+    let user = state.db.user().select(&SelectUserByName { username: user_name }).await?;
+    let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+
+    if user.id != claims.id && !requesting_user.is_admin() {
+        return Err(ListError::Unauthorized);
+    }
+
+    Ok(user)
+}
+
+async fn writable_list(user_name: &str, list_id: &str, state: &Data<AppState>, claims: &TokenClaims) -> Result<List, ListError> {
+    let user = writable_user(user_name, state, claims).await?;
+
+    select_list_by_id(&user, list_id, state).await
+}
+
+// the entries can also be changed by friends the list is shared with for editing
+pub async fn editable_list(user_name: &str, list_id: &str, state: &Data<AppState>, claims: &TokenClaims) -> Result<List, ListError> {
+    let user = readable_user(user_name, state, claims).await?;
+    let list = select_list_by_id(&user, list_id, state).await?;
+    editable(&list, state, claims).await?;
+
+    Ok(list)
+}
+
+// the owner, admins and friends the list is shared with. Expects the requesting user to be a friend of the owner
+pub async fn readable(list: &List, state: &Data<AppState>, claims: &TokenClaims) -> Result<(), ListError> {
+    if is_readable(list, claims, false) || is_admin(state, claims).await? {
+        return Ok(());
+    }
+
+    Err(ListError::Unauthorized)
+}
+
+fn is_readable(list: &List, claims: &TokenClaims, is_admin: bool) -> bool {
+    is_admin || list.owner == claims.id || list.share_of(&claims.id).is_some()
+}
+
+async fn is_admin(state: &Data<AppState>, claims: &TokenClaims) -> Result<bool, ListError> {
+    Ok(state.db.user().select(&SelectUserById { id: claims.id }).await?.is_admin())
+}
+
+// expects the requesting user to be a friend of the owner
+async fn editable(list: &List, state: &Data<AppState>, claims: &TokenClaims) -> Result<(), ListError> {
+    if list.owner == claims.id || list.share_of(&claims.id) == Some(ListPermission::Edit) {
+        return Ok(());
+    }
+
+    if !is_admin(state, claims).await? {
+        return Err(ListError::Unauthorized);
+    }
+
+    Ok(())
+}
+
+pub async fn select_list_by_id(user: &User, list_id: &str, state: &Data<AppState>) -> Result<List, ListError> {
+    let id = ObjectId::parse_str(list_id).map_err(|_| ListError::ListNotFound)?;
+
+    state.db.list().select(&SelectListById { id, owner: user.id })
+        .await?
+        .ok_or(ListError::ListNotFound)
+}
+
+// every user has at least one list, it is created on first access
+// only created for the owner, anyone else reading it must not write it and could not read it anyway,
+// since a list that does not exist is not shared
+async fn select_list(user: &User, state: &Data<AppState>, claims: &TokenClaims) -> Result<List, ListError> {
+    match state.db.list().select(&SelectListByOwner { owner: user.id }).await? {
+        Some(list) => Ok(list),
+        None if user.id != claims.id => Err(ListError::ListNotFound),
+        None => {
+            let list = state.db.list().insert(List::new(user.id, default_list_name(), vec![])).await?;
+            record_revision(state, user.id, &list).await;
+
+            Ok(list)
+        }
+    }
+}
+
+async fn replace_shares(list: &List, shares: Vec<ListShare>, request: &HttpRequest, state: &Data<AppState>, claims: &TokenClaims) -> Result<Json<Vec<ListShareInformation>>, ListError> {
+    let target = loaded_target(list, request)?;
+
+    match state.db.list().update(&ReplaceListShares { target, shares }).await? {
+        Some(after) => {
+            changed(state, claims.id, list, &after).await;
+            Ok(Json(share_information(after.shares, state).await?))
+        },
+        None => Err(ListError::VersionMismatch)
+    }
+}
+
+async fn share_information(shares: Vec<ListShare>, state: &Data<AppState>) -> Result<Vec<ListShareInformation>, ListError> {
+    let ids = shares.iter().map(|share| share.user).collect();
+    let names = state.db.user().select(&SelectUsersByIds { ids })
+        .await?
+        .into_iter()
+        .map(|user| (user.id, user.name))
+        .collect::<HashMap<_, _>>();
+
+    Ok(shares.into_iter()
+        .filter_map(|share| names.get(&share.user).map(|user_name| ListShareInformation { user_name: user_name.clone(), permission: share.permission }))
+        .collect())
+}
+
+// the version of the list the client has seen, `*` matches every version
+fn if_match(request: &HttpRequest) -> Result<Option<u64>, ListError> {
+    let Some(value) = request.headers().get(header::IF_MATCH) else {
+        return Ok(None);
+    };
+
+    let value = value.to_str().map_err(|_| ListError::VersionMismatch)?.trim();
+    if value == "*" {
+        return Ok(None);
+    }
+
+    value.trim_start_matches("W/")
+        .trim_matches('"')
+        .parse()
+        .map(Some)
+        .map_err(|_| ListError::VersionMismatch)
+}
+
+// without If-Match the change is applied to whatever version the list has
+fn target(list: &List, request: &HttpRequest) -> Result<ListTarget, ListError> {
+    Ok(ListTarget { id: list.id, expected_version: if_match(request)? })
+}
+
+// for changes computed from the loaded list, which must not overwrite changes made since loading it
+pub fn loaded_target(list: &List, request: &HttpRequest) -> Result<ListTarget, ListError> {
+    Ok(ListTarget { id: list.id, expected_version: Some(if_match(request)?.unwrap_or(list.version)) })
+}
+
+// for changes replacing every entry, the client has to name the version it replaces
+fn required_target(list: &List, request: &HttpRequest) -> Result<ListTarget, ListError> {
+    if !request.headers().contains_key(header::IF_MATCH) {
+        return Err(ListError::VersionRequired);
+    }
+
+    loaded_target(list, request)
+}
+
+// Markdown or CSV, if the client accepts one of them instead of JSON
+fn negotiated_response(list: List, request: &HttpRequest) -> Result<HttpResponse, ListError> {
+    let format = request.headers()
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .and_then(ListFormat::from_accept);
+
+    match format {
+        Some(format) => formatted_response(&list, format),
+        None => Ok(list_response(list))
+    }
+}
+
+fn formatted_response(list: &List, format: ListFormat) -> Result<HttpResponse, ListError> {
+    Ok(HttpResponse::Ok()
+        .insert_header((header::ETAG, format!("\"{}\"", list.version)))
+        .content_type(format.content_type())
+        .body(list_format::render(list, format)?))
+}
+
+pub fn list_response(list: List) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header((header::ETAG, format!("\"{}\"", list.version)))
+        .json(ListInformation::from(list))
+}
+
+// the owner of the list or one of its friends
+async fn valid_assignee(list: &List, assignee: &Option<Option<String>>, state: &Data<AppState>) -> Result<(), ListError> {
+    let Some(Some(assignee)) = assignee else {
+        return Ok(());
+    };
+
+    let id = ObjectId::parse_str(assignee).map_err(|_| ListError::InvalidAssignee)?;
+    if id == list.owner {
+        return Ok(());
+    }
+
+    let friendship = state.db.friendship().select(&SelectFriendshipBetween { user_a: list.owner, user_b: id }).await?;
+    if !matches!(friendship, Some(friendship) if friendship.status == FriendshipStatus::Accepted) {
+        return Err(ListError::InvalidAssignee);
+    }
+
+    Ok(())
+}
+
+fn valid_name(name: String) -> Result<String, ListError> {
+    let name = name.trim();
+
+    if name.is_empty() {
+        return Err(ListError::InvalidName);
+    }
+
+    Ok(name.to_string())
+}
+
+// `before` is the list the change was applied to
+pub async fn changed(state: &Data<AppState>, author: ObjectId, before: &List, after: &List) {
+    state.list_sync.publish_change(before, after);
+    record_previous_revision(state, before).await;
+    record_revision(state, author, after).await;
+}
+
+// lists from before revisions were recorded have none for their current version, without it their first change
+// could not be undone. The author of that version is unknown, so it is attributed to the owner
+async fn record_previous_revision(state: &Data<AppState>, before: &List) {
+    match state.db.list_revision().select(&SelectRevision { list_id: before.id, version: before.version }).await {
+        Ok(Some(_)) => {},
+        Ok(None) => record_revision(state, before.owner, before).await,
+        Err(err) => log::warn!("Revision {} of list {} not looked up: {}", before.version, before.id, err)
+    }
+}
+
+// the change itself is already stored, so a failing revision is only logged
+pub async fn record_revision(state: &Data<AppState>, author: ObjectId, list: &List) {
+    if let Err(err) = state.db.list_revision().insert(ListRevision::new(list, author)).await {
+        log::warn!("Revision {} of list {} not recorded: {}", list.version, list.id, err);
+        return;
+    }
+
+    // pruned every few versions instead of on every change
+    if list.version % 10 == 0 && list.version >= KEPT_REVISIONS {
+        let prune = DeleteRevisionsBefore { list_id: list.id, version: list.version - KEPT_REVISIONS + 1 };
+        if let Err(err) = state.db.list_revision().delete(&prune).await {
+            log::warn!("Old revisions of list {} not removed: {}", list.id, err);
+        }
+    }
+}
+
+// a change is not applied, if the list has another version than expected or the entry does not exist
+pub async fn updated(state: &Data<AppState>, author: ObjectId, list: &List, target: ListTarget, result: Option<List>) -> Result<HttpResponse, ListError> {
+    if let Some(after) = result {
+        changed(state, author, list, &after).await;
+        return Ok(list_response(after));
+    }
+
+    let current = state.db.list().select(&SelectListById { id: list.id, owner: list.owner })
+        .await?
+        .ok_or(ListError::ListNotFound)?;
+
+    match target.expected_version {
+        Some(version) if version != current.version => Err(ListError::VersionMismatch),
+        _ => Err(ListError::EntryNotFound)
+    }
+}
+
+fn reorder<T>(items: Vec<T>, ids: &[String], id_of: impl Fn(&T) -> &String) -> Result<Vec<T>, ListError> {
+    if items.len() != ids.len() {
+        return Err(ListError::InvalidOrder);
+    }
+
+    let mut by_id = items.into_iter()
+        .map(|item| (id_of(&item).clone(), item))
+        .collect::<HashMap<_, _>>();
+
+    ids.iter()
+        .map(|id| by_id.remove(id).ok_or(ListError::InvalidOrder))
+        .collect()
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use actix_web::{get, post, HttpRequest, HttpResponse};
+use actix_web::web::{Data, Json, Path, Query};
+use mongodb::bson::oid::ObjectId;
+use serde::Deserialize;
+use crate::api::list::{editable_list, loaded_target, readable, readable_user, select_list_by_id, updated};
+use crate::api::shared::QueryInfo;
+use crate::middleware::TokenClaims;
+use crate::model::list::{List, ListError, ReplaceListEntries};
+use crate::model::list_event::list_events;
+use crate::model::list_revision::{ListRevision, RevisionInformation, RevisionSummary, SelectPreviousRevision, SelectRevision, SelectRevisions};
+use crate::model::states::app_state::AppState;
+use crate::model::user::SelectUsersByIds;
+
+#[derive(Debug, Deserialize)]
+pub struct CompareQuery {
+    // the version the revision is compared to, the previous revision by default
+    compare: Option<u64>
+}
+
+// newest first
+#[get("{user_name}/lists/{list_id}/revisions")]
+pub async fn get_revisions(path: Path<(String, String)>, query: Query<QueryInfo>, state: Data<AppState>, claims: TokenClaims) -> Result<Json<Vec<RevisionSummary>>, ListError> {
+    let (user_name, list_id) = path.into_inner();
+    let user = readable_user(&user_name, &state, &claims).await?;
+    let list = select_list_by_id(&user, &list_id, &state).await?;
+    readable(&list, &state, &claims).await?;
+
+    let revisions = state.db.list_revision().select(&SelectRevisions { list_id: list.id, offset: query.offset.unwrap_or(0), limit: query.limit }).await?;
+    let authors = author_names(revisions.iter().map(|revision| revision.author).collect(), &state).await?;
+
+    Ok(Json(revisions.into_iter()
+        .map(|revision| RevisionSummary {
+            version: revision.version,
+            author: author_name(&authors, &revision.author),
+            created_at: revision.created_at,
+        })
+        .collect()))
+}
+
+// the list at `version` and the changes since the compared revision
+#[get("{user_name}/lists/{list_id}/revisions/{version}")]
+pub async fn get_revision(path: Path<(String, String, u64)>, query: Query<CompareQuery>, state: Data<AppState>, claims: TokenClaims) -> Result<Json<RevisionInformation>, ListError> {
+    let (user_name, list_id, version) = path.into_inner();
+    let user = readable_user(&user_name, &state, &claims).await?;
+    let list = select_list_by_id(&user, &list_id, &state).await?;
+    readable(&list, &state, &claims).await?;
+
+    let revision = select_revision(&list, version, &state).await?;
+    let compared = match query.compare {
+        Some(compare) => Some(select_revision(&list, compare, &state).await?),
+        None => state.db.list_revision().select(&SelectPreviousRevision { list_id: list.id, version }).await?
+    };
+
+    let after = revision.to_list(&list);
+    // without an older revision every entry counts as added
+    let before = match &compared {
+        Some(compared) => compared.to_list(&list),
+        None => List { entries: vec![], ..after.clone() }
+    };
+    let authors = author_names(vec![revision.author], &state).await?;
+
+    Ok(Json(RevisionInformation {
+        version: revision.version,
+        author: author_name(&authors, &revision.author),
+        created_at: revision.created_at,
+        compared_to: compared.map(|compared| compared.version),
+        events: list_events(&before, &after),
+        list: after.into(),
+    }))
+}
+
+// the entries of the revision replace the current ones, which creates a new revision
+#[post("{user_name}/lists/{list_id}/revisions/{version}/restore")]
+pub async fn restore_revision(path: Path<(String, String, u64)>, request: HttpRequest, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, ListError> {
+    let (user_name, list_id, version) = path.into_inner();
+    let list = editable_list(&user_name, &list_id, &state, &claims).await?;
+    let target = loaded_target(&list, &request)?;
+    let revision = select_revision(&list, version, &state).await?;
+
+    let result = state.db.list().update(&ReplaceListEntries { target, entries: revision.entries }).await?;
+    updated(&state, claims.id, &list, target, result).await
+}
+
+async fn select_revision(list: &List, version: u64, state: &Data<AppState>) -> Result<ListRevision, ListError> {
+    state.db.list_revision().select(&SelectRevision { list_id: list.id, version })
+        .await?
+        .ok_or(ListError::RevisionNotFound)
+}
+
+async fn author_names(ids: Vec<ObjectId>, state: &Data<AppState>) -> Result<HashMap<ObjectId, String>, ListError> {
+    Ok(state.db.user().select(&SelectUsersByIds { ids })
+        .await?
+        .into_iter()
+        .map(|user| (user.id, user.name))
+        .collect())
+}
+
+// authors can delete their account, their revisions stay
+fn author_name(authors: &HashMap<ObjectId, String>, id: &ObjectId) -> String {
+    authors.get(id).cloned().unwrap_or_default()
+}
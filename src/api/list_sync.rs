@@ -0,0 +1,157 @@
+use std::time::{Duration, Instant};
+use actix_web::{get, Error, HttpRequest, HttpResponse};
+use actix_web::web::{Data, Path, Payload, Query};
+use actix_ws::{Message, MessageStream, Session};
+use futures_util::StreamExt;
+use mongodb::bson::oid::ObjectId;
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Receiver;
+use crate::api::list::{readable, readable_user, select_list_by_id};
+use crate::middleware::TokenClaims;
+use crate::model::list::ListError;
+use crate::model::list_event::ListMessage;
+use crate::model::states::app_state::AppState;
+use crate::model::user::User;
+
+// how long an access decision is reused. Checking it for every change would multiply the load of a busy list by
+// its subscribers, so unfriending, blocking or unsharing ends a subscription within this time
+const ACCESS_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Deserialize)]
+pub struct SyncQuery {
+    // the version the client already has, after a reconnect it only gets the changes made since then
+    version: Option<u64>
+}
+
+// a connected client, which got every change up to `version`
+struct Subscription {
+    state: Data<AppState>,
+    claims: TokenClaims,
+    owner: User,
+    list_id: ObjectId,
+    version: u64,
+    access_checked: Instant
+}
+
+#[get("{user_name}/lists/{list_id}/sync")]
+pub async fn sync_list(path: Path<(String, String)>, query: Query<SyncQuery>, request: HttpRequest, body: Payload, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, Error> {
+    let (user_name, list_id) = path.into_inner();
+    let owner = readable_user(&user_name, &state, &claims).await?;
+    let list = select_list_by_id(&owner, &list_id, &state).await?;
+    readable(&list, &state, &claims).await?;
+
+    // subscribe before loading the version the client starts from, so no change gets lost in between
+    let messages = state.list_sync.subscribe(list.id);
+    let list = select_list_by_id(&owner, &list_id, &state).await?;
+
+    let backlog = match query.version {
+        Some(version) if version == list.version => vec![],
+        Some(version) => state.list_sync.changes_since(&list.id, version, list.version)
+            .unwrap_or_else(|| vec![ListMessage::Snapshot { list: list.clone().into() }]),
+        None => vec![ListMessage::Snapshot { list: list.clone().into() }]
+    };
+
+    let (response, session, stream) = actix_ws::handle(&request, body)?;
+    let subscription = Subscription { state, claims, owner, list_id: list.id, version: list.version, access_checked: Instant::now() };
+    actix_web::rt::spawn(run_subscription(subscription, backlog, session, stream, messages));
+
+    Ok(response)
+}
+
+async fn run_subscription(mut subscription: Subscription, backlog: Vec<ListMessage>, mut session: Session, mut stream: MessageStream, mut messages: Receiver<ListMessage>) {
+    for message in backlog {
+        if !send(&mut session, &message).await {
+            return;
+        }
+    }
+
+    loop {
+        tokio::select! {
+            message = messages.recv() => {
+                let message = match message {
+                    Ok(message) => subscription.next(message).await,
+                    // the client fell behind, it gets the whole list instead of the missed changes
+                    Err(RecvError::Lagged(_)) => subscription.snapshot().await,
+                    Err(RecvError::Closed) => Err(ListError::ListNotFound)
+                };
+
+                match message {
+                    Ok(Some(message)) => {
+                        let deleted = matches!(message, ListMessage::Deleted);
+                        if !send(&mut session, &message).await || deleted {
+                            break;
+                        }
+                    },
+                    Ok(None) => {},
+                    Err(_) => break
+                }
+            },
+            incoming = stream.next() => match incoming {
+                Some(Ok(Message::Ping(bytes))) => {
+                    if session.pong(&bytes).await.is_err() {
+                        return;
+                    }
+                },
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            }
+        }
+    }
+
+    let _ = session.close(None).await;
+}
+
+impl Subscription {
+    // the message for the client, None if it already has the change
+    async fn next(&mut self, message: ListMessage) -> Result<Option<ListMessage>, ListError> {
+        if matches!(message, ListMessage::Deleted) {
+            return Ok(Some(message));
+        }
+
+        if self.access_checked.elapsed() >= ACCESS_CHECK_INTERVAL {
+            self.check_access().await?;
+        }
+
+        match message {
+            ListMessage::Changed { version, .. } if version <= self.version => Ok(None),
+            ListMessage::Changed { version, .. } if version == self.version + 1 => {
+                self.version = version;
+                Ok(Some(message))
+            },
+            // a change the client has not got yet is missing
+            ListMessage::Changed { .. } => self.snapshot().await,
+            ListMessage::Snapshot { ref list } if list.version <= self.version => Ok(None),
+            ListMessage::Snapshot { ref list } => {
+                self.version = list.version;
+                Ok(Some(message))
+            },
+            ListMessage::Deleted => Ok(Some(message))
+        }
+    }
+
+    // unfriending, blocking or unsharing ends the access to the list
+    async fn check_access(&mut self) -> Result<(), ListError> {
+        readable_user(&self.owner.name, &self.state, &self.claims).await?;
+        let list = select_list_by_id(&self.owner, &self.list_id.to_hex(), &self.state).await?;
+        readable(&list, &self.state, &self.claims).await?;
+        self.access_checked = Instant::now();
+
+        Ok(())
+    }
+
+    async fn snapshot(&mut self) -> Result<Option<ListMessage>, ListError> {
+        let list = select_list_by_id(&self.owner, &self.list_id.to_hex(), &self.state).await?;
+        self.version = list.version;
+
+        Ok(Some(ListMessage::Snapshot { list: list.into() }))
+    }
+}
+
+// false if the client is gone
+async fn send(session: &mut Session, message: &ListMessage) -> bool {
+    match serde_json::to_string(message) {
+        Ok(text) => session.text(text).await.is_ok(),
+        Err(_) => false
+    }
+}
@@ -1,23 +1,23 @@
 use actix_files::NamedFile;
 use actix_multipart::Multipart;
 
-use actix_web::{get, HttpRequest, HttpResponse, post};
+use actix_web::{delete, get, HttpRequest, HttpResponse, post};
 use actix_web::web::{Data, Json, Path, Query};
 use serde::Deserialize;
 use uuid::Uuid;
-use crate::api::shared::{GETError, UploadError};
-use crate::database::repositories::SelectRepository;
+use crate::api::shared::{DeleteError, GETError, QueryInfo, UploadError};
+use crate::api::shared::access::{is_muted, media_access, MediaAccess, visible_files};
 use crate::middleware::TokenClaims;
+use crate::model::media::{DeleteMediaEntry, MediaEntry, MediaKind, Visibility};
 use crate::model::states::app_state::AppState;
-use crate::model::user::{SelectUserById, SelectUserByName};
+use crate::model::user::{SelectUserById, SelectUserByName, UpdatePostCount, User};
 use crate::utils::{read_files_in_directory, UploadOptions, validate_stories, write_files_in_directory};
 
 
 #[derive(Debug, Deserialize)]
-pub struct QueryInfo {
-    //pagination
-    pub limit: Option<usize>,
-    pub offset: Option<usize>,
+pub struct UploadQuery {
+    // friends, if not provided. Stories can be restricted to close_friends
+    pub visibility: Option<Visibility>
 }
 
 #[get("/{user_name}")]
@@ -27,28 +27,26 @@ pub async fn list(user_name: Path<String>, state: Data<AppState>, query: Query<Q
 
     let user = state.db.user().select(&SelectUserByName { username: &user_name }).await?;
     let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
-    let are_friends = state.db.friendship().select(&SelectUserById { id: claims.id })
-        .await?
-        .iter()
-        .filter(|friendship| friendship.friend_a == user.id || friendship.friend_b == user.id)
-        .count() > 0;
+    let access = media_access(&state, &user, &requesting_user).await?;
 
-    if !are_friends && user.id != claims.id && !requesting_user.is_admin() {
+    if access == MediaAccess::Denied {
         return Err(GETError::Unauthorized);
     }
 
+    // muted users stay friends, their media is just not listed
+    if is_muted(&state, &requesting_user, &user).await? {
+        return Ok(Json(vec![]));
+    }
+
     let path = format!("{}{}", state.data_directory, user_name);
-    let all_files = read_files_in_directory(&path, false)?;
+    let all_files = read_files_in_directory(&path, false)?
+        .iter()
+        .map(|dir| dir.file_name().into_string())
+        .filter_map(|f| f.ok())
+        .collect::<Vec<String>>();
+    let all_files = visible_files(&state, &user, MediaKind::Post, access, all_files).await?;
 
-    return Ok(Json(
-        all_files
-            .iter()
-            .skip(query.offset.unwrap_or(0))
-            .take(query.limit.unwrap_or(all_files.len()))
-            .map(|dir| dir.file_name().into_string())
-            .filter_map(|f| f.ok())
-            .collect::<Vec<String>>()
-    ));
+    Ok(Json(query.paginate(all_files)))
 }
 
 #[get("stories/{user_name}")]
@@ -56,30 +54,27 @@ pub async fn stories(user_name: Path<String>, state: Data<AppState>, claims: Tok
     let user_name = user_name.into_inner();
     let user = state.db.user().select(&SelectUserByName { username: &user_name }).await?;
     let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+    let access = media_access(&state, &user, &requesting_user).await?;
 
-    let are_friends = state.db.friendship().select(&SelectUserById { id: claims.id })
-        .await?
-        .iter()
-        .filter(|friendship| friendship.friend_a == user.id || friendship.friend_b == user.id)
-        .count() > 0;
-
-    if !are_friends && user.id != claims.id && !requesting_user.is_admin() {
+    if access == MediaAccess::Denied {
         return Err(GETError::Unauthorized);
     }
 
+    if is_muted(&state, &requesting_user, &user).await? {
+        return Ok(Json(vec![]));
+    }
+
     let path = format!("{}{}/stories",  state.data_directory, user.name);
     // create or do nothing, when created
     std::fs::create_dir_all(&path)?;
 
-    let all_files = read_files_in_directory(&path, true)?;
+    let all_files = read_files_in_directory(&path, true)?
+        .iter()
+        .map(|dir| dir.file_name().into_string())
+        .filter_map(|f| f.ok())
+        .collect::<Vec<String>>();
 
-    return Ok(Json(
-        all_files
-            .iter()
-            .map(|dir| dir.file_name().into_string())
-            .filter_map(|f| f.ok())
-            .collect::<Vec<String>>()
-    ));
+    Ok(Json(visible_files(&state, &user, MediaKind::Story, access, all_files).await?))
 }
 
 #[get("stories/{user_name}/{path}")]
@@ -87,14 +82,9 @@ pub async fn story(user_name: Path<(String, String)>, state: Data<AppState>, cla
     let (user_name, media_file_name) = user_name.into_inner();
     let user = state.db.user().select(&SelectUserByName { username: &user_name }).await?;
     let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+    let access = media_access(&state, &user, &requesting_user).await?;
 
-    let are_friends = state.db.friendship().select(&SelectUserById { id: claims.id })
-        .await?
-        .iter()
-        .filter(|friendship| friendship.friend_a == user.id || friendship.friend_b == user.id)
-        .count() > 0;
-
-    if !are_friends && user.id != claims.id && !requesting_user.is_admin() {
+    if access == MediaAccess::Denied {
         return Err(GETError::Unauthorized);
     }
 
@@ -109,6 +99,7 @@ pub async fn story(user_name: Path<(String, String)>, state: Data<AppState>, cla
         .map(|dir| dir.file_name().into_string())
         .filter_map(|f| f.ok())
         .collect::<Vec<String>>();
+    let all_files = visible_files(&state, &user, MediaKind::Story, access, all_files).await?;
 
     if all_files.contains(&media_file_name) {
         Ok(NamedFile::open(format!("{}{}/stories/{}",  state.data_directory, user.name, media_file_name))?)
@@ -117,8 +108,34 @@ pub async fn story(user_name: Path<(String, String)>, state: Data<AppState>, cla
     }
 }
 
+// posts are only served through the same checks as the listing, so unfriending or blocking revokes the access immediately
+#[get("/{user_name}/{file_name}")]
+pub async fn post(path: Path<(String, String)>, state: Data<AppState>, claims: TokenClaims) -> actix_web::Result<NamedFile, GETError> {
+    let (user_name, media_file_name) = path.into_inner();
+    let user = state.db.user().select(&SelectUserByName { username: &user_name }).await?;
+    let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+    let access = media_access(&state, &user, &requesting_user).await?;
+
+    if access == MediaAccess::Denied {
+        return Err(GETError::Unauthorized);
+    }
+
+    let all_files = read_files_in_directory(&format!("{}{}", state.data_directory, user.name), false)?
+        .iter()
+        .map(|dir| dir.file_name().into_string())
+        .filter_map(|f| f.ok())
+        .collect::<Vec<String>>();
+    let all_files = visible_files(&state, &user, MediaKind::Post, access, all_files).await?;
+
+    if all_files.contains(&media_file_name) {
+        Ok(NamedFile::open(format!("{}{}/{}", state.data_directory, user.name, media_file_name))?)
+    } else {
+        Err(GETError::CantRead)
+    }
+}
+
 #[post("stories/{user_name}")]
-pub async fn upload_story(user_name: Path<String>, payload: Multipart, req: HttpRequest, claims: TokenClaims, state: Data<AppState>) -> Result<HttpResponse, UploadError> {
+pub async fn upload_story(user_name: Path<String>, payload: Multipart, req: HttpRequest, query: Query<UploadQuery>, claims: TokenClaims, state: Data<AppState>) -> Result<HttpResponse, UploadError> {
     let user_name = user_name.into_inner();
 
     let user = state.db.user().select(&SelectUserByName { username: &user_name }).await?;
@@ -141,17 +158,21 @@ pub async fn upload_story(user_name: Path<String>, payload: Multipart, req: Http
         ]
     };
 
-    write_files_in_directory(&req, payload, upload_options, &state, |inner_state, file_name| {
+    let written_files = write_files_in_directory(&req, payload, upload_options, &state, |inner_state, file_name| {
         format!("{}{}/stories/{}_{}", inner_state.data_directory, user.name, Uuid::new_v4(), file_name)
     }).await?;
 
-    validate_stories(&format!("{}{}/stories", state.data_directory, user.name))?;
+    insert_media_entries(&state, &user, written_files, MediaKind::Story, query.visibility.unwrap_or_default()).await?;
+
+    for file_name in validate_stories(&format!("{}{}/stories", state.data_directory, user.name))? {
+        state.db.media().delete(&DeleteMediaEntry { owner: user.id, kind: MediaKind::Story, file_name }).await?;
+    }
 
     Ok(HttpResponse::Ok().into())
 }
 
 #[post("/{user_name}")]
-pub async fn upload(payload: Multipart, req: HttpRequest, user_name: Path<String>, claims: TokenClaims, state: Data<AppState>) -> Result<HttpResponse, UploadError> {
+pub async fn upload(payload: Multipart, req: HttpRequest, user_name: Path<String>, query: Query<UploadQuery>, claims: TokenClaims, state: Data<AppState>) -> Result<HttpResponse, UploadError> {
     let user_name = user_name.into_inner();
 
     let user = state.db.user().select(&SelectUserByName { username: &user_name }).await?;
@@ -174,9 +195,49 @@ pub async fn upload(payload: Multipart, req: HttpRequest, user_name: Path<String
         ]
     };
 
-    write_files_in_directory(&req, payload, upload_options, &state, |inner_state, file_name| {
+    let written_files = write_files_in_directory(&req, payload, upload_options, &state, |inner_state, file_name| {
         format!("{}{}/{}_{}", inner_state.data_directory, user.name, Uuid::new_v4(), file_name)
     }).await?;
 
+    state.db.user().update(&UpdatePostCount { target_id: user.id, change: written_files.len() as i64 }).await?;
+    insert_media_entries(&state, &user, written_files, MediaKind::Post, query.visibility.unwrap_or_default()).await?;
+
+    Ok(HttpResponse::Ok().into())
+}
+
+#[delete("/{user_name}/{file_name}")]
+pub async fn delete_post(path: Path<(String, String)>, claims: TokenClaims, state: Data<AppState>) -> Result<HttpResponse, DeleteError> {
+    let (user_name, file_name) = path.into_inner();
+
+    let user = state.db.user().select(&SelectUserByName { username: &user_name }).await?;
+    let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+
+    if user.id != claims.id && !requesting_user.is_admin() {
+        return Err(DeleteError::Unauthorized);
+    }
+
+    // only plain file names, so nothing outside of the posts directory can be removed
+    if std::path::Path::new(&file_name).file_name().and_then(|f| f.to_str()) != Some(file_name.as_str()) {
+        return Err(DeleteError::ContentNotFound(file_name));
+    }
+
+    let path = format!("{}{}/{}", state.data_directory, user.name, file_name);
+    if !std::path::Path::new(&path).is_file() || std::fs::remove_file(&path).is_err() {
+        return Err(DeleteError::ContentNotFound(file_name));
+    }
+
+    state.db.media().delete(&DeleteMediaEntry { owner: user.id, kind: MediaKind::Post, file_name }).await?;
+    state.db.user().update(&UpdatePostCount { target_id: user.id, change: -1 }).await?;
+
     Ok(HttpResponse::Ok().into())
+}
+
+async fn insert_media_entries(state: &Data<AppState>, user: &User, written_files: Vec<String>, kind: MediaKind, visibility: Visibility) -> Result<(), UploadError> {
+    for destination in written_files {
+        if let Some(file_name) = std::path::Path::new(&destination).file_name().and_then(|f| f.to_str()) {
+            state.db.media().insert(MediaEntry::new(user.id, file_name.to_string(), kind, visibility)).await?;
+        }
+    }
+
+    Ok(())
 }
\ No newline at end of file
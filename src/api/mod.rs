@@ -3,3 +3,13 @@ pub mod user;
 pub mod authentication;
 pub mod shared;
 pub mod changelog;
+pub mod takeout;
+pub mod suggestion;
+pub mod follow;
+pub mod close_friend;
+pub mod restriction;
+pub mod list;
+pub mod list_sync;
+pub mod list_revision;
+#[cfg(test)]
+mod tests;
\ No newline at end of file
@@ -0,0 +1,103 @@
+use actix_web::{delete, get, HttpResponse, post};
+use actix_web::web::{Data, Json, Path};
+use crate::api::shared::GETError;
+use crate::api::user::to_friend_list;
+use crate::middleware::TokenClaims;
+use crate::model::close_friend::DeleteCloseFriend;
+use crate::model::follow::DeleteFollow;
+use crate::model::friend::Friend;
+use crate::model::friendship::{DeleteFriendRequest, DeleteFriendship};
+use crate::model::restriction::{DeleteRestriction, Restriction, RestrictionError, RestrictionKind, SelectRestrictions};
+use crate::model::states::app_state::AppState;
+use crate::model::user::{SelectUserById, SelectUserByName, User};
+
+#[post("/{user_a}/block/{user_b}")]
+pub async fn block(users: Path<(String, String)>, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, RestrictionError> {
+    let (owner, target) = select_restriction_users(users.into_inner(), &state, &claims).await?;
+
+    state.db.restriction().insert(Restriction::new(owner.id, target.id, RestrictionKind::Block)).await?;
+
+    // a block ends every relation between both users
+    state.db.friendship().delete(&DeleteFriendship { user_a: owner.id, user_b: target.id }).await?;
+    state.db.friendship().delete(&DeleteFriendRequest { requester: owner.id, addressee: target.id }).await?;
+    state.db.friendship().delete(&DeleteFriendRequest { requester: target.id, addressee: owner.id }).await?;
+    state.db.close_friend().delete(&DeleteCloseFriend { owner: owner.id, friend: target.id }).await?;
+    state.db.close_friend().delete(&DeleteCloseFriend { owner: target.id, friend: owner.id }).await?;
+    state.db.follow().delete(&DeleteFollow { follower: owner.id, followee: target.id }).await?;
+    state.db.follow().delete(&DeleteFollow { follower: target.id, followee: owner.id }).await?;
+
+    Ok(HttpResponse::Ok().into())
+}
+
+#[delete("/{user_a}/block/{user_b}")]
+pub async fn unblock(users: Path<(String, String)>, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, RestrictionError> {
+    remove_restriction(users.into_inner(), RestrictionKind::Block, &state, &claims).await
+}
+
+#[get("/{user_name}/blocked")]
+pub async fn blocked(user_name: Path<String>, state: Data<AppState>, claims: TokenClaims) -> Result<Json<Vec<Friend>>, GETError> {
+    restrictions(&user_name.into_inner(), RestrictionKind::Block, &state, &claims).await
+}
+
+#[post("/{user_a}/mute/{user_b}")]
+pub async fn mute(users: Path<(String, String)>, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, RestrictionError> {
+    let (owner, target) = select_restriction_users(users.into_inner(), &state, &claims).await?;
+
+    state.db.restriction().insert(Restriction::new(owner.id, target.id, RestrictionKind::Mute)).await?;
+
+    Ok(HttpResponse::Ok().into())
+}
+
+#[delete("/{user_a}/mute/{user_b}")]
+pub async fn unmute(users: Path<(String, String)>, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, RestrictionError> {
+    remove_restriction(users.into_inner(), RestrictionKind::Mute, &state, &claims).await
+}
+
+#[get("/{user_name}/muted")]
+pub async fn muted(user_name: Path<String>, state: Data<AppState>, claims: TokenClaims) -> Result<Json<Vec<Friend>>, GETError> {
+    restrictions(&user_name.into_inner(), RestrictionKind::Mute, &state, &claims).await
+}
+
+async fn remove_restriction(users: (String, String), kind: RestrictionKind, state: &Data<AppState>, claims: &TokenClaims) -> Result<HttpResponse, RestrictionError> {
+    let (owner, target) = select_restriction_users(users, state, claims).await?;
+
+    if !state.db.restriction().delete(&DeleteRestriction { owner: owner.id, target: target.id, kind }).await? {
+        return Err(RestrictionError::NotRestricted);
+    }
+
+    Ok(HttpResponse::Ok().into())
+}
+
+async fn restrictions(user_name: &str, kind: RestrictionKind, state: &Data<AppState>, claims: &TokenClaims) -> Result<Json<Vec<Friend>>, GETError> {
+    let user = state.db.user().select(&SelectUserByName { username: user_name }).await?;
+    let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+
+    if user.id != claims.id && !requesting_user.is_admin() {
+        return Err(GETError::Unauthorized);
+    }
+
+    let ids = state.db.restriction().select(&SelectRestrictions { owner: user.id, kind })
+        .await?
+        .into_iter()
+        .map(|restriction| restriction.target)
+        .collect::<Vec<_>>();
+
+    Ok(Json(to_friend_list(ids, state).await?))
+}
+
+// user_a is the restricting user, it must be the requesting one or the requesting one is an admin
+async fn select_restriction_users(users: (String, String), state: &Data<AppState>, claims: &TokenClaims) -> Result<(User, User), RestrictionError> {
+    let user_a = state.db.user().select(&SelectUserByName { username: &users.0 }).await?;
+    let user_b = state.db.user().select(&SelectUserByName { username: &users.1 }).await?;
+    let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+
+    if user_a.id != claims.id && !requesting_user.is_admin() {
+        return Err(RestrictionError::Unauthorized);
+    }
+
+    if user_a.id == user_b.id {
+        return Err(RestrictionError::SelfRestriction);
+    }
+
+    Ok((user_a, user_b))
+}
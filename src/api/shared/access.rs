@@ -0,0 +1,87 @@
+use std::collections::HashSet;
+use actix_web::web::Data;
+use crate::api::shared::GETError;
+use crate::model::close_friend::SelectCloseFriend;
+use crate::model::follow::SelectFollow;
+use crate::model::friendship::{FriendshipStatus, SelectFriendshipBetween};
+use crate::model::media::{MediaKind, SelectMediaByOwner, Visibility};
+use crate::model::restriction::{RestrictionKind, SelectBlockBetween, SelectRestriction};
+use crate::model::states::app_state::AppState;
+use crate::model::user::User;
+use crate::model::SelectDatabaseError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaAccess {
+    // owner and admins
+    Owner,
+    // friends the owner added to its close friends
+    CloseFriend,
+    Friend,
+    // followers of a public or bot account
+    Follower,
+    Denied
+}
+
+impl MediaAccess {
+    pub fn is_friend(&self) -> bool {
+        matches!(self, MediaAccess::Owner | MediaAccess::CloseFriend | MediaAccess::Friend)
+    }
+}
+
+pub async fn media_access(state: &Data<AppState>, owner: &User, requesting_user: &User) -> Result<MediaAccess, GETError> {
+    if owner.id == requesting_user.id || requesting_user.is_admin() {
+        return Ok(MediaAccess::Owner);
+    }
+
+    if is_blocked(state, owner, requesting_user).await? {
+        return Ok(MediaAccess::Denied);
+    }
+
+    let friendship = state.db.friendship().select(&SelectFriendshipBetween { user_a: owner.id, user_b: requesting_user.id }).await?;
+    if matches!(friendship, Some(friendship) if friendship.status == FriendshipStatus::Accepted) {
+        if state.db.close_friend().select(&SelectCloseFriend { owner: owner.id, friend: requesting_user.id }).await?.is_some() {
+            return Ok(MediaAccess::CloseFriend);
+        }
+
+        return Ok(MediaAccess::Friend);
+    }
+
+    if owner.is_followable() && state.db.follow().select(&SelectFollow { follower: requesting_user.id, followee: owner.id }).await?.is_some() {
+        return Ok(MediaAccess::Follower);
+    }
+
+    Ok(MediaAccess::Denied)
+}
+
+// in either direction
+pub async fn is_blocked(state: &Data<AppState>, user_a: &User, user_b: &User) -> Result<bool, SelectDatabaseError> {
+    Ok(state.db.restriction().select(&SelectBlockBetween { user_a: user_a.id, user_b: user_b.id }).await?.is_some())
+}
+
+pub async fn is_muted(state: &Data<AppState>, owner: &User, target: &User) -> Result<bool, SelectDatabaseError> {
+    Ok(state.db.restriction().select(&SelectRestriction { owner: owner.id, target: target.id, kind: RestrictionKind::Mute }).await?.is_some())
+}
+
+// keeps the order of `file_names`
+pub async fn visible_files(state: &Data<AppState>, owner: &User, kind: MediaKind, access: MediaAccess, file_names: Vec<String>) -> Result<Vec<String>, GETError> {
+    match access {
+        MediaAccess::Owner | MediaAccess::CloseFriend => Ok(file_names),
+        MediaAccess::Denied => Err(GETError::Unauthorized),
+        MediaAccess::Friend => {
+            let close_friends_only = file_names_with(state, owner, kind, Visibility::CloseFriends).await?;
+            Ok(file_names.into_iter().filter(|file_name| !close_friends_only.contains(file_name)).collect())
+        },
+        MediaAccess::Follower => {
+            let public = file_names_with(state, owner, kind, Visibility::Public).await?;
+            Ok(file_names.into_iter().filter(|file_name| public.contains(file_name)).collect())
+        }
+    }
+}
+
+async fn file_names_with(state: &Data<AppState>, owner: &User, kind: MediaKind, visibility: Visibility) -> Result<HashSet<String>, GETError> {
+    Ok(state.db.media().select(&SelectMediaByOwner { owner: owner.id, kind, visibility: Some(visibility) })
+        .await?
+        .into_iter()
+        .map(|entry| entry.file_name)
+        .collect())
+}
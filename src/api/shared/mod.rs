@@ -3,18 +3,39 @@ use std::io::Error;
 use std::num::ParseIntError;
 use actix_web::http::StatusCode;
 use actix_web::ResponseError;
+use serde::Deserialize;
 use crate::model::friend::{CreateFriendshipError, FetchFriendshipError};
+use crate::model::list::ListError;
 use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError, UpdateDatabaseError};
 use crate::model::user::FetchUserError;
 
-pub mod list;
+pub mod access;
+
+#[derive(Debug, Deserialize)]
+pub struct QueryInfo {
+    //pagination
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+impl QueryInfo {
+    pub fn paginate<T>(&self, items: Vec<T>) -> Vec<T> {
+        let limit = self.limit.unwrap_or(items.len());
+
+        items
+            .into_iter()
+            .skip(self.offset.unwrap_or(0))
+            .take(limit)
+            .collect()
+    }
+}
 
 #[derive(Debug)]
 pub enum GETError {
     CantRead,
     UserNotFound,
     Unauthorized,
-    DatabaseError(mongodb::error::Error)
+    DatabaseError
 }
 
 #[derive(Debug)]
@@ -22,8 +43,8 @@ pub enum UploadError {
     FileSizeTooBig,
     UserNotFound,
     IllegalContentType,
-    CorruptedHeaderLength(ParseIntError),
-    IOError(Error),
+    CorruptedHeaderLength,
+    IOError,
     WritingError,
     Unauthorized
 }
@@ -31,7 +52,8 @@ pub enum UploadError {
 #[derive(Debug)]
 pub enum DeleteError {
     UserNotFound,
-    DatabaseError(mongodb::error::Error),
+    Unauthorized,
+    DatabaseError,
     ContentNotFound(String),
 }
 
@@ -39,7 +61,7 @@ pub enum DeleteError {
 impl From<DeleteDatabaseError> for DeleteError {
     fn from(value: DeleteDatabaseError) -> Self {
         match value {
-            DeleteDatabaseError::DatabaseError(value) => DeleteError::DatabaseError(value)
+            DeleteDatabaseError::DatabaseError(_) => DeleteError::DatabaseError
         }
     }
 }
@@ -55,7 +77,7 @@ impl From<InsertDatabaseError> for UploadError {
 impl From<InsertDatabaseError> for GETError {
     fn from(value: InsertDatabaseError) -> Self {
         match value {
-            InsertDatabaseError::DatabaseError(e) => GETError::DatabaseError(e)
+            InsertDatabaseError::DatabaseError(_) => GETError::DatabaseError
         }
     }
 }
@@ -63,7 +85,7 @@ impl From<InsertDatabaseError> for GETError {
 impl From<SelectDatabaseError> for GETError {
     fn from(value: SelectDatabaseError) -> Self {
         match value {
-            SelectDatabaseError::DatabaseError(e) => GETError::DatabaseError(e)
+            SelectDatabaseError::DatabaseError(_) => GETError::DatabaseError
         }
     }
 }
@@ -79,7 +101,7 @@ impl From<UpdateDatabaseError> for DeleteError {
 impl From<SelectDatabaseError> for DeleteError {
     fn from(value: SelectDatabaseError) -> Self {
         match value {
-            SelectDatabaseError::DatabaseError(e) => DeleteError::DatabaseError(e)
+            SelectDatabaseError::DatabaseError(_) => DeleteError::DatabaseError
         }
     }
 }
@@ -96,8 +118,8 @@ impl From<FetchUserError> for DeleteError {
 impl Display for UploadError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", match self {
-            UploadError::CorruptedHeaderLength(_) => "The provided Content length header was not parsable to an integer".to_string(),
-            UploadError::IOError(_) => "Internal".to_string(),
+            UploadError::CorruptedHeaderLength => "The provided Content length header was not parsable to an integer".to_string(),
+            UploadError::IOError => "Internal".to_string(),
             UploadError::WritingError => "Internal".to_string(),
             UploadError::FileSizeTooBig => "File size is too big.".to_string(),
             UploadError::UserNotFound => "User not found".to_string(),
@@ -111,8 +133,9 @@ impl Display for DeleteError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", match self {
             DeleteError::UserNotFound => "User not found".to_string(),
+            DeleteError::Unauthorized => "Unauthorized".to_string(),
             DeleteError::ContentNotFound(content) => format!("Content not found: {content}"),
-            DeleteError::DatabaseError(_) => format!("Internal delete error")
+            DeleteError::DatabaseError => "Internal delete error".to_string()
         })
     }
 }
@@ -121,8 +144,9 @@ impl ResponseError for DeleteError {
     fn status_code(&self) -> StatusCode {
         match self {
             DeleteError::UserNotFound => StatusCode::NOT_FOUND,
+            DeleteError::Unauthorized => StatusCode::UNAUTHORIZED,
             DeleteError::ContentNotFound(_) => StatusCode::NOT_FOUND,
-            DeleteError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            DeleteError::DatabaseError => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 }
@@ -147,6 +171,14 @@ impl From<UpdateDatabaseError> for UploadError {
     }
 }
 
+impl From<DeleteDatabaseError> for UploadError {
+    fn from(value: DeleteDatabaseError) -> Self {
+        match value {
+            DeleteDatabaseError::DatabaseError(_) => UploadError::WritingError
+        }
+    }
+}
+
 impl From<SelectDatabaseError> for UploadError {
     fn from(value: SelectDatabaseError) -> Self {
         match value {
@@ -158,8 +190,9 @@ impl From<SelectDatabaseError> for UploadError {
 impl From<CreateFriendshipError> for UploadError {
     fn from(value: CreateFriendshipError) -> Self {
         match value {
-            CreateFriendshipError::DatabaseError(_) => UploadError::WritingError,
-            CreateFriendshipError::AlreadyFriends => UploadError::WritingError
+            CreateFriendshipError::DatabaseError => UploadError::WritingError,
+            CreateFriendshipError::AlreadyFriends => UploadError::WritingError,
+            CreateFriendshipError::RequestPending => UploadError::WritingError
         }
     }
 }
@@ -188,20 +221,40 @@ impl From<GETError> for UploadError {
             GETError::CantRead => UploadError::WritingError,
             GETError::UserNotFound => UploadError::UserNotFound,
             GETError::Unauthorized => UploadError::Unauthorized,
-            GETError::DatabaseError(_) => UploadError::WritingError
+            GETError::DatabaseError => UploadError::WritingError
+        }
+    }
+}
+
+impl From<GETError> for ListError {
+    fn from(value: GETError) -> Self {
+        match value {
+            GETError::UserNotFound => ListError::UserNotFound,
+            GETError::Unauthorized => ListError::Unauthorized,
+            GETError::CantRead | GETError::DatabaseError => ListError::Internal
+        }
+    }
+}
+
+impl From<ListError> for UploadError {
+    fn from(value: ListError) -> Self {
+        match value {
+            ListError::UserNotFound => UploadError::UserNotFound,
+            ListError::Unauthorized => UploadError::Unauthorized,
+            _ => UploadError::WritingError
         }
     }
 }
 
 impl From<ParseIntError> for UploadError {
-    fn from(value: ParseIntError) -> Self {
-        UploadError::CorruptedHeaderLength(value)
+    fn from(_: ParseIntError) -> Self {
+        UploadError::CorruptedHeaderLength
     }
 }
 
 impl From<std::io::Error> for UploadError {
-    fn from(value: std::io::Error) -> Self {
-        UploadError::IOError(value)
+    fn from(_: std::io::Error) -> Self {
+        UploadError::IOError
     }
 }
 
@@ -217,7 +270,7 @@ impl Display for GETError {
             GETError::CantRead => "Not found",
             GETError::UserNotFound => "User not found",
             GETError::Unauthorized => "Unauthorized",
-            GETError::DatabaseError(_) =>"Internal"
+            GETError::DatabaseError =>"Internal"
         })
     }
 }
@@ -228,7 +281,7 @@ impl ResponseError for GETError {
             GETError::CantRead => StatusCode::NOT_FOUND,
             GETError::UserNotFound => StatusCode::NOT_FOUND,
             GETError::Unauthorized => StatusCode::UNAUTHORIZED,
-            GETError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR
+            GETError::DatabaseError => StatusCode::INTERNAL_SERVER_ERROR
         }
     }
 }
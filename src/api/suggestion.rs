@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+use actix_web::{get, HttpResponse, post};
+use actix_web::web::{Data, Json, Path, Query};
+use serde::Deserialize;
+use crate::api::shared::{GETError, UploadError};
+use crate::middleware::TokenClaims;
+use crate::model::friendship::{RequestDirection, SelectFriendRequests};
+use crate::model::restriction::SelectBlockedUsers;
+use crate::model::states::app_state::AppState;
+use crate::model::suggestion::{SelectSuggestions, Suggestion, SuggestionDismissal};
+use crate::model::user::{SelectUserById, SelectUserByName, SelectUsersByIds};
+
+#[derive(Debug, Deserialize)]
+pub struct SuggestionQuery {
+    pub limit: Option<i64>
+}
+
+#[get("/{user_name}/suggestions")]
+pub async fn suggestions(user_name: Path<String>, query: Query<SuggestionQuery>, state: Data<AppState>, claims: TokenClaims) -> Result<Json<Vec<Suggestion>>, GETError> {
+    let user_name = user_name.into_inner();
+
+    let user = state.db.user().select(&SelectUserByName { username: &user_name }).await?;
+    let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+
+    if user.id != claims.id && !requesting_user.is_admin() {
+        return Err(GETError::Unauthorized);
+    }
+
+    let friendships = state.db.friendship().select(&SelectUserById { id: user.id }).await?;
+    let incoming = state.db.friendship().select(&SelectFriendRequests { id: user.id, direction: RequestDirection::Incoming }).await?;
+    let outgoing = state.db.friendship().select(&SelectFriendRequests { id: user.id, direction: RequestDirection::Outgoing }).await?;
+    let dismissals = state.db.suggestion().select(&SelectUserById { id: user.id }).await?;
+    let blocked = state.db.restriction().select(&SelectBlockedUsers { id: user.id }).await?;
+
+    let mut excluded = vec![user.id];
+    excluded.extend(friendships.iter().chain(&incoming).chain(&outgoing).map(|f| if f.friend_a == user.id { f.friend_b } else { f.friend_a }));
+    excluded.extend(dismissals.iter().map(|dismissal| dismissal.dismissed_id));
+    excluded.extend(blocked);
+
+    let select_suggestions = SelectSuggestions {
+        id: user.id,
+        excluded,
+        limit: query.limit.unwrap_or(20).clamp(1, 100),
+    };
+
+    let ranking = state.db.friendship().select(&select_suggestions).await?;
+    let mut candidates = state.db.user().select(&SelectUsersByIds { ids: ranking.iter().map(|mutual| mutual.user_id).collect() })
+        .await?
+        .into_iter()
+        .map(|candidate| (candidate.id, candidate))
+        .collect::<HashMap<_, _>>();
+
+    // keeps the ranking order
+    let suggestions = ranking
+        .into_iter()
+        .filter_map(|mutual| candidates.remove(&mutual.user_id).map(|candidate| Suggestion {
+            user_name: candidate.name.clone(),
+            profile_image: format!("user/{}/avatar", &candidate.name),
+            description: candidate.description,
+            is_bot: candidate.is_bot,
+            mutual_friends: mutual.mutual_friends,
+        }))
+        .collect();
+
+    Ok(Json(suggestions))
+}
+
+#[post("/{user_name}/suggestions/{dismissed_name}/dismiss")]
+pub async fn dismiss_suggestion(users: Path<(String, String)>, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, UploadError> {
+    let (user_name, dismissed_name) = users.into_inner();
+
+    let user = state.db.user().select(&SelectUserByName { username: &user_name }).await?;
+    let dismissed = state.db.user().select(&SelectUserByName { username: &dismissed_name }).await?;
+    let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+
+    if user.id != claims.id && !requesting_user.is_admin() {
+        return Err(UploadError::Unauthorized);
+    }
+
+    state.db.suggestion().insert(SuggestionDismissal::new(user.id, dismissed.id)).await?;
+
+    Ok(HttpResponse::Ok().into())
+}
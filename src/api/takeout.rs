@@ -0,0 +1,339 @@
+use actix_files::NamedFile;
+use actix_multipart::Multipart;
+use actix_web::{get, HttpRequest, post};
+use actix_web::http::header::{ContentDisposition, DispositionParam, DispositionType};
+use actix_web::web::{Data, Json, Path};
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::Serialize;
+use uuid::Uuid;
+use zip::result::ZipError;
+use crate::api::list::{changed, record_revision};
+use crate::api::shared::{GETError, UploadError};
+use crate::api::shared::access::is_blocked;
+use crate::middleware::TokenClaims;
+use crate::model::friendship::{AcceptFriendRequest, Friendship, FriendshipStatus, SelectFriendshipBetween};
+use crate::model::list::{List, ReplaceListEntries, SelectListsByOwner};
+use crate::model::export::{DeleteExportById, ExportJob, ExportStatus, MAX_EXPORTS_PER_USER, SelectExpiredExports, SelectExportById, SelectExportsByUser, UpdateExportStatus};
+use crate::model::states::app_state::AppState;
+use crate::model::takeout::{FriendManifest, ImportReport, ListManifest, ProfileManifest};
+use crate::model::user::{RestoreUserProfile, SelectUserById, SelectUserByName, SelectUsersByIds, SetPostCount, User};
+use crate::utils::{count_files_in_directory, UploadOptions, write_files_in_directory};
+use crate::utils::archive::{ARCHIVE_TOO_LARGE, read_takeout_archive, TakeoutContent, write_takeout_archive};
+
+#[derive(Debug, Serialize)]
+pub struct ExportJobInformation {
+    id: String,
+    status: ExportStatus,
+    created_at: DateTime<Utc>,
+    expires_at: Option<DateTime<Utc>>,
+    download: Option<String>
+}
+
+impl ExportJobInformation {
+    fn new(job: &ExportJob, user_name: &str) -> Self {
+        let status = job.current_status();
+        let download = match status {
+            ExportStatus::Finished if !job.is_expired() => Some(format!("user/{}/export/{}/download", user_name, job.id.to_hex())),
+            _ => None
+        };
+
+        Self {
+            id: job.id.to_hex(),
+            status,
+            created_at: job.created_at,
+            expires_at: job.expires_at,
+            download
+        }
+    }
+}
+
+#[post("/{user_name}/export")]
+pub async fn export(user_name: Path<String>, state: Data<AppState>, claims: TokenClaims) -> Result<Json<ExportJobInformation>, UploadError> {
+    let user_name = user_name.into_inner();
+
+    let user = state.db.user().select(&SelectUserByName { username: &user_name }).await?;
+    let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+
+    if user.id != claims.id && !requesting_user.is_admin() {
+        return Err(UploadError::Unauthorized);
+    }
+
+    remove_expired_exports(&state).await;
+
+    // a running export is reported instead of starting another one
+    let jobs = state.db.export().select(&SelectExportsByUser { user_id: user.id }).await?;
+    if let Some(pending) = jobs.iter().find(|job| job.status == ExportStatus::Pending) {
+        return Ok(Json(ExportJobInformation::new(pending, &user.name)));
+    }
+
+    for job in jobs.iter().take((jobs.len() + 1).saturating_sub(MAX_EXPORTS_PER_USER)) {
+        remove_export(&state, job).await;
+    }
+
+    let new_job = ExportJob::new(user.id);
+    let job = state.db.export().insert(new_job.clone()).await?;
+    let information = ExportJobInformation::new(&job, &user.name);
+
+    // a concurrent request started an export in the meantime
+    if job.id == new_job.id {
+        actix_web::rt::spawn(run_export(state, user, job.id));
+    }
+
+    Ok(Json(information))
+}
+
+#[get("/{user_name}/export/{export_id}")]
+pub async fn export_status(path: Path<(String, String)>, state: Data<AppState>, claims: TokenClaims) -> Result<Json<ExportJobInformation>, GETError> {
+    let (user_name, export_id) = path.into_inner();
+    let (user, job) = select_export(&user_name, &export_id, &state, &claims).await?;
+
+    Ok(Json(ExportJobInformation::new(&job, &user.name)))
+}
+
+#[get("/{user_name}/export/{export_id}/download")]
+pub async fn download_export(path: Path<(String, String)>, state: Data<AppState>, claims: TokenClaims) -> Result<NamedFile, GETError> {
+    let (user_name, export_id) = path.into_inner();
+    let (user, job) = select_export(&user_name, &export_id, &state, &claims).await?;
+
+    if job.is_expired() {
+        remove_export(&state, &job).await;
+        return Err(GETError::CantRead);
+    }
+
+    if job.status != ExportStatus::Finished {
+        return Err(GETError::CantRead);
+    }
+
+    let file = NamedFile::open(export_path(&state, &job.id))?
+        .set_content_disposition(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(format!("{}_takeout.zip", user.name))],
+        });
+
+    Ok(file)
+}
+
+#[post("/{user_name}/import")]
+pub async fn import(payload: Multipart, req: HttpRequest, user_name: Path<String>, claims: TokenClaims, state: Data<AppState>) -> Result<Json<ImportReport>, UploadError> {
+    let user_name = user_name.into_inner();
+
+    let user = state.db.user().select(&SelectUserByName { username: &user_name }).await?;
+    let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+
+    if user.id != claims.id && !requesting_user.is_admin() {
+        return Err(UploadError::Unauthorized);
+    }
+
+    let upload_options = UploadOptions {
+        max_file_count: 1,
+        max_file_size: 2_000_000_000, // 2gb
+        legal_file_types: vec![
+            #[allow(clippy::unwrap_used)]
+            "application/zip".parse().unwrap(),
+            #[allow(clippy::unwrap_used)]
+            "application/x-zip-compressed".parse().unwrap(),
+            mime::APPLICATION_OCTET_STREAM
+        ]
+    };
+
+    let source = format!("{}import_{}.zip", state.export_directory, Uuid::new_v4());
+    if let Err(err) = write_files_in_directory(&req, payload, upload_options, &state, |_, _| source.clone()).await {
+        let _ = std::fs::remove_file(&source);
+        return Err(err);
+    }
+
+    let archive_path = source.clone();
+    let user_directory = format!("{}{}", state.data_directory, user.name);
+    let restored = actix_web::rt::task::spawn_blocking(move || read_takeout_archive(&archive_path, &user_directory)).await;
+    let _ = std::fs::remove_file(&source);
+
+    let restored = match restored {
+        Ok(Ok(restored)) => restored,
+        Ok(Err(ZipError::Io(err))) => {
+            log::error!("Import for {} failed: {}", user.name, err);
+            return Err(UploadError::IOError);
+        },
+        Ok(Err(ZipError::InvalidArchive(ARCHIVE_TOO_LARGE))) => return Err(UploadError::FileSizeTooBig),
+        Ok(Err(err)) => {
+            log::warn!("Import for {} rejected: {}", user.name, err);
+            return Err(UploadError::IllegalContentType);
+        },
+        Err(_) => return Err(UploadError::WritingError)
+    };
+
+    // only admins may turn accounts into bots, which makes them followable
+    if let Some(profile) = restored.profile {
+        let is_bot = if requesting_user.is_admin() { profile.is_bot } else { user.is_bot };
+        state.db.user().update(&RestoreUserProfile { target_id: user.id, description: profile.description, is_bot, is_public: profile.is_public }).await?;
+    }
+
+    // lists with the name of an existing list replace its entries
+    let mut lists = state.db.list().select(&SelectListsByOwner { owner: user.id }).await?;
+    for manifest in restored.lists {
+        match lists.iter().find(|list| list.name == manifest.name) {
+            Some(list) => {
+                if let Some(after) = state.db.list().update(&ReplaceListEntries { target: list.target(), entries: manifest.entries }).await? {
+                    changed(&state, claims.id, list, &after).await;
+                }
+            },
+            None => {
+                let mut list = List::new(user.id, manifest.name, manifest.entries);
+                list.archived = manifest.archived;
+                let list = state.db.list().insert(list).await?;
+                record_revision(&state, claims.id, &list).await;
+                lists.push(list);
+            }
+        }
+    }
+
+    // restored posts are not known to the stored counter yet
+    let posts_directory = format!("{}{}", state.data_directory, user.name);
+    let amount_posts = actix_web::rt::task::spawn_blocking(move || count_files_in_directory(&posts_directory))
+        .await
+        .map_err(|_| UploadError::WritingError)??;
+    state.db.user().update(&SetPostCount { target_id: user.id, amount_posts }).await?;
+
+    let mut report = ImportReport {
+        restored_files: restored.restored_files,
+        linked_friends: vec![],
+        requested_friends: vec![],
+        unmatched_friends: vec![],
+    };
+
+    // the archive can name anyone, so friends only get a request like from `post_friendship`
+    for friend in restored.friends {
+        let friend_user = match state.db.user().select(&SelectUserByName { username: &friend.user_name }).await {
+            Ok(friend_user) if friend_user.id != user.id => friend_user,
+            _ => {
+                report.unmatched_friends.push(friend.user_name);
+                continue;
+            }
+        };
+
+        if is_blocked(&state, &user, &friend_user).await? {
+            report.unmatched_friends.push(friend.user_name);
+            continue;
+        }
+
+        match request_friendship(&state, &user, &friend_user).await {
+            Ok(FriendshipStatus::Accepted) => report.linked_friends.push(friend.user_name),
+            Ok(FriendshipStatus::Pending) => report.requested_friends.push(friend.user_name),
+            Err(err) => {
+                log::error!("Could not request {} for {}: {:?}", friend.user_name, user.name, err);
+                report.unmatched_friends.push(friend.user_name);
+            }
+        }
+    }
+
+    Ok(Json(report))
+}
+
+// a pending request of `friend` is accepted, otherwise one is sent to `friend`
+async fn request_friendship(state: &Data<AppState>, user: &User, friend: &User) -> Result<FriendshipStatus, UploadError> {
+    let existing = state.db.friendship().select(&SelectFriendshipBetween { user_a: user.id, user_b: friend.id }).await?;
+
+    match existing {
+        Some(existing) if existing.status == FriendshipStatus::Pending && existing.friend_a == friend.id => {
+            state.db.friendship().update(&AcceptFriendRequest { requester: friend.id, addressee: user.id }).await?;
+            Ok(FriendshipStatus::Accepted)
+        },
+        Some(existing) => Ok(existing.status),
+        None => {
+            state.db.friendship().insert(Friendship::new(user.id, friend.id, FriendshipStatus::Pending)).await?;
+            Ok(FriendshipStatus::Pending)
+        }
+    }
+}
+
+async fn select_export(user_name: &str, export_id: &str, state: &Data<AppState>, claims: &TokenClaims) -> Result<(User, ExportJob), GETError> {
+    let user = state.db.user().select(&SelectUserByName { username: user_name }).await?;
+    let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+
+    if user.id != claims.id && !requesting_user.is_admin() {
+        return Err(GETError::Unauthorized);
+    }
+
+    let export_id = ObjectId::parse_str(export_id).map_err(|_| GETError::CantRead)?;
+
+    match state.db.export().select(&SelectExportById { id: export_id }).await? {
+        Some(job) if job.user_id == user.id => Ok((user, job)),
+        _ => Err(GETError::CantRead)
+    }
+}
+
+async fn run_export(state: Data<AppState>, user: User, job_id: ObjectId) {
+    let status = match build_archive(&state, &user, &job_id).await {
+        Ok(_) => ExportStatus::Finished,
+        Err(err) => {
+            log::error!("Export {} for {} failed: {}", job_id, user.name, err);
+            remove_archive(export_path(&state, &job_id)).await;
+            ExportStatus::Failed
+        }
+    };
+
+    if let Err(err) = state.db.export().update(&UpdateExportStatus { target_id: job_id, status }).await {
+        log::error!("Could not update export {}: {}", job_id, err);
+    }
+}
+
+async fn build_archive(state: &Data<AppState>, user: &User, job_id: &ObjectId) -> anyhow::Result<()> {
+    let friend_ids = state.db.friendship().select(&SelectUserById { id: user.id })
+        .await?
+        .into_iter()
+        .map(|friendship| if friendship.friend_a == user.id { friendship.friend_b } else { friendship.friend_a })
+        .collect::<Vec<_>>();
+
+    let friends = state.db.user().select(&SelectUsersByIds { ids: friend_ids })
+        .await?
+        .into_iter()
+        .map(|friend| FriendManifest { user_name: friend.name })
+        .collect();
+
+    let content = TakeoutContent {
+        user_directory: format!("{}{}", state.data_directory, user.name),
+        profile: ProfileManifest {
+            user_name: user.name.clone(),
+            description: user.description.clone(),
+            is_bot: user.is_bot,
+            is_public: user.is_public,
+            created_at: user.created_at,
+        },
+        friends,
+        lists: state.db.list().select(&SelectListsByOwner { owner: user.id })
+            .await?
+            .into_iter()
+            .map(ListManifest::from)
+            .collect(),
+    };
+
+    let destination = export_path(state, job_id);
+    actix_web::rt::task::spawn_blocking(move || write_takeout_archive(&content, &destination)).await??;
+
+    Ok(())
+}
+
+async fn remove_expired_exports(state: &Data<AppState>) {
+    if let Ok(jobs) = state.db.export().select(&SelectExpiredExports).await {
+        for job in jobs {
+            remove_export(state, &job).await;
+        }
+    }
+}
+
+async fn remove_export(state: &Data<AppState>, job: &ExportJob) {
+    remove_archive(export_path(state, &job.id)).await;
+
+    if let Err(err) = state.db.export().delete(&DeleteExportById { id: job.id }).await {
+        log::error!("Could not delete export {}: {}", job.id, err);
+    }
+}
+
+// blocking, so it is removed off the executor like the archive is written
+async fn remove_archive(path: String) {
+    let _ = actix_web::rt::task::spawn_blocking(move || std::fs::remove_file(path)).await;
+}
+
+fn export_path(state: &AppState, job_id: &ObjectId) -> String {
+    format!("{}{}.zip", state.export_directory, job_id.to_hex())
+}
@@ -0,0 +1,81 @@
+use actix_web::{App, test};
+use actix_web::http::StatusCode;
+use actix_web::web::Data;
+use serde_json::{json, Value};
+use crate::api::changelog::{changelog_feed, create_changelog, create_feed_token, publish_changelog};
+use crate::api::tests::{FEED_API_KEY, insert_user, test_state, token};
+
+#[actix_web::test]
+async fn only_admins_manage_changelogs() {
+    let state = test_state();
+    let alice = insert_user(&state, "alice").await;
+    let app = test::init_service(App::new().app_data(Data::new(state.clone())).service(create_changelog)).await;
+
+    let request = test::TestRequest::post()
+        .uri("/admin/changelogs")
+        .cookie(token(&state, &alice))
+        .set_json(json!({ "version": "1.0.0" }))
+        .to_request();
+
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn published_changelogs_are_in_the_feed() {
+    let state = test_state();
+    let admin = insert_user(&state, "admin").await;
+    let alice = insert_user(&state, "alice").await;
+    let app = test::init_service(App::new()
+        .app_data(Data::new(state.clone()))
+        .service(create_changelog)
+        .service(publish_changelog)
+        .service(create_feed_token)
+        .service(changelog_feed)).await;
+
+    for version in ["1.0.0", "1.1.0"] {
+        let request = test::TestRequest::post()
+            .uri("/admin/changelogs")
+            .cookie(token(&state, &admin))
+            .set_json(json!({ "version": version, "added": [format!("feature of {}", version)] }))
+            .to_request();
+        let draft: Value = test::call_and_read_body_json(&app, request).await;
+        assert_eq!(draft["status"], "draft");
+    }
+
+    // a version exists only once
+    let request = test::TestRequest::post()
+        .uri("/admin/changelogs")
+        .cookie(token(&state, &admin))
+        .set_json(json!({ "version": "1.0.0" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::CONFLICT);
+
+    let request = test::TestRequest::post().uri("/admin/changelogs/1.0.0/publish").cookie(token(&state, &admin)).to_request();
+    let published: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(published["status"], "published");
+
+    let request = test::TestRequest::post().uri("/admin/changelogs/1.0.0/publish").cookie(token(&state, &admin)).to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::CONFLICT);
+
+    let request = test::TestRequest::get().uri("/changelog/feed").insert_header(("X-API-Key", FEED_API_KEY)).to_request();
+    let feed = String::from_utf8(test::call_and_read_body(&app, request).await.to_vec()).unwrap();
+    assert!(feed.contains("feature of 1.0.0"));
+    assert!(!feed.contains("feature of 1.1.0"));
+
+    let request = test::TestRequest::post().uri("/changelog/feed/token").cookie(token(&state, &alice)).to_request();
+    let feed_token: Value = test::call_and_read_body_json(&app, request).await;
+    let request = test::TestRequest::get().uri(&format!("/changelog/feed?token={}", feed_token["token"].as_str().unwrap())).to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn the_feed_requires_a_key_or_token() {
+    let state = test_state();
+    let app = test::init_service(App::new().app_data(Data::new(state)).service(changelog_feed)).await;
+
+    let request = test::TestRequest::get().uri("/changelog/feed").insert_header(("X-API-Key", "wrong")).to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::UNAUTHORIZED);
+
+    let request = test::TestRequest::get().uri("/changelog/feed?token=unknown").to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::UNAUTHORIZED);
+}
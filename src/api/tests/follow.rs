@@ -0,0 +1,35 @@
+use actix_web::{App, test};
+use actix_web::http::StatusCode;
+use actix_web::web::Data;
+use futures_util::future::join;
+use serde_json::Value;
+use crate::api::follow::{follow, followers};
+use crate::api::tests::{insert_user, test_state, token};
+use crate::model::user::CreateUser;
+
+#[actix_web::test]
+async fn concurrent_follows_count_once() {
+    let state = test_state();
+    let alice = insert_user(&state, "alice").await;
+    let bot = state.db.user().insert(CreateUser {
+        username: String::from("bot"),
+        password: String::from("password"),
+        is_bot: true,
+        description: String::new(),
+    }).await.unwrap();
+    let app = test::init_service(App::new()
+        .app_data(Data::new(state.clone()))
+        .service(follow)
+        .service(followers)).await;
+
+    let first = test::TestRequest::post().uri("/alice/follow/bot").cookie(token(&state, &alice)).to_request();
+    let second = test::TestRequest::post().uri("/alice/follow/bot").cookie(token(&state, &alice)).to_request();
+    let (first, second) = join(test::call_service(&app, first), test::call_service(&app, second)).await;
+    let mut statuses = vec![first.status(), second.status()];
+    statuses.sort();
+    assert_eq!(statuses, vec![StatusCode::OK, StatusCode::CONFLICT]);
+
+    let request = test::TestRequest::get().uri("/bot/followers").cookie(token(&state, &bot)).to_request();
+    let follows: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(follows.as_array().unwrap().len(), 1);
+}
@@ -0,0 +1,109 @@
+use actix_web::{App, test};
+use actix_web::http::StatusCode;
+use actix_web::web::Data;
+use serde_json::Value;
+use crate::api::tests::{insert_user, test_state, token};
+use crate::api::user::{accept_friendship, get_friends, incoming_friend_requests, post_friendship};
+use crate::model::restriction::{Restriction, RestrictionKind};
+
+// the names of the friends or requests in a response
+fn user_names(friends: &Value) -> Vec<&str> {
+    friends.as_array()
+        .unwrap()
+        .iter()
+        .map(|friend| friend["user_name"].as_str().unwrap())
+        .collect()
+}
+
+#[actix_web::test]
+async fn accepted_requests_make_friends() {
+    let state = test_state();
+    let alice = insert_user(&state, "alice").await;
+    let bob = insert_user(&state, "bob").await;
+    let app = test::init_service(App::new()
+        .app_data(Data::new(state.clone()))
+        .service(post_friendship)
+        .service(accept_friendship)
+        .service(incoming_friend_requests)
+        .service(get_friends)).await;
+
+    let request = test::TestRequest::post().uri("/alice/friendship/bob").cookie(token(&state, &alice)).to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::OK);
+
+    let request = test::TestRequest::get().uri("/bob/friendRequests/incoming").cookie(token(&state, &bob)).to_request();
+    let requests: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(user_names(&requests), vec!["alice"]);
+
+    let request = test::TestRequest::post().uri("/bob/friendship/alice/accept").cookie(token(&state, &bob)).to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::OK);
+
+    let request = test::TestRequest::get().uri("/alice/friends").cookie(token(&state, &alice)).to_request();
+    let friends: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(user_names(&friends), vec!["bob"]);
+}
+
+#[actix_web::test]
+async fn a_request_in_the_other_direction_is_accepted() {
+    let state = test_state();
+    let alice = insert_user(&state, "alice").await;
+    let bob = insert_user(&state, "bob").await;
+    let app = test::init_service(App::new()
+        .app_data(Data::new(state.clone()))
+        .service(post_friendship)
+        .service(get_friends)).await;
+
+    let request = test::TestRequest::post().uri("/alice/friendship/bob").cookie(token(&state, &alice)).to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::OK);
+
+    // asking again is a conflict, asking back accepts
+    let request = test::TestRequest::post().uri("/alice/friendship/bob").cookie(token(&state, &alice)).to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::CONFLICT);
+
+    let request = test::TestRequest::post().uri("/bob/friendship/alice").cookie(token(&state, &bob)).to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::OK);
+
+    let request = test::TestRequest::get().uri("/bob/friends").cookie(token(&state, &bob)).to_request();
+    let friends: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(user_names(&friends), vec!["alice"]);
+}
+
+#[actix_web::test]
+async fn requests_are_sent_only_by_the_user_itself() {
+    let state = test_state();
+    let alice = insert_user(&state, "alice").await;
+    insert_user(&state, "bob").await;
+    let app = test::init_service(App::new().app_data(Data::new(state.clone())).service(post_friendship)).await;
+
+    let request = test::TestRequest::post().uri("/bob/friendship/alice").cookie(token(&state, &alice)).to_request();
+
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn users_can_not_befriend_themselves() {
+    let state = test_state();
+    let alice = insert_user(&state, "alice").await;
+    let app = test::init_service(App::new()
+        .app_data(Data::new(state.clone()))
+        .service(post_friendship)
+        .service(accept_friendship)).await;
+
+    let request = test::TestRequest::post().uri("/alice/friendship/alice").cookie(token(&state, &alice)).to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::BAD_REQUEST);
+
+    let request = test::TestRequest::post().uri("/alice/friendship/alice/accept").cookie(token(&state, &alice)).to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn blocked_users_can_not_send_requests() {
+    let state = test_state();
+    let alice = insert_user(&state, "alice").await;
+    let bob = insert_user(&state, "bob").await;
+    state.db.restriction().insert(Restriction::new(bob.id, alice.id, RestrictionKind::Block)).await.unwrap();
+    let app = test::init_service(App::new().app_data(Data::new(state.clone())).service(post_friendship)).await;
+
+    let request = test::TestRequest::post().uri("/alice/friendship/bob").cookie(token(&state, &alice)).to_request();
+
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::FORBIDDEN);
+}
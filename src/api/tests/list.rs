@@ -0,0 +1,209 @@
+use actix_web::{App, test};
+use actix_web::http::{header, StatusCode};
+use actix_web::web::Data;
+use serde_json::{json, Value};
+use crate::api::list::{add_entry, create_list, get_list, get_list_by_id, put_list, share_list, shared_lists, update_entry};
+use crate::api::list_revision::{get_revisions, restore_revision};
+use crate::api::tests::{insert_user, test_state, token};
+use crate::model::friendship::{Friendship, FriendshipStatus};
+use crate::model::list::{List, ListEntry, SelectListsByOwner};
+
+#[actix_web::test]
+async fn entries_are_added_to_the_expected_version() {
+    let state = test_state();
+    let alice = insert_user(&state, "alice").await;
+    let app = test::init_service(App::new()
+        .app_data(Data::new(state.clone()))
+        .service(create_list)
+        .service(add_entry)
+        .service(update_entry)
+        .service(get_list_by_id)).await;
+
+    let request = test::TestRequest::post().uri("/alice/lists").cookie(token(&state, &alice)).set_json(json!({ "name": "groceries" })).to_request();
+    let list: Value = test::call_and_read_body_json(&app, request).await;
+    let list_id = list["id"].as_str().unwrap();
+    let version = list["version"].as_u64().unwrap();
+
+    let request = test::TestRequest::post()
+        .uri(&format!("/alice/lists/{}/entries", list_id))
+        .cookie(token(&state, &alice))
+        .insert_header((header::IF_MATCH, format!("\"{}\"", version)))
+        .set_json(json!({ "title": "milk" }))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get(header::ETAG).unwrap(), format!("\"{}\"", version + 1).as_str());
+
+    // the list changed since `version`
+    let request = test::TestRequest::post()
+        .uri(&format!("/alice/lists/{}/entries", list_id))
+        .cookie(token(&state, &alice))
+        .insert_header((header::IF_MATCH, format!("\"{}\"", version)))
+        .set_json(json!({ "title": "bread" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::PRECONDITION_FAILED);
+
+    let request = test::TestRequest::get().uri(&format!("/alice/lists/{}", list_id)).cookie(token(&state, &alice)).to_request();
+    let list: Value = test::call_and_read_body_json(&app, request).await;
+    let entries = list["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["title"], "milk");
+
+    let request = test::TestRequest::put()
+        .uri(&format!("/alice/lists/{}/entries/{}", list_id, entries[0]["id"].as_str().unwrap()))
+        .cookie(token(&state, &alice))
+        .set_json(json!({ "checked": true }))
+        .to_request();
+    let list: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(list["entries"][0]["checked"], true);
+}
+
+#[actix_web::test]
+async fn replacing_all_entries_requires_a_version() {
+    let state = test_state();
+    let alice = insert_user(&state, "alice").await;
+    let app = test::init_service(App::new()
+        .app_data(Data::new(state.clone()))
+        .service(create_list)
+        .service(put_list)).await;
+
+    let request = test::TestRequest::post().uri("/alice/lists").cookie(token(&state, &alice)).set_json(json!({ "name": "groceries" })).to_request();
+    let list: Value = test::call_and_read_body_json(&app, request).await;
+    let version = list["version"].as_u64().unwrap();
+    let entries = json!({ "entries": [{ "title": "milk" }] });
+
+    let request = test::TestRequest::put().uri("/alice/list").cookie(token(&state, &alice)).set_json(&entries).to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::PRECONDITION_REQUIRED);
+
+    let request = test::TestRequest::put()
+        .uri("/alice/list")
+        .cookie(token(&state, &alice))
+        .insert_header((header::IF_MATCH, format!("\"{}\"", version)))
+        .set_json(&entries)
+        .to_request();
+    let list: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(list["entries"][0]["title"], "milk");
+}
+
+#[actix_web::test]
+async fn lists_are_shared_with_friends_only() {
+    let state = test_state();
+    let alice = insert_user(&state, "alice").await;
+    let bob = insert_user(&state, "bob").await;
+    insert_user(&state, "carol").await;
+    state.db.friendship().insert(Friendship::new(alice.id, bob.id, FriendshipStatus::Accepted)).await.unwrap();
+    let app = test::init_service(App::new()
+        .app_data(Data::new(state.clone()))
+        .service(create_list)
+        .service(get_list_by_id)
+        .service(share_list)
+        .service(shared_lists)).await;
+
+    let request = test::TestRequest::post().uri("/alice/lists").cookie(token(&state, &alice)).set_json(json!({ "name": "holidays" })).to_request();
+    let list: Value = test::call_and_read_body_json(&app, request).await;
+    let list_id = list["id"].as_str().unwrap();
+
+    // being a friend is not enough to read it
+    let request = test::TestRequest::get().uri(&format!("/alice/lists/{}", list_id)).cookie(token(&state, &bob)).to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::UNAUTHORIZED);
+
+    let request = test::TestRequest::put()
+        .uri(&format!("/alice/lists/{}/shares/carol", list_id))
+        .cookie(token(&state, &alice))
+        .set_json(json!({ "permission": "view" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::FORBIDDEN);
+
+    let request = test::TestRequest::put()
+        .uri(&format!("/alice/lists/{}/shares/bob", list_id))
+        .cookie(token(&state, &alice))
+        .set_json(json!({ "permission": "edit" }))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::OK);
+
+    let request = test::TestRequest::get().uri("/bob/sharedLists").cookie(token(&state, &bob)).to_request();
+    let shared: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(shared[0]["owner"], "alice");
+    assert_eq!(shared[0]["permission"], "edit");
+    assert_eq!(shared[0]["name"], "holidays");
+
+    let request = test::TestRequest::get().uri(&format!("/alice/lists/{}", list_id)).cookie(token(&state, &bob)).to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn revisions_are_paged_newest_first() {
+    let state = test_state();
+    let alice = insert_user(&state, "alice").await;
+    let app = test::init_service(App::new()
+        .app_data(Data::new(state.clone()))
+        .service(create_list)
+        .service(add_entry)
+        .service(get_revisions)).await;
+
+    let request = test::TestRequest::post().uri("/alice/lists").cookie(token(&state, &alice)).set_json(json!({ "name": "groceries" })).to_request();
+    let list: Value = test::call_and_read_body_json(&app, request).await;
+    let list_id = list["id"].as_str().unwrap();
+    let version = list["version"].as_u64().unwrap();
+
+    for (offset, title) in ["milk", "bread", "eggs"].iter().enumerate() {
+        let request = test::TestRequest::post()
+            .uri(&format!("/alice/lists/{}/entries", list_id))
+            .cookie(token(&state, &alice))
+            .insert_header((header::IF_MATCH, format!("\"{}\"", version + offset as u64)))
+            .set_json(json!({ "title": title }))
+            .to_request();
+        assert_eq!(test::call_service(&app, request).await.status(), StatusCode::OK);
+    }
+
+    let request = test::TestRequest::get().uri(&format!("/alice/lists/{}/revisions?offset=1&limit=2", list_id)).cookie(token(&state, &alice)).to_request();
+    let revisions: Value = test::call_and_read_body_json(&app, request).await;
+    let versions = revisions.as_array().unwrap().iter().map(|revision| revision["version"].as_u64().unwrap()).collect::<Vec<_>>();
+    assert_eq!(versions, vec![version + 2, version + 1]);
+}
+
+#[actix_web::test]
+async fn the_version_before_the_first_recorded_change_can_be_restored() {
+    let state = test_state();
+    let alice = insert_user(&state, "alice").await;
+    // created before revisions were recorded, so it has none
+    let list = List::new(alice.id, String::from("groceries"), vec![ListEntry::new(String::from("milk")), ListEntry::new(String::from("bread"))]);
+    let list = state.db.list().insert(list).await.unwrap();
+    let app = test::init_service(App::new()
+        .app_data(Data::new(state.clone()))
+        .service(put_list)
+        .service(restore_revision)
+        .service(get_list_by_id)).await;
+
+    let request = test::TestRequest::put()
+        .uri("/alice/list")
+        .cookie(token(&state, &alice))
+        .insert_header((header::IF_MATCH, format!("\"{}\"", list.version)))
+        .set_json(json!({ "entries": [] }))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::OK);
+
+    let request = test::TestRequest::post()
+        .uri(&format!("/alice/lists/{}/revisions/{}/restore", list.id.to_hex(), list.version))
+        .cookie(token(&state, &alice))
+        .to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::OK);
+
+    let request = test::TestRequest::get().uri(&format!("/alice/lists/{}", list.id.to_hex())).cookie(token(&state, &alice)).to_request();
+    let restored: Value = test::call_and_read_body_json(&app, request).await;
+    let titles = restored["entries"].as_array().unwrap().iter().map(|entry| entry["title"].as_str().unwrap()).collect::<Vec<_>>();
+    assert_eq!(titles, vec!["milk", "bread"]);
+}
+
+#[actix_web::test]
+async fn reading_a_missing_default_list_does_not_create_it() {
+    let state = test_state();
+    let alice = insert_user(&state, "alice").await;
+    let bob = insert_user(&state, "bob").await;
+    state.db.friendship().insert(Friendship::new(alice.id, bob.id, FriendshipStatus::Accepted)).await.unwrap();
+    let app = test::init_service(App::new().app_data(Data::new(state.clone())).service(get_list)).await;
+
+    let request = test::TestRequest::get().uri("/alice/list").cookie(token(&state, &bob)).to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::NOT_FOUND);
+    assert!(state.db.list().select(&SelectListsByOwner { owner: alice.id }).await.unwrap().is_empty());
+}
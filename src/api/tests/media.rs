@@ -0,0 +1,30 @@
+use actix_web::{App, test};
+use actix_web::http::StatusCode;
+use actix_web::web::Data;
+use crate::api::media::post;
+use crate::api::tests::{insert_user, test_state, token};
+use crate::model::friendship::{Friendship, FriendshipStatus};
+
+#[actix_web::test]
+async fn posts_are_served_to_friends_only() {
+    let state = test_state();
+    let alice = insert_user(&state, "alice").await;
+    let bob = insert_user(&state, "bob").await;
+    let carol = insert_user(&state, "carol").await;
+    state.db.friendship().insert(Friendship::new(alice.id, bob.id, FriendshipStatus::Accepted)).await.unwrap();
+    std::fs::create_dir_all(format!("{}alice", state.data_directory)).unwrap();
+    std::fs::write(format!("{}alice/post.jpeg", state.data_directory), b"post").unwrap();
+    let app = test::init_service(App::new().app_data(Data::new(state.clone())).service(post)).await;
+
+    let request = test::TestRequest::get().uri("/alice/post.jpeg").cookie(token(&state, &bob)).to_request();
+    assert_eq!(test::call_and_read_body(&app, request).await, "post");
+
+    let request = test::TestRequest::get().uri("/alice/post.jpeg").cookie(token(&state, &carol)).to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::UNAUTHORIZED);
+
+    // only posts, nothing else of the user directory
+    let request = test::TestRequest::get().uri("/alice/..%2F..%2Fetc%2Fpasswd").cookie(token(&state, &alice)).to_request();
+    assert_eq!(test::call_service(&app, request).await.status(), StatusCode::NOT_FOUND);
+
+    std::fs::remove_dir_all(&state.data_directory).unwrap();
+}
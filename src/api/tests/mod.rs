@@ -0,0 +1,57 @@
+// handler tests against the in memory database, no MongoDB needed
+use std::sync::Arc;
+use actix_web::cookie::Cookie;
+use hmac::digest::KeyInit;
+use hmac::Hmac;
+use jwt::SignWithKey;
+use sha2::Sha256;
+use uuid::Uuid;
+use crate::database::memory::MemoryDatabaseContext;
+use crate::middleware::TokenClaims;
+use crate::model::states::app_state::AppState;
+use crate::model::states::list_sync::ListSync;
+use crate::model::user::{CreateUser, User};
+
+mod user;
+mod media;
+mod friendship;
+mod follow;
+mod list;
+mod changelog;
+mod takeout;
+
+pub const FEED_API_KEY: &str = "feed-api-key";
+
+// every test gets its own database and directories
+pub fn test_state() -> AppState {
+    let root = std::env::temp_dir().join(format!("image_server_test_{}", Uuid::new_v4().simple()));
+
+    AppState {
+        ip_port_tuple: (String::from("127.0.0.1"), 0),
+        jwt_secret: String::from("test-secret"),
+        data_directory: format!("{}/data/", root.display()),
+        export_directory: format!("{}/exports/", root.display()),
+        feed_api_key: Some(String::from(FEED_API_KEY)),
+        db: Arc::new(MemoryDatabaseContext::default()),
+        list_sync: ListSync::default(),
+    }
+}
+
+pub async fn insert_user(state: &AppState, name: &str) -> User {
+    let create_user = CreateUser {
+        username: String::from(name),
+        password: String::from("password"),
+        is_bot: false,
+        description: String::new(),
+    };
+
+    state.db.user().insert(create_user).await.expect("user not inserted")
+}
+
+// the cookie the login handler sets
+pub fn token(state: &AppState, user: &User) -> Cookie<'static> {
+    let key = Hmac::<Sha256>::new_from_slice(state.jwt_secret.as_bytes()).unwrap();
+    let token = TokenClaims { id: user.id }.sign_with_key(&key).unwrap();
+
+    Cookie::new("token", token)
+}
@@ -0,0 +1,60 @@
+use actix_web::{App, test};
+use actix_web::web::Data;
+use chrono::{Duration, Utc};
+use serde_json::Value;
+use crate::api::takeout::{export, export_status};
+use crate::api::tests::{insert_user, test_state, token};
+use crate::model::export::{ExportJob, ExportStatus, MAX_EXPORTS_PER_USER, SelectExportsByUser, UpdateExportStatus};
+
+#[actix_web::test]
+async fn lost_exports_count_as_failed() {
+    let state = test_state();
+    let alice = insert_user(&state, "alice").await;
+    let mut job = ExportJob::new(alice.id);
+    // the server restarted while the job was pending
+    job.expires_at = Some(Utc::now() - Duration::minutes(1));
+    let job = state.db.export().insert(job).await.unwrap();
+    let app = test::init_service(App::new().app_data(Data::new(state.clone())).service(export_status)).await;
+
+    let request = test::TestRequest::get().uri(&format!("/alice/export/{}", job.id.to_hex())).cookie(token(&state, &alice)).to_request();
+    let information: Value = test::call_and_read_body_json(&app, request).await;
+
+    assert_eq!(information["status"], "failed");
+    assert_eq!(information["download"], Value::Null);
+}
+
+#[actix_web::test]
+async fn a_pending_export_is_returned_instead_of_starting_another() {
+    let state = test_state();
+    let alice = insert_user(&state, "alice").await;
+    let job = state.db.export().insert(ExportJob::new(alice.id)).await.unwrap();
+    let app = test::init_service(App::new().app_data(Data::new(state.clone())).service(export)).await;
+
+    let request = test::TestRequest::post().uri("/alice/export").cookie(token(&state, &alice)).to_request();
+    let information: Value = test::call_and_read_body_json(&app, request).await;
+
+    assert_eq!(information["id"], job.id.to_hex());
+    assert_eq!(state.db.export().select(&SelectExportsByUser { user_id: alice.id }).await.unwrap().len(), 1);
+}
+
+#[actix_web::test]
+async fn the_oldest_archive_makes_room_for_a_new_export() {
+    let state = test_state();
+    let alice = insert_user(&state, "alice").await;
+    let mut finished = vec![];
+    for _ in 0..MAX_EXPORTS_PER_USER {
+        let job = state.db.export().insert(ExportJob::new(alice.id)).await.unwrap();
+        state.db.export().update(&UpdateExportStatus { target_id: job.id, status: ExportStatus::Finished }).await.unwrap();
+        finished.push(job.id);
+    }
+    let app = test::init_service(App::new().app_data(Data::new(state.clone())).service(export)).await;
+
+    let request = test::TestRequest::post().uri("/alice/export").cookie(token(&state, &alice)).to_request();
+    let information: Value = test::call_and_read_body_json(&app, request).await;
+
+    let jobs = state.db.export().select(&SelectExportsByUser { user_id: alice.id }).await.unwrap();
+    let ids = jobs.iter().map(|job| job.id).collect::<Vec<_>>();
+    assert_eq!(jobs.len(), MAX_EXPORTS_PER_USER);
+    assert!(!ids.contains(&finished[0]));
+    assert_eq!(information["id"], ids[MAX_EXPORTS_PER_USER - 1].to_hex());
+}
@@ -0,0 +1,47 @@
+use std::path::Path;
+use actix_web::{App, test};
+use actix_web::http::StatusCode;
+use actix_web::web::Data;
+use serde_json::json;
+use crate::api::tests::{insert_user, test_state, token};
+use crate::api::user::{create_user, who_am_i};
+
+#[actix_web::test]
+async fn who_am_i_returns_the_name_of_the_token() {
+    let state = test_state();
+    let user = insert_user(&state, "alice").await;
+    let app = test::init_service(App::new().app_data(Data::new(state.clone())).service(who_am_i)).await;
+
+    let request = test::TestRequest::get().uri("/whoAmI").cookie(token(&state, &user)).to_request();
+    let name: String = test::call_and_read_body_json(&app, request).await;
+
+    assert_eq!(name, "alice");
+}
+
+#[actix_web::test]
+async fn who_am_i_requires_a_token() {
+    let state = test_state();
+    let app = test::init_service(App::new().app_data(Data::new(state)).service(who_am_i)).await;
+
+    let response = test::call_service(&app, test::TestRequest::get().uri("/whoAmI").to_request()).await;
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn create_user_creates_the_information_directory() {
+    let state = test_state();
+    let app = test::init_service(App::new().app_data(Data::new(state.clone())).service(create_user)).await;
+    let body = json!({ "username": "bob", "password": "password", "is_bot": false, "description": "" });
+
+    let response = test::call_service(&app, test::TestRequest::post().uri("/user").set_json(&body).to_request()).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(Path::new(&format!("{}bob/information", state.data_directory)).is_dir());
+
+    // the name is unique, the directory of the first user is kept
+    let response = test::call_service(&app, test::TestRequest::post().uri("/user").set_json(&body).to_request()).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert!(Path::new(&format!("{}bob/information", state.data_directory)).is_dir());
+
+    std::fs::remove_dir_all(&state.data_directory).unwrap();
+}
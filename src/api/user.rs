@@ -1,21 +1,20 @@
-use std::fs::File;
-use std::io::{BufReader, Write};
+use std::collections::HashMap;
 use actix_files::NamedFile;
 use actix_multipart::Multipart;
-use actix_web::{get, HttpRequest, HttpResponse, post, put, Responder};
-use actix_web::web::{Data, Json, Path};
-use futures_util::{TryFutureExt};
+use actix_web::{delete, get, HttpRequest, HttpResponse, post, put, Responder};
+use actix_web::web::{Data, Json, Path, Query};
+use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
-use tokio::io::AsyncWriteExt;
-use crate::api::shared::{GETError, UploadError};
-use crate::api::shared::list::List;
+use crate::api::shared::{GETError, QueryInfo, UploadError};
+use crate::api::shared::access::is_blocked;
 
-use crate::database::repositories::{InsertRepository, SelectRepository, UpdateRepository};
 use crate::middleware::TokenClaims;
-use crate::model::friend::Friend;
-use crate::model::friendship::Friendship;
+use crate::model::close_friend::DeleteCloseFriend;
+use crate::model::follow::{CountFollows, FollowDirection};
+use crate::model::friend::{Friend, FriendRequestError};
+use crate::model::friendship::{AcceptFriendRequest, DeleteFriendRequest, DeleteFriendship, Friendship, FriendshipStatus, RequestDirection, SelectFriendRequests, SelectFriendshipBetween};
 use crate::model::states::app_state::AppState;
-use crate::model::user::{CreateUser, CreateUserError, SelectUserById, SelectUserByName, UpdateUser};
+use crate::model::user::{CreateUser, CreateUserError, SelectUserById, SelectUserByName, SelectUsersByIds, UpdateUser, User};
 use crate::utils::{UploadOptions, write_files_in_directory};
 
 #[derive(Serialize)]
@@ -25,14 +24,18 @@ struct UserNoPassword {
 
 #[derive(Debug, Serialize)]
 pub struct UserProfile {
-    amount_posts: usize,
+    amount_posts: u64,
     description: String,
-    is_bot: bool
+    is_bot: bool,
+    is_public: bool,
+    followers: u64,
+    following: u64
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateDescription {
-    description: String
+    description: String,
+    is_public: Option<bool>
 }
 
 
@@ -40,10 +43,14 @@ pub struct UpdateDescription {
 async fn create_user(app_state: Data<AppState>, body: Json<CreateUser>) -> Result<impl Responder, CreateUserError> {
     let create_user: CreateUser = body.into_inner();
     let repo = app_state.db.user();
-    let created_user = repo.insert(create_user).await?;
+    let information_directory = format!("{}{}/information", app_state.data_directory, create_user.username);
 
-    // todo check if name collides with naming policy of operating system
-    let _ = std::fs::create_dir_all(format!("{}{}/information", app_state.data_directory, created_user.name));
+    // the directory is removed again if the user can not be inserted
+    let created_user = app_state.db.unit_of_work(move |unit| Box::pin(async move {
+        // todo check if name collides with naming policy of operating system
+        unit.create_dir_all(&information_directory)?;
+        repo.insert_in(unit, create_user).await
+    })).await?;
 
     Ok(Json(UserNoPassword { username: created_user.name }))
 }
@@ -58,82 +65,14 @@ pub async fn who_am_i(app_state: Data<AppState>, claims: TokenClaims) -> Result<
     Err(GETError::UserNotFound)
 }
 
-#[put("{user_name}/list")]
-pub async fn put_list(user_name: Path<String>, body: Json<List>, state: Data<AppState>, claims: TokenClaims) -> Result<HttpResponse, UploadError> {
-    let user_name = user_name.into_inner();
-    let send_list: List = body.into_inner();
-
-    let user = state.db.user().select(&SelectUserByName { username: &user_name }).await?;
-    let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
-
-    if user.id != claims.id && !requesting_user.is_admin() {
-        return Err(UploadError::Unauthorized);
-    }
-
-    let destination = format!("{}{}/information/list.json", state.data_directory, user.name);
-
-    let list_as_string = serde_json::ser::to_string_pretty(&send_list)
-        .map_err(|_| UploadError::WritingError)?;
-
-    let mut saved_file: tokio::fs::File = tokio::fs::File::create(&destination).await?;
-    saved_file.write_all(list_as_string.as_bytes()).map_err(|_| UploadError::WritingError).await?;
-
-
-    Ok(HttpResponse::Ok().into())
-}
-
-#[get("{user_name}/list")]
-pub async fn list(user_name: Path<String>, state: Data<AppState>, claims: TokenClaims) -> Result<Json<List>, GETError> {
-    let user_name = user_name.into_inner();
-    let user = state.db.user().select(&SelectUserByName { username: &user_name }).await?;
-    let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
-
-    let are_friends = state.db.friendship().select(&SelectUserById { id: claims.id })
-        .await?
-        .iter()
-        .filter(|friendship| friendship.friend_a == user.id || friendship.friend_b == user.id)
-        .count() > 0;
-
-    if !are_friends && user.id != claims.id && !requesting_user.is_admin() {
-        return Err(GETError::Unauthorized);
-    }
-
-    let path = format!("{}{}/information/list.json", state.data_directory, user.name);
-
-    let file = File::open(&path);
-
-    if file.is_err() {
-        match File::create(&path) {
-            Ok(mut file) => { let _ = file.write_all(b"{\"entries\": []}"); },
-            Err(err) => {
-                log::error!("{}", err);
-                return Err(GETError::CantRead);
-            }
-        }
-    }
-
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-
-    if let Ok(list) = serde_json::from_reader(reader) {
-        return Ok(Json(list));
-    }
-
-    Err(GETError::CantRead)
-}
-
 #[get("/{user_name}/information")]
-pub async fn full_profile_information(user_name: Path<String>, state: Data<AppState>) -> actix_web::Result<Json<UserProfile>, GETError> {
+pub async fn full_profile_information(user_name: Path<String>, state: Data<AppState>, claims: TokenClaims) -> actix_web::Result<Json<UserProfile>, GETError> {
     let user_name = user_name.into_inner();
 
     let user = state.db.user().select(&SelectUserByName { username: &user_name }).await?;
-    let amount_posts = count(&user_name, &state)?;
+    hide_blocked(&user, &state, &claims).await?;
 
-    Ok(Json(UserProfile {
-        amount_posts,
-        description: user.description,
-        is_bot: user.is_bot
-    }))
+    Ok(Json(user_profile(user, &state).await?))
 }
 
 #[put("/{user_name}/information")]
@@ -147,14 +86,14 @@ pub async fn put_user_information(new_description: Json<UpdateDescription>, user
         return Err(UploadError::Unauthorized);
     }
 
-    let updated_user = state.db.user().update(&UpdateUser { target_id: user.id, new_description: new_description.description.clone() }).await?;
-    let amount_posts = count(&user_name, &state)?;
+    let update = UpdateUser {
+        target_id: user.id,
+        new_description: new_description.description.clone(),
+        new_is_public: new_description.is_public
+    };
+    let updated_user = state.db.user().update(&update).await?;
 
-    Ok(Json(UserProfile {
-        amount_posts,
-        description: updated_user.description,
-        is_bot: user.is_bot
-    }))
+    Ok(Json(user_profile(updated_user, &state).await?))
 }
 
 #[post("/{user_name}/avatar")]
@@ -183,26 +122,97 @@ pub async fn upload_avatar(payload: Multipart, req: HttpRequest, user_name: Path
 }
 
 #[post("/{user_a}/friendship/{user_b}")]
-pub async fn post_friendship(users: Path<(String, String)>, state: Data<AppState>, claims: TokenClaims) -> actix_web::Result<HttpResponse, UploadError> {
-    let users = users.into_inner();
+pub async fn post_friendship(users: Path<(String, String)>, state: Data<AppState>, claims: TokenClaims) -> actix_web::Result<HttpResponse, FriendRequestError> {
+    let (user_a, user_b) = select_friendship_users(users.into_inner(), &state, &claims).await?;
 
-    let user_a = state.db.user().select(&SelectUserByName { username: &users.0 }).await?;
-    let user_b = state.db.user().select(&SelectUserByName { username: &users.1 }).await?;
-    let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+    if is_blocked(&state, &user_a, &user_b).await? {
+        return Err(FriendRequestError::Blocked);
+    }
 
-    if user_a.id != claims.id && !requesting_user.is_admin() {
-        return Err(UploadError::Unauthorized);
+    let (user_a, user_b) = (user_a.id, user_b.id);
+    let repo = state.db.friendship();
+
+    // checked and written in one transaction, so two requests can not both pass the check
+    state.db.unit_of_work::<_, FriendRequestError, _>(move |unit| Box::pin(async move {
+        // a pending request in the other direction is accepted instead of creating a second one
+        if let Some(existing) = repo.select_in(unit, &SelectFriendshipBetween { user_a, user_b }).await? {
+            if existing.status == FriendshipStatus::Pending && existing.friend_a == user_b {
+                repo.update_in(unit, &AcceptFriendRequest { requester: user_b, addressee: user_a }).await?;
+                return Ok(());
+            }
+        }
+
+        repo.insert_in(unit, Friendship::new(user_a, user_b, FriendshipStatus::Pending)).await?;
+        Ok(())
+    })).await?;
+
+    Ok(HttpResponse::Ok().into())
+}
+
+// access to media, stories and lists is checked per request, so it is revoked immediately
+#[delete("/{user_a}/friendship/{user_b}")]
+pub async fn delete_friendship(users: Path<(String, String)>, state: Data<AppState>, claims: TokenClaims) -> actix_web::Result<HttpResponse, FriendRequestError> {
+    let (user_a, user_b) = select_friendship_users(users.into_inner(), &state, &claims).await?;
+
+    if !state.db.friendship().delete(&DeleteFriendship { user_a: user_a.id, user_b: user_b.id }).await? {
+        return Err(FriendRequestError::NotFriends);
     }
 
-    let friendship = Friendship { friend_a: user_a.id, friend_b: user_b.id };
-    state.db.friendship().insert(friendship).await?;
+    state.db.close_friend().delete(&DeleteCloseFriend { owner: user_a.id, friend: user_b.id }).await?;
+    state.db.close_friend().delete(&DeleteCloseFriend { owner: user_b.id, friend: user_a.id }).await?;
 
     Ok(HttpResponse::Ok().into())
 }
 
+#[post("/{user_a}/friendship/{user_b}/accept")]
+pub async fn accept_friendship(users: Path<(String, String)>, state: Data<AppState>, claims: TokenClaims) -> actix_web::Result<HttpResponse, FriendRequestError> {
+    let (user_a, user_b) = select_friendship_users(users.into_inner(), &state, &claims).await?;
+
+    if is_blocked(&state, &user_a, &user_b).await? {
+        return Err(FriendRequestError::Blocked);
+    }
+
+    if !state.db.friendship().update(&AcceptFriendRequest { requester: user_b.id, addressee: user_a.id }).await? {
+        return Err(FriendRequestError::RequestNotFound);
+    }
+
+    Ok(HttpResponse::Ok().into())
+}
+
+#[post("/{user_a}/friendship/{user_b}/decline")]
+pub async fn decline_friendship(users: Path<(String, String)>, state: Data<AppState>, claims: TokenClaims) -> actix_web::Result<HttpResponse, FriendRequestError> {
+    let (user_a, user_b) = select_friendship_users(users.into_inner(), &state, &claims).await?;
+
+    if !state.db.friendship().delete(&DeleteFriendRequest { requester: user_b.id, addressee: user_a.id }).await? {
+        return Err(FriendRequestError::RequestNotFound);
+    }
+
+    Ok(HttpResponse::Ok().into())
+}
+
+#[post("/{user_a}/friendship/{user_b}/cancel")]
+pub async fn cancel_friendship(users: Path<(String, String)>, state: Data<AppState>, claims: TokenClaims) -> actix_web::Result<HttpResponse, FriendRequestError> {
+    let (user_a, user_b) = select_friendship_users(users.into_inner(), &state, &claims).await?;
+
+    if !state.db.friendship().delete(&DeleteFriendRequest { requester: user_a.id, addressee: user_b.id }).await? {
+        return Err(FriendRequestError::RequestNotFound);
+    }
+
+    Ok(HttpResponse::Ok().into())
+}
+
+#[get("/{user_name}/friendRequests/incoming")]
+pub async fn incoming_friend_requests(user_name: Path<String>, state: Data<AppState>, claims: TokenClaims) -> actix_web::Result<Json<Vec<Friend>>, GETError> {
+    friend_requests(&user_name.into_inner(), RequestDirection::Incoming, &state, &claims).await
+}
+
+#[get("/{user_name}/friendRequests/outgoing")]
+pub async fn outgoing_friend_requests(user_name: Path<String>, state: Data<AppState>, claims: TokenClaims) -> actix_web::Result<Json<Vec<Friend>>, GETError> {
+    friend_requests(&user_name.into_inner(), RequestDirection::Outgoing, &state, &claims).await
+}
 
 #[get("/{user_name}/friends")]
-pub async fn get_friends(user_name: Path<String>, state: Data<AppState>, claims: TokenClaims) -> actix_web::Result<Json<Vec<Friend>>, GETError> {
+pub async fn get_friends(user_name: Path<String>, query: Query<QueryInfo>, state: Data<AppState>, claims: TokenClaims) -> actix_web::Result<Json<Vec<Friend>>, GETError> {
     let user = state.db.user().select(&SelectUserByName { username: &user_name.into_inner() }).await?;
     let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
 
@@ -211,46 +221,104 @@ pub async fn get_friends(user_name: Path<String>, state: Data<AppState>, claims:
     }
 
     let friendships = state.db.friendship().select(&SelectUserById { id: user.id }).await?;
+    let friendships = query.paginate(friendships);
+
+    Ok(Json(to_friends(&user.id, &friendships, &state).await?))
+}
+
+async fn friend_requests(user_name: &str, direction: RequestDirection, state: &Data<AppState>, claims: &TokenClaims) -> Result<Json<Vec<Friend>>, GETError> {
+    let user = state.db.user().select(&SelectUserByName { username: user_name }).await?;
+    let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+
+    if user.id != claims.id && !requesting_user.is_admin() {
+        return Err(GETError::Unauthorized);
+    }
+
+    let requests = state.db.friendship().select(&SelectFriendRequests { id: user.id, direction }).await?;
+
+    Ok(Json(to_friends(&user.id, &requests, state).await?))
+}
+
+// user_a is the acting user, it must be the requesting one or the requesting one is an admin
+async fn select_friendship_users(users: (String, String), state: &Data<AppState>, claims: &TokenClaims) -> Result<(User, User), FriendRequestError> {
+    let user_a = state.db.user().select(&SelectUserByName { username: &users.0 }).await?;
+    let user_b = state.db.user().select(&SelectUserByName { username: &users.1 }).await?;
+    let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+
+    if user_a.id != claims.id && !requesting_user.is_admin() {
+        return Err(FriendRequestError::Unauthorized);
+    }
+
+    if user_a.id == user_b.id {
+        return Err(FriendRequestError::SelfRequest);
+    }
+
+    Ok((user_a, user_b))
+}
 
+async fn to_friends(user_id: &ObjectId, friendships: &[Friendship], state: &Data<AppState>) -> Result<Vec<Friend>, GETError> {
     let friend_ids = friendships
         .iter()
-        .map(|friendship| if friendship.friend_a == user.id { friendship.friend_b } else { friendship.friend_a })
+        .map(|friendship| if friendship.friend_a == *user_id { friendship.friend_b } else { friendship.friend_a })
         .collect::<Vec<_>>();
 
-    let mut friends = vec![];
+    to_friend_list(friend_ids, state).await
+}
+
+// keeps the order of `friend_ids`, users which do not exist anymore are left out
+pub async fn to_friend_list(friend_ids: Vec<ObjectId>, state: &Data<AppState>) -> Result<Vec<Friend>, GETError> {
+    let mut users = state.db.user().select(&SelectUsersByIds { ids: friend_ids.clone() })
+        .await?
+        .into_iter()
+        .map(|user| (user.id, user))
+        .collect::<HashMap<_, _>>();
 
-    for friend in friend_ids {
-        // potentially do this in parallel
-        let user = state.db.user().select(&SelectUserById { id: friend }).await?;
-        friends.push(Friend {
+    Ok(friend_ids
+        .iter()
+        .filter_map(|id| users.remove(id))
+        .map(|user| Friend {
+            id: user.id.to_hex(),
             user_name: user.name.clone(),
             profile_image: format!("user/{}/avatar", &user.name),
             description: user.description,
             is_bot: user.is_bot,
-            amount_posts: count(&user.name, &state)?
-        });
-    }
-
-    Ok(Json(friends))
+            amount_posts: user.amount_posts
+        })
+        .collect())
 }
 
 
 #[get("/{user_name}/avatar")]
-pub async fn avatar(user_name: Path<String>, state: Data<AppState>) -> actix_web::Result<NamedFile, GETError> {
+pub async fn avatar(user_name: Path<String>, state: Data<AppState>, claims: TokenClaims) -> actix_web::Result<NamedFile, GETError> {
     let user_name = user_name.into_inner();
     let user = state.db.user().select(&SelectUserByName { username: &user_name }).await?;
+    hide_blocked(&user, &state, &claims).await?;
 
     Ok(NamedFile::open(format!("{}{}/information/avatar.jpeg", state.data_directory, user.name))?)
 }
 
-fn count(user_name: &str, app_state: &Data<AppState>) -> Result<usize, GETError> {
-    if let Ok(paths) = std::fs::read_dir(format!("{}{}", &app_state.data_directory, user_name)) {
-        return Ok(paths
-            .filter_map(|file| file.ok())
-            .filter(|a| a.path().is_file())
-            .count()
-        )
+// blocked users do not exist for each other
+async fn hide_blocked(user: &User, state: &Data<AppState>, claims: &TokenClaims) -> Result<(), GETError> {
+    let requesting_user = state.db.user().select(&SelectUserById { id: claims.id }).await?;
+
+    if !requesting_user.is_admin() && is_blocked(state, user, &requesting_user).await? {
+        return Err(GETError::UserNotFound);
     }
 
-    Err(GETError::CantRead)
+    Ok(())
 }
+
+async fn user_profile(user: User, state: &Data<AppState>) -> Result<UserProfile, GETError> {
+    let followers = state.db.follow().select(&CountFollows { id: user.id, direction: FollowDirection::Followers }).await?;
+    let following = state.db.follow().select(&CountFollows { id: user.id, direction: FollowDirection::Following }).await?;
+
+    Ok(UserProfile {
+        amount_posts: user.amount_posts,
+        description: user.description,
+        is_bot: user.is_bot,
+        is_public: user.is_public,
+        followers,
+        following
+    })
+}
+
@@ -1,76 +1,59 @@
-use std::env::VarError;
-use std::fmt::{Display, Formatter};
-use mongodb::{Client, Collection, Database};
-use mongodb::bson::doc;
-use mongodb::options::ClientOptions;
-use crate::database::repositories::friendship_repo::FriendshipRepository;
-use crate::database::repositories::user_repo::UserRepository;
-use crate::model::friendship::Friendship;
-use crate::model::user::User;
-
-#[derive(Clone)]
-pub struct DatabaseContext {
-    _db: Database,
-    users: Collection<User>,
-    friendships: Collection<Friendship>
-}
+use async_trait::async_trait;
+use crate::database::unit_of_work::{UnitFuture, UnitOfWork};
+use crate::database::repositories::{ChangelogRepository, CloseFriendRepository, ExportRepository, FollowRepository, FriendshipRepository, ListRepository, ListRevisionRepository, MediaRepository, RestrictionRepository, SuggestionRepository, UserRepository};
 
-#[derive(Debug)]
-pub enum Error {
-    VarError(std::env::VarError),
-    MongoDBError(mongodb::error::Error)
-}
+// the repositories the handlers work with, backed by mongodb or, in tests, by memory
+#[async_trait]
+pub trait DatabaseContext: Send + Sync {
+    fn user(&self) -> Box<dyn UserRepository>;
 
-impl Display for Error {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", match self {
-            Error::VarError(v) => v.to_string(),
-            Error::MongoDBError(v) => v.to_string()
-        })
-    }
-}
+    fn friendship(&self) -> Box<dyn FriendshipRepository>;
 
-impl From<std::env::VarError> for Error {
-    fn from(value: VarError) -> Self {
-        Error::VarError(value)
-    }
-}
+    fn export(&self) -> Box<dyn ExportRepository>;
 
-impl From<mongodb::error::Error> for Error {
-    fn from(value: mongodb::error::Error) -> Self {
-        Error::MongoDBError(value)
-    }
-}
+    fn suggestion(&self) -> Box<dyn SuggestionRepository>;
 
-impl std::error::Error for Error { }
+    fn follow(&self) -> Box<dyn FollowRepository>;
 
-impl DatabaseContext {
-    pub async fn new() -> Result<Self, Error> {
-        let client_options = ClientOptions::parse(
-            std::env::var("MONGO_URL")?
-        ).await?;
+    fn media(&self) -> Box<dyn MediaRepository>;
 
-        let client = Client::with_options(client_options)?;
+    fn close_friend(&self) -> Box<dyn CloseFriendRepository>;
 
-        let mongodb_name = std::env::var("MONGO_DATABASE")?;
-        let db = client.database(&mongodb_name);
-        db.run_command(doc! { "ping": 1 }, None)
-            .await?;
+    fn restriction(&self) -> Box<dyn RestrictionRepository>;
 
-        log::info!("Established connection the database");
+    fn list(&self) -> Box<dyn ListRepository>;
 
-        Ok(Self {
-            _db: db.clone(),
-            users: db.collection("users"),
-            friendships: db.collection("friendships")
-        })
-    }
+    fn list_revision(&self) -> Box<dyn ListRevisionRepository>;
 
-    pub fn friendship(&self) -> FriendshipRepository { 
-        FriendshipRepository::new(self.friendships.clone()) 
-    }
-    
-    pub fn user(&self) -> UserRepository {
-        UserRepository::new(self.users.clone())
+    fn changelog(&self) -> Box<dyn ChangelogRepository>;
+
+    // a unit with a transaction, when the backend supports them
+    async fn start_unit(&self) -> mongodb::error::Result<UnitOfWork>;
+}
+
+impl dyn DatabaseContext {
+    // runs `work` in a transaction when the database supports them. When it fails, the transaction is aborted
+    // and the directories it created are removed again
+    pub async fn unit_of_work<T, E, F>(&self, work: F) -> Result<T, E>
+    where
+        F: for<'u> FnOnce(&'u mut UnitOfWork) -> UnitFuture<'u, T, E>,
+        E: From<mongodb::error::Error>
+    {
+        let mut unit = self.start_unit().await?;
+
+        let result = work(&mut unit).await;
+        let result = match result {
+            Ok(value) => unit.commit().await.map(|_| value).map_err(E::from),
+            Err(err) => {
+                unit.abort().await;
+                Err(err)
+            }
+        };
+
+        if result.is_err() {
+            unit.compensate();
+        }
+
+        result
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,37 @@
+use std::collections::HashSet;
+use mongodb::{Collection, IndexModel};
+use mongodb::error::ErrorKind;
+use crate::database::repositories::DUPLICATE_KEY;
+
+const INDEX_OPTIONS_CONFLICT: i32 = 85;
+const INDEX_KEY_SPECS_CONFLICT: i32 = 86;
+
+// creates the declared indexes of a collection. Differences to what the database has are logged, not fixed,
+// since dropping an index or deduplicating documents is up to an admin
+pub async fn ensure_indexes<T>(collection: &Collection<T>, declared: Vec<IndexModel>) -> mongodb::error::Result<()> {
+    let mut names = HashSet::from([String::from("_id_")]);
+
+    for index in declared {
+        let name = index.options.as_ref().and_then(|options| options.name.clone()).unwrap_or_default();
+        names.insert(name.clone());
+
+        match collection.create_index(index, None).await {
+            Ok(_) => {},
+            Err(err) => match *err.kind {
+                ErrorKind::Command(ref command_error) if command_error.code == INDEX_OPTIONS_CONFLICT || command_error.code == INDEX_KEY_SPECS_CONFLICT =>
+                    log::warn!("Index \"{}\" of {} differs from its declaration: {}", name, collection.name(), command_error.message),
+                ErrorKind::Command(ref command_error) if command_error.code == DUPLICATE_KEY =>
+                    log::error!("Unique index \"{}\" of {} can not be created, documents share a value: {}", name, collection.name(), command_error.message),
+                _ => return Err(err)
+            }
+        }
+    }
+
+    // listed afterwards, a collection that does not exist yet has no indexes to list
+    let existing = collection.list_index_names().await?;
+    for name in existing.iter().filter(|name| !names.contains(*name)) {
+        log::warn!("Index \"{}\" of {} is not declared", name, collection.name());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use crate::database::memory::MemoryCollection;
+use crate::database::repositories::{ChangelogRepository, DeleteRepository, InsertRepository, SelectRepository, UpdateRepository};
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError, UpdateDatabaseError};
+use crate::model::changelog::{ChangelogEntry, ChangelogStatus, DeleteChangelogDraft, PublishChangelog, SelectChangelog, SelectChangelogs, UpdateChangelog};
+
+pub struct MemoryChangelogRepository {
+    context: MemoryCollection<ChangelogEntry>
+}
+
+impl MemoryChangelogRepository {
+    pub fn new(context: MemoryCollection<ChangelogEntry>) -> Self {
+        Self {
+            context
+        }
+    }
+}
+
+#[async_trait]
+impl InsertRepository<ChangelogEntry, ChangelogEntry, InsertDatabaseError> for MemoryChangelogRepository {
+    async fn insert(&self, data: ChangelogEntry) -> Result<ChangelogEntry, InsertDatabaseError> {
+        // the version is the id, so it can only be inserted once
+        let mut changelogs = self.context.lock();
+        if changelogs.iter().any(|changelog| changelog.version == data.version) {
+            let message = format!("A changelog for version {} already exists", data.version);
+            return Err(InsertDatabaseError::DatabaseError(mongodb::error::Error::custom(message)));
+        }
+        changelogs.push(data.clone());
+
+        Ok(data)
+    }
+}
+
+// newest first
+#[async_trait]
+impl SelectRepository<SelectChangelogs, Vec<ChangelogEntry>, SelectDatabaseError> for MemoryChangelogRepository {
+    async fn select(&self, data: &SelectChangelogs) -> Result<Vec<ChangelogEntry>, SelectDatabaseError> {
+        let mut changelogs = self.context.find_many(|changelog| data.status.map_or(true, |status| changelog.status == status));
+        changelogs.sort_by(|a, b| b.version.cmp(&a.version));
+
+        Ok(changelogs)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectChangelog, Option<ChangelogEntry>, SelectDatabaseError> for MemoryChangelogRepository {
+    async fn select(&self, data: &SelectChangelog) -> Result<Option<ChangelogEntry>, SelectDatabaseError> {
+        Ok(self.context.find_one(|changelog| changelog.version == data.version))
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<UpdateChangelog, Option<ChangelogEntry>, UpdateDatabaseError> for MemoryChangelogRepository {
+    async fn update(&self, data: &UpdateChangelog) -> Result<Option<ChangelogEntry>, UpdateDatabaseError> {
+        Ok(self.context.update_one(|changelog| changelog.version == data.version, |changelog| {
+            if let Some(added) = &data.added {
+                changelog.added = added.clone();
+            }
+            if let Some(fixed) = &data.fixed {
+                changelog.fixed = fixed.clone();
+            }
+            if let Some(changed) = &data.changed {
+                changelog.changed = changed.clone();
+            }
+            changelog.updated_at = Utc::now();
+        }))
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<PublishChangelog, Option<ChangelogEntry>, UpdateDatabaseError> for MemoryChangelogRepository {
+    async fn update(&self, data: &PublishChangelog) -> Result<Option<ChangelogEntry>, UpdateDatabaseError> {
+        let is_draft = |changelog: &ChangelogEntry| changelog.version == data.version && changelog.status == ChangelogStatus::Draft;
+
+        Ok(self.context.update_one(is_draft, |changelog| {
+            let now = Utc::now();
+            changelog.status = ChangelogStatus::Published;
+            changelog.published_at = Some(now);
+            changelog.updated_at = now;
+        }))
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<DeleteChangelogDraft, bool, DeleteDatabaseError> for MemoryChangelogRepository {
+    async fn delete(&self, data: &DeleteChangelogDraft) -> Result<bool, DeleteDatabaseError> {
+        Ok(self.context.delete_one(|changelog| changelog.version == data.version && changelog.status == ChangelogStatus::Draft))
+    }
+}
+
+impl ChangelogRepository for MemoryChangelogRepository {}
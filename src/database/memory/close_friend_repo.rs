@@ -0,0 +1,55 @@
+use async_trait::async_trait;
+use crate::database::memory::MemoryCollection;
+use crate::database::repositories::{CloseFriendRepository, DeleteRepository, InsertRepository, SelectRepository};
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError};
+use crate::model::close_friend::{CloseFriend, DeleteCloseFriend, SelectCloseFriend};
+use crate::model::user::SelectUserById;
+
+pub struct MemoryCloseFriendRepository {
+    context: MemoryCollection<CloseFriend>
+}
+
+impl MemoryCloseFriendRepository {
+    pub fn new(context: MemoryCollection<CloseFriend>) -> Self {
+        Self {
+            context
+        }
+    }
+}
+
+#[async_trait]
+impl InsertRepository<CloseFriend, CloseFriend, InsertDatabaseError> for MemoryCloseFriendRepository {
+    async fn insert(&self, data: CloseFriend) -> Result<CloseFriend, InsertDatabaseError> {
+        if let Some(existing) = self.context.find_one(|close_friend| close_friend.owner == data.owner && close_friend.friend == data.friend) {
+            return Ok(existing);
+        }
+
+        self.context.insert_one(data.clone());
+
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectCloseFriend, Option<CloseFriend>, SelectDatabaseError> for MemoryCloseFriendRepository {
+    async fn select(&self, data: &SelectCloseFriend) -> Result<Option<CloseFriend>, SelectDatabaseError> {
+        Ok(self.context.find_one(|close_friend| close_friend.owner == data.owner && close_friend.friend == data.friend))
+    }
+}
+
+// all close friends of `data.id`
+#[async_trait]
+impl SelectRepository<SelectUserById, Vec<CloseFriend>, SelectDatabaseError> for MemoryCloseFriendRepository {
+    async fn select(&self, data: &SelectUserById) -> Result<Vec<CloseFriend>, SelectDatabaseError> {
+        Ok(self.context.find_many(|close_friend| close_friend.owner == data.id))
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<DeleteCloseFriend, bool, DeleteDatabaseError> for MemoryCloseFriendRepository {
+    async fn delete(&self, data: &DeleteCloseFriend) -> Result<bool, DeleteDatabaseError> {
+        Ok(self.context.delete_one(|close_friend| close_friend.owner == data.owner && close_friend.friend == data.friend))
+    }
+}
+
+impl CloseFriendRepository for MemoryCloseFriendRepository {}
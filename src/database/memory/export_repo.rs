@@ -0,0 +1,81 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use crate::database::memory::MemoryCollection;
+use crate::database::repositories::{ExportRepository, DeleteRepository, InsertRepository, SelectRepository, UpdateRepository};
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError, UpdateDatabaseError};
+use crate::model::export::{DeleteExportById, ExportJob, ExportStatus, SelectExpiredExports, SelectExportById, SelectExportsByUser, UpdateExportStatus};
+
+pub struct MemoryExportRepository {
+    context: MemoryCollection<ExportJob>
+}
+
+impl MemoryExportRepository {
+    pub fn new(context: MemoryCollection<ExportJob>) -> Self {
+        Self {
+            context
+        }
+    }
+}
+
+#[async_trait]
+impl InsertRepository<ExportJob, ExportJob, InsertDatabaseError> for MemoryExportRepository {
+    async fn insert(&self, data: ExportJob) -> Result<ExportJob, InsertDatabaseError> {
+        // checked while holding the lock, like the unique index on pending jobs
+        let mut jobs = self.context.lock();
+        if let Some(pending) = jobs.iter().find(|job| job.user_id == data.user_id && job.status == ExportStatus::Pending) {
+            return Ok(pending.clone());
+        }
+        jobs.push(data.clone());
+
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectExportById, Option<ExportJob>, SelectDatabaseError> for MemoryExportRepository {
+    async fn select(&self, data: &SelectExportById) -> Result<Option<ExportJob>, SelectDatabaseError> {
+        Ok(self.context.find_one(|job| job.id == data.id))
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectExpiredExports, Vec<ExportJob>, SelectDatabaseError> for MemoryExportRepository {
+    async fn select(&self, _: &SelectExpiredExports) -> Result<Vec<ExportJob>, SelectDatabaseError> {
+        let now = Utc::now();
+
+        Ok(self.context.find_many(|job| job.expires_at.is_some_and(|expires_at| expires_at < now)))
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectExportsByUser, Vec<ExportJob>, SelectDatabaseError> for MemoryExportRepository {
+    async fn select(&self, data: &SelectExportsByUser) -> Result<Vec<ExportJob>, SelectDatabaseError> {
+        let mut jobs = self.context.find_many(|job| job.user_id == data.user_id);
+        jobs.sort_by_key(|job| job.created_at);
+
+        Ok(jobs)
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<UpdateExportStatus, (), UpdateDatabaseError> for MemoryExportRepository {
+    async fn update(&self, data: &UpdateExportStatus) -> Result<(), UpdateDatabaseError> {
+        self.context.update_one(|job| job.id == data.target_id, |job| {
+            job.status = data.status;
+            job.expires_at = data.expires_at();
+        });
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<DeleteExportById, (), DeleteDatabaseError> for MemoryExportRepository {
+    async fn delete(&self, data: &DeleteExportById) -> Result<(), DeleteDatabaseError> {
+        self.context.delete_one(|job| job.id == data.id);
+
+        Ok(())
+    }
+}
+
+impl ExportRepository for MemoryExportRepository {}
@@ -0,0 +1,74 @@
+use async_trait::async_trait;
+use mongodb::bson::oid::ObjectId;
+use crate::database::memory::MemoryCollection;
+use crate::database::repositories::{FollowRepository, DeleteRepository, InsertRepository, SelectRepository};
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError};
+use crate::model::follow::{CountFollows, DeleteFollow, Follow, FollowDirection, SelectFollow, SelectFollows};
+
+pub struct MemoryFollowRepository {
+    context: MemoryCollection<Follow>
+}
+
+impl MemoryFollowRepository {
+    pub fn new(context: MemoryCollection<Follow>) -> Self {
+        Self {
+            context
+        }
+    }
+}
+
+fn in_direction(follow: &Follow, id: &ObjectId, direction: FollowDirection) -> bool {
+    match direction {
+        FollowDirection::Followers => follow.followee == *id,
+        FollowDirection::Following => follow.follower == *id
+    }
+}
+
+#[async_trait]
+impl InsertRepository<Follow, bool, InsertDatabaseError> for MemoryFollowRepository {
+    async fn insert(&self, data: Follow) -> Result<bool, InsertDatabaseError> {
+        // checked while holding the lock, like the unique index on the pair
+        let mut follows = self.context.lock();
+        if follows.iter().any(|follow| follow.follower == data.follower && follow.followee == data.followee) {
+            return Ok(false);
+        }
+        follows.push(data);
+
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectFollow, Option<Follow>, SelectDatabaseError> for MemoryFollowRepository {
+    async fn select(&self, data: &SelectFollow) -> Result<Option<Follow>, SelectDatabaseError> {
+        Ok(self.context.find_one(|follow| follow.follower == data.follower && follow.followee == data.followee))
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectFollows, Vec<Follow>, SelectDatabaseError> for MemoryFollowRepository {
+    async fn select(&self, data: &SelectFollows) -> Result<Vec<Follow>, SelectDatabaseError> {
+        Ok(self.context.find_many(|follow| in_direction(follow, &data.id, data.direction)))
+    }
+}
+
+#[async_trait]
+impl SelectRepository<CountFollows, u64, SelectDatabaseError> for MemoryFollowRepository {
+    async fn select(&self, data: &CountFollows) -> Result<u64, SelectDatabaseError> {
+        let count = self.context.lock()
+            .iter()
+            .filter(|follow| in_direction(follow, &data.id, data.direction))
+            .count();
+
+        Ok(u64::try_from(count).unwrap_or(u64::MAX))
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<DeleteFollow, bool, DeleteDatabaseError> for MemoryFollowRepository {
+    async fn delete(&self, data: &DeleteFollow) -> Result<bool, DeleteDatabaseError> {
+        Ok(self.context.delete_one(|follow| follow.follower == data.follower && follow.followee == data.followee))
+    }
+}
+
+impl FollowRepository for MemoryFollowRepository {}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use async_trait::async_trait;
+use mongodb::bson::oid::ObjectId;
+use crate::database::memory::MemoryCollection;
+use crate::database::repositories::{FriendshipRepository, DeleteRepository, InsertRepository, SelectRepository, TransactionalInsertRepository, TransactionalSelectRepository, TransactionalUpdateRepository, UpdateRepository};
+use crate::database::unit_of_work::UnitOfWork;
+use crate::model::{DeleteDatabaseError, UpdateDatabaseError};
+use crate::model::friend::{CreateFriendshipError, FetchFriendshipError};
+use crate::model::friendship::{AcceptFriendRequest, DeleteFriendRequest, DeleteFriendship, Friendship, FriendshipStatus, normalized_pair, RequestDirection, SelectFriendRequests, SelectFriendshipBetween};
+use crate::model::suggestion::{MutualFriends, SelectSuggestions};
+use crate::model::user::SelectUserById;
+
+pub struct MemoryFriendshipRepository {
+    context: MemoryCollection<Friendship>
+}
+
+impl MemoryFriendshipRepository {
+    pub fn new(context: MemoryCollection<Friendship>) -> Self {
+        Self {
+            context
+        }
+    }
+
+    // oldest first, so pages stay stable
+    fn find_many(&self, filter: impl Fn(&Friendship) -> bool) -> Vec<Friendship> {
+        let mut friendships = self.context.find_many(filter);
+        friendships.sort_by_key(|friendship| friendship.id);
+
+        friendships
+    }
+}
+
+fn is_pair(friendship: &Friendship, user_a: ObjectId, user_b: ObjectId) -> bool {
+    (friendship.member_low, friendship.member_high) == normalized_pair(user_a, user_b)
+}
+
+fn is_request(friendship: &Friendship, requester: &ObjectId, addressee: &ObjectId) -> bool {
+    friendship.friend_a == *requester && friendship.friend_b == *addressee && friendship.status == FriendshipStatus::Pending
+}
+
+// the other member of an accepted friendship of `id`
+fn accepted_friend(friendship: &Friendship, id: &ObjectId) -> Option<ObjectId> {
+    if friendship.status == FriendshipStatus::Pending {
+        return None;
+    }
+
+    match (friendship.friend_a == *id, friendship.friend_b == *id) {
+        (true, _) => Some(friendship.friend_b),
+        (_, true) => Some(friendship.friend_a),
+        _ => None
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectUserById, Vec<Friendship>, FetchFriendshipError> for MemoryFriendshipRepository {
+    async fn select(&self, data: &SelectUserById) -> Result<Vec<Friendship>, FetchFriendshipError> {
+        Ok(self.find_many(|friendship| accepted_friend(friendship, &data.id).is_some()))
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectFriendRequests, Vec<Friendship>, FetchFriendshipError> for MemoryFriendshipRepository {
+    async fn select(&self, data: &SelectFriendRequests) -> Result<Vec<Friendship>, FetchFriendshipError> {
+        Ok(self.find_many(|friendship| {
+            let member = match data.direction {
+                RequestDirection::Incoming => friendship.friend_b,
+                RequestDirection::Outgoing => friendship.friend_a
+            };
+
+            member == data.id && friendship.status == FriendshipStatus::Pending
+        }))
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectFriendshipBetween, Option<Friendship>, FetchFriendshipError> for MemoryFriendshipRepository {
+    async fn select(&self, data: &SelectFriendshipBetween) -> Result<Option<Friendship>, FetchFriendshipError> {
+        Ok(self.context.find_one(|friendship| is_pair(friendship, data.user_a, data.user_b)))
+    }
+}
+
+#[async_trait]
+impl TransactionalSelectRepository<SelectFriendshipBetween, Option<Friendship>, FetchFriendshipError> for MemoryFriendshipRepository {
+    async fn select_in(&self, _: &mut UnitOfWork, data: &SelectFriendshipBetween) -> Result<Option<Friendship>, FetchFriendshipError> {
+        self.select(data).await
+    }
+}
+
+// ranks every friend of a friend by the amount of friends shared with `data.id`
+#[async_trait]
+impl SelectRepository<SelectSuggestions, Vec<MutualFriends>, FetchFriendshipError> for MemoryFriendshipRepository {
+    async fn select(&self, data: &SelectSuggestions) -> Result<Vec<MutualFriends>, FetchFriendshipError> {
+        let friendships = self.context.lock().clone();
+        let mut mutual_friends = HashMap::<ObjectId, u32>::new();
+
+        for friend in friendships.iter().filter_map(|friendship| accepted_friend(friendship, &data.id)) {
+            for candidate in friendships.iter().filter_map(|friendship| accepted_friend(friendship, &friend)) {
+                *mutual_friends.entry(candidate).or_default() += 1;
+            }
+        }
+
+        let mut suggestions = mutual_friends.into_iter()
+            .filter(|(candidate, _)| !data.excluded.contains(candidate))
+            .map(|(user_id, mutual_friends)| MutualFriends { user_id, mutual_friends })
+            .collect::<Vec<_>>();
+        suggestions.sort_by(|a, b| b.mutual_friends.cmp(&a.mutual_friends).then(a.user_id.cmp(&b.user_id)));
+        suggestions.truncate(usize::try_from(data.limit).unwrap_or_default());
+
+        Ok(suggestions)
+    }
+}
+
+#[async_trait]
+impl InsertRepository<Friendship, Friendship, CreateFriendshipError> for MemoryFriendshipRepository {
+    async fn insert(&self, data: Friendship) -> Result<Friendship, CreateFriendshipError> {
+        // checked while holding the lock, like the unique index on the pair
+        let mut friendships = self.context.lock();
+        if let Some(existing) = friendships.iter().find(|friendship| is_pair(friendship, data.friend_a, data.friend_b)) {
+            return Err(match existing.status {
+                FriendshipStatus::Pending => CreateFriendshipError::RequestPending,
+                FriendshipStatus::Accepted => CreateFriendshipError::AlreadyFriends
+            });
+        }
+        friendships.push(data.clone());
+
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl TransactionalInsertRepository<Friendship, Friendship, CreateFriendshipError> for MemoryFriendshipRepository {
+    async fn insert_in(&self, _: &mut UnitOfWork, data: Friendship) -> Result<Friendship, CreateFriendshipError> {
+        self.insert(data).await
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<AcceptFriendRequest, bool, UpdateDatabaseError> for MemoryFriendshipRepository {
+    async fn update(&self, data: &AcceptFriendRequest) -> Result<bool, UpdateDatabaseError> {
+        let accepted = self.context.update_one(
+            |friendship| is_request(friendship, &data.requester, &data.addressee),
+            |friendship| friendship.status = FriendshipStatus::Accepted
+        );
+
+        Ok(accepted.is_some())
+    }
+}
+
+#[async_trait]
+impl TransactionalUpdateRepository<AcceptFriendRequest, bool, UpdateDatabaseError> for MemoryFriendshipRepository {
+    async fn update_in(&self, _: &mut UnitOfWork, data: &AcceptFriendRequest) -> Result<bool, UpdateDatabaseError> {
+        self.update(data).await
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<DeleteFriendRequest, bool, DeleteDatabaseError> for MemoryFriendshipRepository {
+    async fn delete(&self, data: &DeleteFriendRequest) -> Result<bool, DeleteDatabaseError> {
+        Ok(self.context.delete_one(|friendship| is_request(friendship, &data.requester, &data.addressee)))
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<DeleteFriendship, bool, DeleteDatabaseError> for MemoryFriendshipRepository {
+    async fn delete(&self, data: &DeleteFriendship) -> Result<bool, DeleteDatabaseError> {
+        Ok(self.context.delete_one(|friendship| is_pair(friendship, data.user_a, data.user_b) && friendship.status != FriendshipStatus::Pending))
+    }
+}
+
+impl FriendshipRepository for MemoryFriendshipRepository {}
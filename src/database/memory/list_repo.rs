@@ -0,0 +1,200 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use crate::database::memory::MemoryCollection;
+use crate::database::repositories::{ListRepository, DeleteRepository, InsertRepository, SelectRepository, UpdateRepository};
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError, UpdateDatabaseError};
+use crate::model::list::{DeleteListById, DeleteListEntry, DeleteSubEntry, InsertListEntry, InsertSubEntry, List, ListEntry, ListTarget, ReplaceListEntries, ReplaceListShares, SelectListById, SelectListByOwner, SelectListsByOwner, SelectSharedLists, UpdateListDetails, UpdateListEntry, UpdateSubEntry};
+
+pub struct MemoryListRepository {
+    context: MemoryCollection<List>
+}
+
+impl MemoryListRepository {
+    pub fn new(context: MemoryCollection<List>) -> Self {
+        Self {
+            context
+        }
+    }
+
+    // oldest first
+    fn find_many(&self, filter: impl Fn(&List) -> bool) -> Vec<List> {
+        let mut lists = self.context.find_many(filter);
+        lists.sort_by_key(|list| list.id);
+
+        lists
+    }
+
+    // every update returns the changed list and increases its version.
+    // None if the list or the targeted entry (`matches`) does not exist or the list has not the expected version
+    fn update_list(&self, target: &ListTarget, matches: impl Fn(&List) -> bool, update: impl FnOnce(&mut List)) -> Option<List> {
+        self.context.update_one(|list| is_target(list, target) && matches(list), |list| {
+            update(list);
+            list.updated_at = Utc::now();
+            list.version += 1;
+        })
+    }
+}
+
+// lists created before versions existed have none, they are read as version 0
+fn is_target(list: &List, target: &ListTarget) -> bool {
+    list.id == target.id && target.expected_version.map_or(true, |version| list.version == version)
+}
+
+fn has_entry(list: &List, entry_id: &str) -> bool {
+    list.entries.iter().any(|entry| entry.id == entry_id)
+}
+
+fn has_sub_entry(list: &List, entry_id: &str, sub_entry_id: &str) -> bool {
+    list.entries.iter().any(|entry| entry.id == entry_id && entry.sub_entries.iter().any(|sub_entry| sub_entry.id == sub_entry_id))
+}
+
+fn entries_with_id<'a>(list: &'a mut List, entry_id: &'a str) -> impl Iterator<Item = &'a mut ListEntry> {
+    list.entries.iter_mut().filter(move |entry| entry.id == entry_id)
+}
+
+#[async_trait]
+impl InsertRepository<List, List, InsertDatabaseError> for MemoryListRepository {
+    async fn insert(&self, data: List) -> Result<List, InsertDatabaseError> {
+        self.context.insert_one(data.clone());
+
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectListByOwner, Option<List>, SelectDatabaseError> for MemoryListRepository {
+    async fn select(&self, data: &SelectListByOwner) -> Result<Option<List>, SelectDatabaseError> {
+        Ok(self.find_many(|list| list.owner == data.owner).into_iter().next())
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectListsByOwner, Vec<List>, SelectDatabaseError> for MemoryListRepository {
+    async fn select(&self, data: &SelectListsByOwner) -> Result<Vec<List>, SelectDatabaseError> {
+        Ok(self.find_many(|list| list.owner == data.owner))
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectListById, Option<List>, SelectDatabaseError> for MemoryListRepository {
+    async fn select(&self, data: &SelectListById) -> Result<Option<List>, SelectDatabaseError> {
+        Ok(self.context.find_one(|list| list.id == data.id && list.owner == data.owner))
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectSharedLists, Vec<List>, SelectDatabaseError> for MemoryListRepository {
+    async fn select(&self, data: &SelectSharedLists) -> Result<Vec<List>, SelectDatabaseError> {
+        Ok(self.find_many(|list| list.shares.iter().any(|share| share.user == data.user)))
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<ReplaceListShares, Option<List>, UpdateDatabaseError> for MemoryListRepository {
+    async fn update(&self, data: &ReplaceListShares) -> Result<Option<List>, UpdateDatabaseError> {
+        Ok(self.update_list(&data.target, |_| true, |list| list.shares = data.shares.clone()))
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<UpdateListDetails, Option<List>, UpdateDatabaseError> for MemoryListRepository {
+    async fn update(&self, data: &UpdateListDetails) -> Result<Option<List>, UpdateDatabaseError> {
+        Ok(self.update_list(&data.target, |_| true, |list| {
+            if let Some(name) = &data.name {
+                list.name = name.clone();
+            }
+            if let Some(archived) = data.archived {
+                list.archived = archived;
+            }
+        }))
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<DeleteListById, bool, DeleteDatabaseError> for MemoryListRepository {
+    async fn delete(&self, data: &DeleteListById) -> Result<bool, DeleteDatabaseError> {
+        Ok(self.context.delete_one(|list| is_target(list, &data.target)))
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<ReplaceListEntries, Option<List>, UpdateDatabaseError> for MemoryListRepository {
+    async fn update(&self, data: &ReplaceListEntries) -> Result<Option<List>, UpdateDatabaseError> {
+        Ok(self.update_list(&data.target, |_| true, |list| list.entries = data.entries.clone()))
+    }
+}
+
+// like $position, positions after the last entry append
+#[async_trait]
+impl UpdateRepository<InsertListEntry, Option<List>, UpdateDatabaseError> for MemoryListRepository {
+    async fn update(&self, data: &InsertListEntry) -> Result<Option<List>, UpdateDatabaseError> {
+        Ok(self.update_list(&data.target, |_| true, |list| {
+            let position = data.position.unwrap_or(list.entries.len()).min(list.entries.len());
+            list.entries.insert(position, data.entry.clone());
+        }))
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<UpdateListEntry, Option<List>, UpdateDatabaseError> for MemoryListRepository {
+    async fn update(&self, data: &UpdateListEntry) -> Result<Option<List>, UpdateDatabaseError> {
+        Ok(self.update_list(&data.target, |list| has_entry(list, &data.entry_id), |list| {
+            for entry in entries_with_id(list, &data.entry_id) {
+                data.changes.apply(entry);
+            }
+        }))
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<DeleteListEntry, Option<List>, UpdateDatabaseError> for MemoryListRepository {
+    async fn update(&self, data: &DeleteListEntry) -> Result<Option<List>, UpdateDatabaseError> {
+        Ok(self.update_list(&data.target, |list| has_entry(list, &data.entry_id), |list| {
+            list.entries.retain(|entry| entry.id != data.entry_id);
+        }))
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<InsertSubEntry, Option<List>, UpdateDatabaseError> for MemoryListRepository {
+    async fn update(&self, data: &InsertSubEntry) -> Result<Option<List>, UpdateDatabaseError> {
+        Ok(self.update_list(&data.target, |list| has_entry(list, &data.entry_id), |list| {
+            for entry in entries_with_id(list, &data.entry_id) {
+                entry.sub_entries.push(data.sub_entry.clone());
+            }
+        }))
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<UpdateSubEntry, Option<List>, UpdateDatabaseError> for MemoryListRepository {
+    async fn update(&self, data: &UpdateSubEntry) -> Result<Option<List>, UpdateDatabaseError> {
+        Ok(self.update_list(&data.target, |list| has_sub_entry(list, &data.entry_id, &data.sub_entry_id), |list| {
+            let sub_entries = entries_with_id(list, &data.entry_id)
+                .flat_map(|entry| entry.sub_entries.iter_mut())
+                .filter(|sub_entry| sub_entry.id == data.sub_entry_id);
+
+            for sub_entry in sub_entries {
+                if let Some(title) = &data.title {
+                    sub_entry.title = title.clone();
+                }
+                if let Some(checked) = data.checked {
+                    sub_entry.checked = checked;
+                }
+            }
+        }))
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<DeleteSubEntry, Option<List>, UpdateDatabaseError> for MemoryListRepository {
+    async fn update(&self, data: &DeleteSubEntry) -> Result<Option<List>, UpdateDatabaseError> {
+        Ok(self.update_list(&data.target, |list| has_sub_entry(list, &data.entry_id, &data.sub_entry_id), |list| {
+            for entry in entries_with_id(list, &data.entry_id) {
+                entry.sub_entries.retain(|sub_entry| sub_entry.id != data.sub_entry_id);
+            }
+        }))
+    }
+}
+
+impl ListRepository for MemoryListRepository {}
@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+use std::cmp::Reverse;
+use crate::database::memory::MemoryCollection;
+use crate::database::repositories::{ListRevisionRepository, DeleteRepository, InsertRepository, SelectRepository};
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError};
+use crate::model::list_revision::{DeleteRevisions, DeleteRevisionsBefore, ListRevision, SelectPreviousRevision, SelectRevision, SelectRevisions};
+
+pub struct MemoryListRevisionRepository {
+    context: MemoryCollection<ListRevision>
+}
+
+impl MemoryListRevisionRepository {
+    pub fn new(context: MemoryCollection<ListRevision>) -> Self {
+        Self {
+            context
+        }
+    }
+}
+
+#[async_trait]
+impl InsertRepository<ListRevision, ListRevision, InsertDatabaseError> for MemoryListRevisionRepository {
+    async fn insert(&self, data: ListRevision) -> Result<ListRevision, InsertDatabaseError> {
+        self.context.insert_one(data.clone());
+
+        Ok(data)
+    }
+}
+
+// newest first
+#[async_trait]
+impl SelectRepository<SelectRevisions, Vec<ListRevision>, SelectDatabaseError> for MemoryListRevisionRepository {
+    async fn select(&self, data: &SelectRevisions) -> Result<Vec<ListRevision>, SelectDatabaseError> {
+        let mut revisions = self.context.find_many(|revision| revision.list_id == data.list_id);
+        revisions.sort_by_key(|revision| Reverse(revision.version));
+
+        Ok(revisions.into_iter()
+            .skip(data.offset)
+            .take(data.limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectRevision, Option<ListRevision>, SelectDatabaseError> for MemoryListRevisionRepository {
+    async fn select(&self, data: &SelectRevision) -> Result<Option<ListRevision>, SelectDatabaseError> {
+        Ok(self.context.find_one(|revision| revision.list_id == data.list_id && revision.version == data.version))
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectPreviousRevision, Option<ListRevision>, SelectDatabaseError> for MemoryListRevisionRepository {
+    async fn select(&self, data: &SelectPreviousRevision) -> Result<Option<ListRevision>, SelectDatabaseError> {
+        Ok(self.context.find_many(|revision| revision.list_id == data.list_id && revision.version < data.version)
+            .into_iter()
+            .max_by_key(|revision| revision.version))
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<DeleteRevisions, (), DeleteDatabaseError> for MemoryListRevisionRepository {
+    async fn delete(&self, data: &DeleteRevisions) -> Result<(), DeleteDatabaseError> {
+        self.context.delete_many(|revision| revision.list_id == data.list_id);
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<DeleteRevisionsBefore, (), DeleteDatabaseError> for MemoryListRevisionRepository {
+    async fn delete(&self, data: &DeleteRevisionsBefore) -> Result<(), DeleteDatabaseError> {
+        self.context.delete_many(|revision| revision.list_id == data.list_id && revision.version < data.version);
+
+        Ok(())
+    }
+}
+
+impl ListRevisionRepository for MemoryListRevisionRepository {}
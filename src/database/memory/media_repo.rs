@@ -0,0 +1,44 @@
+use async_trait::async_trait;
+use crate::database::memory::MemoryCollection;
+use crate::database::repositories::{MediaRepository, DeleteRepository, InsertRepository, SelectRepository};
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError};
+use crate::model::media::{DeleteMediaEntry, MediaEntry, SelectMediaByOwner};
+
+pub struct MemoryMediaRepository {
+    context: MemoryCollection<MediaEntry>
+}
+
+impl MemoryMediaRepository {
+    pub fn new(context: MemoryCollection<MediaEntry>) -> Self {
+        Self {
+            context
+        }
+    }
+}
+
+#[async_trait]
+impl InsertRepository<MediaEntry, MediaEntry, InsertDatabaseError> for MemoryMediaRepository {
+    async fn insert(&self, data: MediaEntry) -> Result<MediaEntry, InsertDatabaseError> {
+        self.context.insert_one(data.clone());
+
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectMediaByOwner, Vec<MediaEntry>, SelectDatabaseError> for MemoryMediaRepository {
+    async fn select(&self, data: &SelectMediaByOwner) -> Result<Vec<MediaEntry>, SelectDatabaseError> {
+        Ok(self.context.find_many(|entry| {
+            entry.owner == data.owner && entry.kind == data.kind && data.visibility.map_or(true, |visibility| entry.visibility == visibility)
+        }))
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<DeleteMediaEntry, bool, DeleteDatabaseError> for MemoryMediaRepository {
+    async fn delete(&self, data: &DeleteMediaEntry) -> Result<bool, DeleteDatabaseError> {
+        Ok(self.context.delete_one(|entry| entry.owner == data.owner && entry.kind == data.kind && entry.file_name == data.file_name))
+    }
+}
+
+impl MediaRepository for MemoryMediaRepository {}
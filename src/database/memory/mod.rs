@@ -0,0 +1,166 @@
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use async_trait::async_trait;
+use crate::database::database_context::DatabaseContext;
+use crate::database::memory::changelog_repo::MemoryChangelogRepository;
+use crate::database::memory::close_friend_repo::MemoryCloseFriendRepository;
+use crate::database::memory::export_repo::MemoryExportRepository;
+use crate::database::memory::follow_repo::MemoryFollowRepository;
+use crate::database::memory::friendship_repo::MemoryFriendshipRepository;
+use crate::database::memory::list_repo::MemoryListRepository;
+use crate::database::memory::list_revision_repo::MemoryListRevisionRepository;
+use crate::database::memory::media_repo::MemoryMediaRepository;
+use crate::database::memory::restriction_repo::MemoryRestrictionRepository;
+use crate::database::memory::suggestion_repo::MemorySuggestionRepository;
+use crate::database::memory::user_repo::MemoryUserRepository;
+use crate::database::repositories::{ChangelogRepository, CloseFriendRepository, ExportRepository, FollowRepository, FriendshipRepository, ListRepository, ListRevisionRepository, MediaRepository, RestrictionRepository, SuggestionRepository, UserRepository};
+use crate::database::unit_of_work::UnitOfWork;
+use crate::model::changelog::ChangelogEntry;
+use crate::model::close_friend::CloseFriend;
+use crate::model::export::ExportJob;
+use crate::model::follow::Follow;
+use crate::model::friendship::Friendship;
+use crate::model::list::List;
+use crate::model::list_revision::ListRevision;
+use crate::model::media::MediaEntry;
+use crate::model::restriction::Restriction;
+use crate::model::suggestion::SuggestionDismissal;
+use crate::model::user::User;
+
+pub mod user_repo;
+pub mod friendship_repo;
+pub mod export_repo;
+pub mod suggestion_repo;
+pub mod follow_repo;
+pub mod media_repo;
+pub mod close_friend_repo;
+pub mod restriction_repo;
+pub mod list_repo;
+pub mod list_revision_repo;
+pub mod changelog_repo;
+
+// the documents of a collection, shared by every repository handed out for it
+pub struct MemoryCollection<T>(Arc<Mutex<Vec<T>>>);
+
+impl<T> MemoryCollection<T> {
+    // a test that panicked while holding the lock does not take the others down with it
+    pub fn lock(&self) -> MutexGuard<'_, Vec<T>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // in insertion order
+    pub fn find_many(&self, filter: impl Fn(&T) -> bool) -> Vec<T> where T: Clone {
+        self.lock().iter().filter(|document| filter(document)).cloned().collect()
+    }
+
+    pub fn find_one(&self, filter: impl Fn(&T) -> bool) -> Option<T> where T: Clone {
+        self.lock().iter().find(|document| filter(document)).cloned()
+    }
+
+    pub fn insert_one(&self, document: T) {
+        self.lock().push(document);
+    }
+
+    // the document after the change, None if none matched
+    pub fn update_one(&self, filter: impl Fn(&T) -> bool, update: impl FnOnce(&mut T)) -> Option<T> where T: Clone {
+        let mut documents = self.lock();
+        let document = documents.iter_mut().find(|document| filter(document))?;
+        update(document);
+
+        Some(document.clone())
+    }
+
+    pub fn delete_one(&self, filter: impl Fn(&T) -> bool) -> bool {
+        let mut documents = self.lock();
+        match documents.iter().position(filter) {
+            Some(index) => {
+                documents.remove(index);
+                true
+            },
+            None => false
+        }
+    }
+
+    pub fn delete_many(&self, filter: impl Fn(&T) -> bool) {
+        self.lock().retain(|document| !filter(document));
+    }
+}
+
+impl<T> Clone for MemoryCollection<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Default for MemoryCollection<T> {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(vec![])))
+    }
+}
+
+// answers every query like the mongo backend, without a server. Units of work run without transactions,
+// like they do on a standalone mongodb
+#[derive(Clone, Default)]
+pub struct MemoryDatabaseContext {
+    users: MemoryCollection<User>,
+    friendships: MemoryCollection<Friendship>,
+    exports: MemoryCollection<ExportJob>,
+    dismissals: MemoryCollection<SuggestionDismissal>,
+    follows: MemoryCollection<Follow>,
+    media: MemoryCollection<MediaEntry>,
+    close_friends: MemoryCollection<CloseFriend>,
+    restrictions: MemoryCollection<Restriction>,
+    lists: MemoryCollection<List>,
+    list_revisions: MemoryCollection<ListRevision>,
+    changelogs: MemoryCollection<ChangelogEntry>
+}
+
+#[async_trait]
+impl DatabaseContext for MemoryDatabaseContext {
+    fn user(&self) -> Box<dyn UserRepository> {
+        Box::new(MemoryUserRepository::new(self.users.clone()))
+    }
+
+    fn friendship(&self) -> Box<dyn FriendshipRepository> {
+        Box::new(MemoryFriendshipRepository::new(self.friendships.clone()))
+    }
+
+    fn export(&self) -> Box<dyn ExportRepository> {
+        Box::new(MemoryExportRepository::new(self.exports.clone()))
+    }
+
+    fn suggestion(&self) -> Box<dyn SuggestionRepository> {
+        Box::new(MemorySuggestionRepository::new(self.dismissals.clone()))
+    }
+
+    fn follow(&self) -> Box<dyn FollowRepository> {
+        Box::new(MemoryFollowRepository::new(self.follows.clone()))
+    }
+
+    fn media(&self) -> Box<dyn MediaRepository> {
+        Box::new(MemoryMediaRepository::new(self.media.clone()))
+    }
+
+    fn close_friend(&self) -> Box<dyn CloseFriendRepository> {
+        Box::new(MemoryCloseFriendRepository::new(self.close_friends.clone()))
+    }
+
+    fn restriction(&self) -> Box<dyn RestrictionRepository> {
+        Box::new(MemoryRestrictionRepository::new(self.restrictions.clone()))
+    }
+
+    fn list(&self) -> Box<dyn ListRepository> {
+        Box::new(MemoryListRepository::new(self.lists.clone()))
+    }
+
+    fn list_revision(&self) -> Box<dyn ListRevisionRepository> {
+        Box::new(MemoryListRevisionRepository::new(self.list_revisions.clone()))
+    }
+
+    fn changelog(&self) -> Box<dyn ChangelogRepository> {
+        Box::new(MemoryChangelogRepository::new(self.changelogs.clone()))
+    }
+
+    async fn start_unit(&self) -> mongodb::error::Result<UnitOfWork> {
+        Ok(UnitOfWork::new(None))
+    }
+}
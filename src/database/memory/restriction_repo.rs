@@ -0,0 +1,81 @@
+use async_trait::async_trait;
+use mongodb::bson::oid::ObjectId;
+use crate::database::memory::MemoryCollection;
+use crate::database::repositories::{RestrictionRepository, DeleteRepository, InsertRepository, SelectRepository};
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError};
+use crate::model::restriction::{DeleteRestriction, Restriction, RestrictionKind, SelectBlockBetween, SelectBlockedUsers, SelectRestriction, SelectRestrictions};
+
+pub struct MemoryRestrictionRepository {
+    context: MemoryCollection<Restriction>
+}
+
+impl MemoryRestrictionRepository {
+    pub fn new(context: MemoryCollection<Restriction>) -> Self {
+        Self {
+            context
+        }
+    }
+}
+
+fn is_restriction(restriction: &Restriction, owner: &ObjectId, target: &ObjectId, kind: RestrictionKind) -> bool {
+    restriction.owner == *owner && restriction.target == *target && restriction.kind == kind
+}
+
+#[async_trait]
+impl InsertRepository<Restriction, Restriction, InsertDatabaseError> for MemoryRestrictionRepository {
+    async fn insert(&self, data: Restriction) -> Result<Restriction, InsertDatabaseError> {
+        if let Some(existing) = self.context.find_one(|restriction| is_restriction(restriction, &data.owner, &data.target, data.kind)) {
+            return Ok(existing);
+        }
+
+        self.context.insert_one(data.clone());
+
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectRestrictions, Vec<Restriction>, SelectDatabaseError> for MemoryRestrictionRepository {
+    async fn select(&self, data: &SelectRestrictions) -> Result<Vec<Restriction>, SelectDatabaseError> {
+        Ok(self.context.find_many(|restriction| restriction.owner == data.owner && restriction.kind == data.kind))
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectRestriction, Option<Restriction>, SelectDatabaseError> for MemoryRestrictionRepository {
+    async fn select(&self, data: &SelectRestriction) -> Result<Option<Restriction>, SelectDatabaseError> {
+        Ok(self.context.find_one(|restriction| is_restriction(restriction, &data.owner, &data.target, data.kind)))
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectBlockBetween, Option<Restriction>, SelectDatabaseError> for MemoryRestrictionRepository {
+    async fn select(&self, data: &SelectBlockBetween) -> Result<Option<Restriction>, SelectDatabaseError> {
+        Ok(self.context.find_one(|restriction| {
+            is_restriction(restriction, &data.user_a, &data.user_b, RestrictionKind::Block)
+                || is_restriction(restriction, &data.user_b, &data.user_a, RestrictionKind::Block)
+        }))
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectBlockedUsers, Vec<ObjectId>, SelectDatabaseError> for MemoryRestrictionRepository {
+    async fn select(&self, data: &SelectBlockedUsers) -> Result<Vec<ObjectId>, SelectDatabaseError> {
+        let blocks = self.context.find_many(|restriction| {
+            (restriction.owner == data.id || restriction.target == data.id) && restriction.kind == RestrictionKind::Block
+        });
+
+        Ok(blocks.into_iter()
+            .map(|block| if block.owner == data.id { block.target } else { block.owner })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<DeleteRestriction, bool, DeleteDatabaseError> for MemoryRestrictionRepository {
+    async fn delete(&self, data: &DeleteRestriction) -> Result<bool, DeleteDatabaseError> {
+        Ok(self.context.delete_one(|restriction| is_restriction(restriction, &data.owner, &data.target, data.kind)))
+    }
+}
+
+impl RestrictionRepository for MemoryRestrictionRepository {}
@@ -0,0 +1,40 @@
+use async_trait::async_trait;
+use crate::database::memory::MemoryCollection;
+use crate::database::repositories::{SuggestionRepository, InsertRepository, SelectRepository};
+use crate::model::{InsertDatabaseError, SelectDatabaseError};
+use crate::model::suggestion::SuggestionDismissal;
+use crate::model::user::SelectUserById;
+
+pub struct MemorySuggestionRepository {
+    context: MemoryCollection<SuggestionDismissal>
+}
+
+impl MemorySuggestionRepository {
+    pub fn new(context: MemoryCollection<SuggestionDismissal>) -> Self {
+        Self {
+            context
+        }
+    }
+}
+
+#[async_trait]
+impl InsertRepository<SuggestionDismissal, SuggestionDismissal, InsertDatabaseError> for MemorySuggestionRepository {
+    async fn insert(&self, data: SuggestionDismissal) -> Result<SuggestionDismissal, InsertDatabaseError> {
+        if let Some(existing) = self.context.find_one(|dismissal| dismissal.user_id == data.user_id && dismissal.dismissed_id == data.dismissed_id) {
+            return Ok(existing);
+        }
+
+        self.context.insert_one(data.clone());
+
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectUserById, Vec<SuggestionDismissal>, SelectDatabaseError> for MemorySuggestionRepository {
+    async fn select(&self, data: &SelectUserById) -> Result<Vec<SuggestionDismissal>, SelectDatabaseError> {
+        Ok(self.context.find_many(|dismissal| dismissal.user_id == data.id))
+    }
+}
+
+impl SuggestionRepository for MemorySuggestionRepository {}
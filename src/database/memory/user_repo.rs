@@ -0,0 +1,149 @@
+use async_trait::async_trait;
+use mongodb::bson::oid::ObjectId;
+use crate::database::memory::MemoryCollection;
+use crate::database::repositories::{UserRepository, SelectRepository, InsertRepository, TransactionalInsertRepository, UpdateRepository};
+use crate::database::unit_of_work::UnitOfWork;
+use crate::model::{SelectDatabaseError, UpdateDatabaseError};
+use crate::model::user::{CreateUser, CreateUserError, FetchUserError, RestoreUserProfile, SelectUserByFeedToken, SelectUserById, SelectUserByName, SelectUsersByIds, SetPostCount, UpdateFeedToken, UpdateLastSeenChangelog, UpdatePostCount, UpdateUser, User};
+
+pub struct MemoryUserRepository {
+    context: MemoryCollection<User>
+}
+
+impl MemoryUserRepository {
+    pub fn new(context: MemoryCollection<User>) -> Self {
+        Self {
+            context
+        }
+    }
+
+    fn find_one(&self, filter: impl Fn(&User) -> bool) -> Result<User, FetchUserError> {
+        self.context.find_one(filter).ok_or(FetchUserError::UserNotFound)
+    }
+
+    // the user after the change, None if there is none with `id`
+    fn update_one(&self, id: &ObjectId, update: impl FnOnce(&mut User)) -> Option<User> {
+        self.context.update_one(|user| user.id == *id, update)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectUserByName<'_>, User, FetchUserError> for MemoryUserRepository {
+    async fn select(&self, data: &SelectUserByName) -> Result<User, FetchUserError> {
+        self.find_one(|user| user.name == data.username)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectUserById, User, FetchUserError> for MemoryUserRepository {
+    async fn select(&self, data: &SelectUserById) -> Result<User, FetchUserError> {
+        self.find_one(|user| user.id == data.id)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectUsersByIds, Vec<User>, SelectDatabaseError> for MemoryUserRepository {
+    async fn select(&self, data: &SelectUsersByIds) -> Result<Vec<User>, SelectDatabaseError> {
+        Ok(self.context.find_many(|user| data.ids.contains(&user.id)))
+    }
+}
+
+#[async_trait]
+impl InsertRepository<CreateUser, User, CreateUserError> for MemoryUserRepository {
+    async fn insert(&self, create_user: CreateUser) -> Result<User, CreateUserError> {
+        let user = User::try_from(create_user)?;
+
+        // checked while holding the lock, like the unique index on the name
+        let mut users = self.context.lock();
+        if users.iter().any(|existing| existing.name == user.name) {
+            return Err(CreateUserError::UserNameTaken);
+        }
+        users.push(user.clone());
+
+        Ok(user)
+    }
+}
+
+#[async_trait]
+impl TransactionalInsertRepository<CreateUser, User, CreateUserError> for MemoryUserRepository {
+    async fn insert_in(&self, _: &mut UnitOfWork, create_user: CreateUser) -> Result<User, CreateUserError> {
+        self.insert(create_user).await
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<UpdateUser, User, FetchUserError> for MemoryUserRepository {
+    async fn update(&self, data: &UpdateUser) -> Result<User, FetchUserError> {
+        self.update_one(&data.target_id, |user| {
+            user.description = data.new_description.clone();
+            if let Some(is_public) = data.new_is_public {
+                user.is_public = is_public;
+            }
+        }).ok_or(FetchUserError::UserNotFound)
+    }
+}
+
+// returns the profile before the restore, like the mongo backend
+#[async_trait]
+impl UpdateRepository<RestoreUserProfile, User, FetchUserError> for MemoryUserRepository {
+    async fn update(&self, data: &RestoreUserProfile) -> Result<User, FetchUserError> {
+        let mut previous = None;
+        self.update_one(&data.target_id, |user| {
+            previous = Some(user.clone());
+            user.description = data.description.clone();
+            user.is_bot = data.is_bot;
+            user.is_public = data.is_public;
+        });
+
+        previous.ok_or(FetchUserError::UserNotFound)
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<UpdatePostCount, bool, UpdateDatabaseError> for MemoryUserRepository {
+    async fn update(&self, data: &UpdatePostCount) -> Result<bool, UpdateDatabaseError> {
+        let mut users = self.context.lock();
+        let Some(user) = users.iter_mut().find(|user| user.id == data.target_id) else {
+            return Ok(false);
+        };
+
+        // never drop below zero, when the counter and the disk disagree
+        match user.amount_posts.checked_add_signed(data.change) {
+            Some(amount_posts) => {
+                user.amount_posts = amount_posts;
+                Ok(true)
+            },
+            None => Ok(false)
+        }
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<SetPostCount, bool, UpdateDatabaseError> for MemoryUserRepository {
+    async fn update(&self, data: &SetPostCount) -> Result<bool, UpdateDatabaseError> {
+        Ok(self.update_one(&data.target_id, |user| user.amount_posts = data.amount_posts).is_some())
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<UpdateLastSeenChangelog, bool, UpdateDatabaseError> for MemoryUserRepository {
+    async fn update(&self, data: &UpdateLastSeenChangelog) -> Result<bool, UpdateDatabaseError> {
+        Ok(self.update_one(&data.target_id, |user| user.last_seen_changelog = Some(data.version.clone())).is_some())
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectUserByFeedToken<'_>, User, FetchUserError> for MemoryUserRepository {
+    async fn select(&self, data: &SelectUserByFeedToken) -> Result<User, FetchUserError> {
+        self.find_one(|user| user.feed_token.as_deref() == Some(data.token))
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<UpdateFeedToken, bool, UpdateDatabaseError> for MemoryUserRepository {
+    async fn update(&self, data: &UpdateFeedToken) -> Result<bool, UpdateDatabaseError> {
+        Ok(self.update_one(&data.target_id, |user| user.feed_token = data.token.clone()).is_some())
+    }
+}
+
+impl UserRepository for MemoryUserRepository {}
@@ -1,2 +1,7 @@
 pub mod database_context;
-pub mod repositories;
\ No newline at end of file
+pub mod mongo_context;
+#[cfg(test)]
+pub mod memory;
+pub mod repositories;
+pub mod indexes;
+pub mod unit_of_work;
\ No newline at end of file
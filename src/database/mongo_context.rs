@@ -0,0 +1,224 @@
+use std::env::VarError;
+use std::fmt::{Display, Formatter};
+use mongodb::{Client, Collection, Database};
+use mongodb::bson::doc;
+use mongodb::options::ClientOptions;
+use async_trait::async_trait;
+use crate::database::database_context::DatabaseContext;
+use crate::database::indexes::ensure_indexes;
+use crate::database::unit_of_work::UnitOfWork;
+use crate::database::repositories::{ChangelogRepository, CloseFriendRepository, ExportRepository, FollowRepository, FriendshipRepository, ListRepository, ListRevisionRepository, MediaRepository, RestrictionRepository, SuggestionRepository, UserRepository};
+use crate::database::repositories::changelog_repo::MongoChangelogRepository;
+use crate::database::repositories::close_friend_repo::MongoCloseFriendRepository;
+use crate::database::repositories::export_repo::MongoExportRepository;
+use crate::database::repositories::follow_repo::MongoFollowRepository;
+use crate::database::repositories::friendship_repo::MongoFriendshipRepository;
+use crate::database::repositories::list_repo::MongoListRepository;
+use crate::database::repositories::list_revision_repo::MongoListRevisionRepository;
+use crate::database::repositories::media_repo::MongoMediaRepository;
+use crate::database::repositories::migration_repo::MongoMigrationRepository;
+use crate::database::repositories::restriction_repo::MongoRestrictionRepository;
+use crate::database::repositories::suggestion_repo::MongoSuggestionRepository;
+use crate::database::repositories::user_repo::MongoUserRepository;
+use crate::model::changelog::ChangelogEntry;
+use crate::model::close_friend::CloseFriend;
+use crate::model::export::ExportJob;
+use crate::model::follow::Follow;
+use crate::model::friendship::Friendship;
+use crate::model::list::List;
+use crate::model::list_revision::ListRevision;
+use crate::model::media::MediaEntry;
+use crate::model::migration::{AppliedMigration, MigrationLock};
+use crate::model::restriction::Restriction;
+use crate::model::suggestion::SuggestionDismissal;
+use crate::model::user::User;
+
+#[derive(Clone)]
+pub struct MongoDatabaseContext {
+    _db: Database,
+    client: Client,
+    // only replica sets and sharded clusters support transactions
+    transactions: bool,
+    users: Collection<User>,
+    friendships: Collection<Friendship>,
+    exports: Collection<ExportJob>,
+    dismissals: Collection<SuggestionDismissal>,
+    follows: Collection<Follow>,
+    media: Collection<MediaEntry>,
+    close_friends: Collection<CloseFriend>,
+    restrictions: Collection<Restriction>,
+    lists: Collection<List>,
+    list_revisions: Collection<ListRevision>,
+    changelogs: Collection<ChangelogEntry>,
+    migrations: Collection<AppliedMigration>,
+    migration_locks: Collection<MigrationLock>
+}
+
+#[derive(Debug)]
+pub enum Error {
+    VarError(std::env::VarError),
+    MongoDBError(mongodb::error::Error)
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Error::VarError(v) => v.to_string(),
+            Error::MongoDBError(v) => v.to_string()
+        })
+    }
+}
+
+impl From<std::env::VarError> for Error {
+    fn from(value: VarError) -> Self {
+        Error::VarError(value)
+    }
+}
+
+impl From<mongodb::error::Error> for Error {
+    fn from(value: mongodb::error::Error) -> Self {
+        Error::MongoDBError(value)
+    }
+}
+
+impl std::error::Error for Error { }
+
+async fn supports_transactions(db: &Database) -> bool {
+    match db.run_command(doc! { "hello": 1 }, None).await {
+        Ok(hello) => hello.contains_key("setName") || hello.get_str("msg").is_ok_and(|msg| msg == "isdbgrid"),
+        Err(_) => false
+    }
+}
+
+impl MongoDatabaseContext {
+    pub async fn new() -> Result<Self, Error> {
+        let client_options = ClientOptions::parse(
+            std::env::var("MONGO_URL")?
+        ).await?;
+
+        let client = Client::with_options(client_options)?;
+
+        let mongodb_name = std::env::var("MONGO_DATABASE")?;
+        let db = client.database(&mongodb_name);
+        db.run_command(doc! { "ping": 1 }, None)
+            .await?;
+
+        log::info!("Established connection the database");
+
+        let transactions = supports_transactions(&db).await;
+        if !transactions {
+            log::warn!("The database is a standalone server, units of work run without transactions");
+        }
+
+        let context = Self {
+            _db: db.clone(),
+            client,
+            transactions,
+            users: db.collection("users"),
+            friendships: db.collection("friendships"),
+            exports: db.collection("exports"),
+            dismissals: db.collection("suggestion_dismissals"),
+            follows: db.collection("follows"),
+            media: db.collection("media"),
+            close_friends: db.collection("close_friends"),
+            restrictions: db.collection("restrictions"),
+            lists: db.collection("lists"),
+            list_revisions: db.collection("list_revisions"),
+            changelogs: db.collection("changelogs"),
+            migrations: db.collection("migrations"),
+            migration_locks: db.collection("migration_locks")
+        };
+
+        context.create_indexes().await?;
+
+        Ok(context)
+    }
+
+    async fn create_indexes(&self) -> Result<(), Error> {
+        ensure_indexes(&self.users, MongoUserRepository::indexes()).await?;
+        ensure_indexes(&self.friendships, MongoFriendshipRepository::indexes()).await?;
+        ensure_indexes(&self.exports, MongoExportRepository::indexes()).await?;
+        ensure_indexes(&self.dismissals, MongoSuggestionRepository::indexes()).await?;
+        ensure_indexes(&self.follows, MongoFollowRepository::indexes()).await?;
+        ensure_indexes(&self.media, MongoMediaRepository::indexes()).await?;
+        ensure_indexes(&self.close_friends, MongoCloseFriendRepository::indexes()).await?;
+        ensure_indexes(&self.restrictions, MongoRestrictionRepository::indexes()).await?;
+        ensure_indexes(&self.lists, MongoListRepository::indexes()).await?;
+        ensure_indexes(&self.list_revisions, MongoListRevisionRepository::indexes()).await?;
+
+        Ok(())
+    }
+
+    // the raw collections, for migrations that work on documents of older shapes
+    pub fn users(&self) -> &Collection<User> {
+        &self.users
+    }
+
+    pub fn friendships(&self) -> &Collection<Friendship> {
+        &self.friendships
+    }
+
+    pub fn media_entries(&self) -> &Collection<MediaEntry> {
+        &self.media
+    }
+
+    pub fn migration(&self) -> MongoMigrationRepository {
+        MongoMigrationRepository::new(self.migrations.clone(), self.migration_locks.clone())
+    }
+}
+
+#[async_trait]
+impl DatabaseContext for MongoDatabaseContext {
+    fn user(&self) -> Box<dyn UserRepository> {
+        Box::new(MongoUserRepository::new(self.users.clone()))
+    }
+
+    fn friendship(&self) -> Box<dyn FriendshipRepository> {
+        Box::new(MongoFriendshipRepository::new(self.friendships.clone()))
+    }
+
+    fn export(&self) -> Box<dyn ExportRepository> {
+        Box::new(MongoExportRepository::new(self.exports.clone()))
+    }
+
+    fn suggestion(&self) -> Box<dyn SuggestionRepository> {
+        Box::new(MongoSuggestionRepository::new(self.dismissals.clone()))
+    }
+
+    fn follow(&self) -> Box<dyn FollowRepository> {
+        Box::new(MongoFollowRepository::new(self.follows.clone()))
+    }
+
+    fn media(&self) -> Box<dyn MediaRepository> {
+        Box::new(MongoMediaRepository::new(self.media.clone()))
+    }
+
+    fn close_friend(&self) -> Box<dyn CloseFriendRepository> {
+        Box::new(MongoCloseFriendRepository::new(self.close_friends.clone()))
+    }
+
+    fn restriction(&self) -> Box<dyn RestrictionRepository> {
+        Box::new(MongoRestrictionRepository::new(self.restrictions.clone()))
+    }
+
+    fn list(&self) -> Box<dyn ListRepository> {
+        Box::new(MongoListRepository::new(self.lists.clone()))
+    }
+
+    fn list_revision(&self) -> Box<dyn ListRevisionRepository> {
+        Box::new(MongoListRevisionRepository::new(self.list_revisions.clone()))
+    }
+
+    fn changelog(&self) -> Box<dyn ChangelogRepository> {
+        Box::new(MongoChangelogRepository::new(self.changelogs.clone()))
+    }
+
+    async fn start_unit(&self) -> mongodb::error::Result<UnitOfWork> {
+        let mut unit = UnitOfWork::new(Some(self.client.start_session(None).await?));
+        if self.transactions {
+            unit.start_transaction().await?;
+        }
+
+        Ok(unit)
+    }
+}
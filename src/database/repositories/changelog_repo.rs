@@ -0,0 +1,119 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use futures_util::StreamExt;
+use mongodb::bson::{doc, to_bson, Document};
+use mongodb::Collection;
+use mongodb::options::{FindOneAndUpdateOptions, ReturnDocument};
+use crate::database::repositories::{ChangelogRepository, DeleteRepository, InsertRepository, SelectRepository, UpdateRepository};
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError, UpdateDatabaseError};
+use crate::model::changelog::{ChangelogEntry, ChangelogStatus, DeleteChangelogDraft, PublishChangelog, SelectChangelog, SelectChangelogs, UpdateChangelog};
+
+pub struct MongoChangelogRepository {
+    context: Collection<ChangelogEntry>
+}
+
+impl MongoChangelogRepository {
+    pub fn new(context: Collection<ChangelogEntry>) -> Self {
+        Self {
+            context
+        }
+    }
+
+    async fn update_changelog(&self, query: Document, set: Document) -> Result<Option<ChangelogEntry>, UpdateDatabaseError> {
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        Ok(self.context.find_one_and_update(query, doc! { "$set": set }, options).await?)
+    }
+}
+
+fn status_bson(status: ChangelogStatus) -> &'static str {
+    match status {
+        ChangelogStatus::Draft => "draft",
+        ChangelogStatus::Published => "published"
+    }
+}
+
+#[async_trait]
+impl InsertRepository<ChangelogEntry, ChangelogEntry, InsertDatabaseError> for MongoChangelogRepository {
+    async fn insert(&self, data: ChangelogEntry) -> Result<ChangelogEntry, InsertDatabaseError> {
+        self.context.insert_one(&data, None).await?;
+
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectChangelogs, Vec<ChangelogEntry>, SelectDatabaseError> for MongoChangelogRepository {
+    async fn select(&self, data: &SelectChangelogs) -> Result<Vec<ChangelogEntry>, SelectDatabaseError> {
+        let query = match data.status {
+            Some(status) => doc! { "status": status_bson(status) },
+            None => doc! {}
+        };
+
+        let mut changelogs = vec![];
+        let mut cursor = self.context.find(query, None)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)?;
+
+        while let Some(document) = cursor.next().await {
+            changelogs.push(document.map_err(SelectDatabaseError::DatabaseError)?);
+        }
+
+        // versions are stored as strings, which do not sort like versions
+        changelogs.sort_by(|a, b| b.version.cmp(&a.version));
+
+        Ok(changelogs)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectChangelog, Option<ChangelogEntry>, SelectDatabaseError> for MongoChangelogRepository {
+    async fn select(&self, data: &SelectChangelog) -> Result<Option<ChangelogEntry>, SelectDatabaseError> {
+        self.context.find_one(doc! { "_id": data.version.to_string() }, None)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<UpdateChangelog, Option<ChangelogEntry>, UpdateDatabaseError> for MongoChangelogRepository {
+    async fn update(&self, data: &UpdateChangelog) -> Result<Option<ChangelogEntry>, UpdateDatabaseError> {
+        let mut set = doc! { "updated_at": to_bson(&Utc::now()).unwrap_or_default() };
+        if let Some(added) = &data.added {
+            set.insert("added", added);
+        }
+        if let Some(fixed) = &data.fixed {
+            set.insert("fixed", fixed);
+        }
+        if let Some(changed) = &data.changed {
+            set.insert("changed", changed);
+        }
+
+        self.update_changelog(doc! { "_id": data.version.to_string() }, set).await
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<PublishChangelog, Option<ChangelogEntry>, UpdateDatabaseError> for MongoChangelogRepository {
+    async fn update(&self, data: &PublishChangelog) -> Result<Option<ChangelogEntry>, UpdateDatabaseError> {
+        let now = to_bson(&Utc::now()).unwrap_or_default();
+
+        self.update_changelog(
+            doc! { "_id": data.version.to_string(), "status": status_bson(ChangelogStatus::Draft) },
+            doc! { "status": status_bson(ChangelogStatus::Published), "published_at": now.clone(), "updated_at": now }
+        ).await
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<DeleteChangelogDraft, bool, DeleteDatabaseError> for MongoChangelogRepository {
+    async fn delete(&self, data: &DeleteChangelogDraft) -> Result<bool, DeleteDatabaseError> {
+        let result = self.context.delete_one(doc! { "_id": data.version.to_string(), "status": status_bson(ChangelogStatus::Draft) }, None).await?;
+
+        Ok(result.deleted_count > 0)
+    }
+}
+
+impl ChangelogRepository for MongoChangelogRepository {}
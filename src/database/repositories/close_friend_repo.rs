@@ -0,0 +1,83 @@
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use mongodb::bson::doc;
+use mongodb::{Collection, IndexModel};
+use mongodb::options::IndexOptions;
+use crate::database::repositories::{is_duplicate_key, CloseFriendRepository, DeleteRepository, InsertRepository, SelectRepository};
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError};
+use crate::model::close_friend::{CloseFriend, DeleteCloseFriend, SelectCloseFriend};
+use crate::model::user::SelectUserById;
+
+pub struct MongoCloseFriendRepository {
+    context: Collection<CloseFriend>
+}
+
+impl MongoCloseFriendRepository {
+    pub fn new(context: Collection<CloseFriend>) -> Self {
+        Self {
+            context
+        }
+    }
+
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![
+            IndexModel::builder()
+                .keys(doc! { "owner": 1, "friend": 1 })
+                .options(IndexOptions::builder().name(String::from("unique_close_friend")).unique(true).build())
+                .build()
+        ]
+    }
+}
+
+#[async_trait]
+impl InsertRepository<CloseFriend, CloseFriend, InsertDatabaseError> for MongoCloseFriendRepository {
+    async fn insert(&self, data: CloseFriend) -> Result<CloseFriend, InsertDatabaseError> {
+        if let Some(existing) = self.context.find_one(doc! { "owner": &data.owner, "friend": &data.friend }, None).await? {
+            return Ok(existing);
+        }
+
+        match self.context.insert_one(&data, None).await {
+            Ok(_) => Ok(data),
+            // inserted by a concurrent request in the meantime
+            Err(err) if is_duplicate_key(&err) => Ok(self.context.find_one(doc! { "owner": &data.owner, "friend": &data.friend }, None).await?.unwrap_or(data)),
+            Err(err) => Err(err.into())
+        }
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectCloseFriend, Option<CloseFriend>, SelectDatabaseError> for MongoCloseFriendRepository {
+    async fn select(&self, data: &SelectCloseFriend) -> Result<Option<CloseFriend>, SelectDatabaseError> {
+        self.context.find_one(doc! { "owner": &data.owner, "friend": &data.friend }, None)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)
+    }
+}
+
+// all close friends of `data.id`
+#[async_trait]
+impl SelectRepository<SelectUserById, Vec<CloseFriend>, SelectDatabaseError> for MongoCloseFriendRepository {
+    async fn select(&self, data: &SelectUserById) -> Result<Vec<CloseFriend>, SelectDatabaseError> {
+        let mut close_friends = vec![];
+        let mut cursor = self.context.find(doc! { "owner": &data.id }, None)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)?;
+
+        while let Some(document) = cursor.next().await {
+            close_friends.push(document.map_err(SelectDatabaseError::DatabaseError)?);
+        }
+
+        Ok(close_friends)
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<DeleteCloseFriend, bool, DeleteDatabaseError> for MongoCloseFriendRepository {
+    async fn delete(&self, data: &DeleteCloseFriend) -> Result<bool, DeleteDatabaseError> {
+        let result = self.context.delete_one(doc! { "owner": &data.owner, "friend": &data.friend }, None).await?;
+
+        Ok(result.deleted_count > 0)
+    }
+}
+
+impl CloseFriendRepository for MongoCloseFriendRepository {}
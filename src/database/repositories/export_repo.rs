@@ -0,0 +1,130 @@
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use mongodb::bson::{DateTime, doc};
+use mongodb::{Collection, IndexModel};
+use mongodb::options::{FindOptions, IndexOptions};
+use crate::database::repositories::{is_duplicate_key, ExportRepository, DeleteRepository, InsertRepository, SelectRepository, UpdateRepository};
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError, UpdateDatabaseError};
+use crate::model::export::{DeleteExportById, ExportJob, SelectExpiredExports, SelectExportById, SelectExportsByUser, UpdateExportStatus};
+
+pub struct MongoExportRepository {
+    context: Collection<ExportJob>
+}
+
+impl MongoExportRepository {
+    pub fn new(context: Collection<ExportJob>) -> Self {
+        Self {
+            context
+        }
+    }
+
+    // a user has at most one pending job, so concurrent requests can not start several exports
+    pub fn indexes() -> Vec<IndexModel> {
+        let unique_pending = IndexOptions::builder()
+            .name(String::from("unique_pending"))
+            .unique(true)
+            .partial_filter_expression(doc! { "status": "pending" })
+            .build();
+
+        vec![
+            IndexModel::builder()
+                .keys(doc! { "expires_at": 1 })
+                .options(IndexOptions::builder().name(String::from("expires_at")).build())
+                .build(),
+            IndexModel::builder()
+                .keys(doc! { "user_id": 1, "status": 1 })
+                .options(unique_pending)
+                .build(),
+            IndexModel::builder()
+                .keys(doc! { "user_id": 1, "created_at": 1 })
+                .options(IndexOptions::builder().name(String::from("user_created_at")).build())
+                .build()
+        ]
+    }
+}
+
+#[async_trait]
+impl InsertRepository<ExportJob, ExportJob, InsertDatabaseError> for MongoExportRepository {
+    // the pending job of the user, if it already has one
+    async fn insert(&self, data: ExportJob) -> Result<ExportJob, InsertDatabaseError> {
+        let pending = doc! { "user_id": &data.user_id, "status": "pending" };
+
+        match self.context.insert_one(&data, None).await {
+            Ok(_) => Ok(data),
+            Err(err) if is_duplicate_key(&err) => Ok(self.context.find_one(pending, None).await?.unwrap_or(data)),
+            Err(err) => Err(err.into())
+        }
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectExportById, Option<ExportJob>, SelectDatabaseError> for MongoExportRepository {
+    async fn select(&self, data: &SelectExportById) -> Result<Option<ExportJob>, SelectDatabaseError> {
+        self.context.find_one(doc! { "_id": &data.id }, None)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectExpiredExports, Vec<ExportJob>, SelectDatabaseError> for MongoExportRepository {
+    async fn select(&self, _: &SelectExpiredExports) -> Result<Vec<ExportJob>, SelectDatabaseError> {
+        let mut jobs = vec![];
+        let mut cursor = self.context.find(doc! { "expires_at": { "$lt": DateTime::now() } }, None)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)?;
+
+        while let Some(document) = cursor.next().await {
+            jobs.push(document.map_err(SelectDatabaseError::DatabaseError)?);
+        }
+
+        Ok(jobs)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectExportsByUser, Vec<ExportJob>, SelectDatabaseError> for MongoExportRepository {
+    async fn select(&self, data: &SelectExportsByUser) -> Result<Vec<ExportJob>, SelectDatabaseError> {
+        let options = FindOptions::builder()
+            .sort(doc! { "created_at": 1 })
+            .build();
+
+        let mut jobs = vec![];
+        let mut cursor = self.context.find(doc! { "user_id": &data.user_id }, options)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)?;
+
+        while let Some(document) = cursor.next().await {
+            jobs.push(document.map_err(SelectDatabaseError::DatabaseError)?);
+        }
+
+        Ok(jobs)
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<UpdateExportStatus, (), UpdateDatabaseError> for MongoExportRepository {
+    async fn update(&self, data: &UpdateExportStatus) -> Result<(), UpdateDatabaseError> {
+        let update = doc! {
+            "$set": {
+                "status": mongodb::bson::to_bson(&data.status).unwrap_or_default(),
+                "expires_at": data.expires_at().map(DateTime::from_chrono)
+            }
+        };
+
+        self.context.update_one(doc! { "_id": &data.target_id }, update, None).await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<DeleteExportById, (), DeleteDatabaseError> for MongoExportRepository {
+    async fn delete(&self, data: &DeleteExportById) -> Result<(), DeleteDatabaseError> {
+        self.context.delete_one(doc! { "_id": &data.id }, None).await?;
+
+        Ok(())
+    }
+}
+
+impl ExportRepository for MongoExportRepository {}
@@ -0,0 +1,99 @@
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use mongodb::bson::{doc, Document};
+use mongodb::bson::oid::ObjectId;
+use mongodb::{Collection, IndexModel};
+use mongodb::options::IndexOptions;
+use crate::database::repositories::{is_duplicate_key, FollowRepository, DeleteRepository, InsertRepository, SelectRepository};
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError};
+use crate::model::follow::{CountFollows, DeleteFollow, Follow, FollowDirection, SelectFollow, SelectFollows};
+
+pub struct MongoFollowRepository {
+    context: Collection<Follow>
+}
+
+impl MongoFollowRepository {
+    pub fn new(context: Collection<Follow>) -> Self {
+        Self {
+            context
+        }
+    }
+
+    // a pair follows at most once, so concurrent follows can not inflate the counts
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![
+            IndexModel::builder()
+                .keys(doc! { "follower": 1, "followee": 1 })
+                .options(IndexOptions::builder().name(String::from("unique_follow")).unique(true).build())
+                .build(),
+            IndexModel::builder()
+                .keys(doc! { "followee": 1 })
+                .options(IndexOptions::builder().name(String::from("followee")).build())
+                .build()
+        ]
+    }
+}
+
+fn direction_query(id: &ObjectId, direction: FollowDirection) -> Document {
+    match direction {
+        FollowDirection::Followers => doc! { "followee": id },
+        FollowDirection::Following => doc! { "follower": id }
+    }
+}
+
+#[async_trait]
+impl InsertRepository<Follow, bool, InsertDatabaseError> for MongoFollowRepository {
+    // false if the pair already follows, the unique index decides between concurrent follows
+    async fn insert(&self, data: Follow) -> Result<bool, InsertDatabaseError> {
+        match self.context.insert_one(&data, None).await {
+            Ok(_) => Ok(true),
+            Err(err) if is_duplicate_key(&err) => Ok(false),
+            Err(err) => Err(err.into())
+        }
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectFollow, Option<Follow>, SelectDatabaseError> for MongoFollowRepository {
+    async fn select(&self, data: &SelectFollow) -> Result<Option<Follow>, SelectDatabaseError> {
+        self.context.find_one(doc! { "follower": &data.follower, "followee": &data.followee }, None)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectFollows, Vec<Follow>, SelectDatabaseError> for MongoFollowRepository {
+    async fn select(&self, data: &SelectFollows) -> Result<Vec<Follow>, SelectDatabaseError> {
+        let mut follows = vec![];
+        let mut cursor = self.context.find(direction_query(&data.id, data.direction), None)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)?;
+
+        while let Some(document) = cursor.next().await {
+            follows.push(document.map_err(SelectDatabaseError::DatabaseError)?);
+        }
+
+        Ok(follows)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<CountFollows, u64, SelectDatabaseError> for MongoFollowRepository {
+    async fn select(&self, data: &CountFollows) -> Result<u64, SelectDatabaseError> {
+        self.context.count_documents(direction_query(&data.id, data.direction), None)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<DeleteFollow, bool, DeleteDatabaseError> for MongoFollowRepository {
+    async fn delete(&self, data: &DeleteFollow) -> Result<bool, DeleteDatabaseError> {
+        let result = self.context.delete_one(doc! { "follower": &data.follower, "followee": &data.followee }, None).await?;
+
+        Ok(result.deleted_count > 0)
+    }
+}
+
+impl FollowRepository for MongoFollowRepository {}
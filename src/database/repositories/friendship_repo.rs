@@ -1,37 +1,65 @@
 use async_trait::async_trait;
 use futures_util::StreamExt;
-use mongodb::bson::doc;
-use mongodb::Collection;
-use crate::database::repositories::{InsertRepository, SelectRepository};
+use mongodb::bson::{doc, Document, from_document};
+use mongodb::bson::oid::ObjectId;
+use mongodb::{Collection, IndexModel};
+use mongodb::error::{ErrorKind, WriteFailure};
+use mongodb::options::{FindOptions, IndexOptions};
+use mongodb::results::InsertOneResult;
+use crate::database::repositories::{FriendshipRepository, DUPLICATE_KEY, DeleteRepository, InsertRepository, SelectRepository, TransactionalInsertRepository, TransactionalSelectRepository, TransactionalUpdateRepository, UpdateRepository};
+use crate::database::unit_of_work::UnitOfWork;
+use crate::model::{DeleteDatabaseError, UpdateDatabaseError};
 use crate::model::friend::{CreateFriendshipError, FetchFriendshipError};
-use crate::model::friendship::{Friendship};
+use crate::model::friendship::{AcceptFriendRequest, DeleteFriendRequest, DeleteFriendship, Friendship, FriendshipStatus, normalized_pair, RequestDirection, SelectFriendRequests, SelectFriendshipBetween};
+use crate::model::suggestion::{MutualFriends, SelectSuggestions};
 use crate::model::user::SelectUserById;
 
-pub struct FriendshipRepository {
+
+pub struct MongoFriendshipRepository {
     context: Collection<Friendship>
 }
 
-impl FriendshipRepository {
+impl MongoFriendshipRepository {
     pub fn new(context: Collection<Friendship>) -> Self {
         Self {
             context
         }
     }
-}
 
-#[async_trait]
-impl SelectRepository<SelectUserById, Vec<Friendship>, FetchFriendshipError> for FriendshipRepository {
-    async fn select(&self, data: &SelectUserById) -> Result<Vec<Friendship>, FetchFriendshipError> {
+    // documents of the old schema have no member_low and are left out of the unique pair until they are migrated
+    pub fn indexes() -> Vec<IndexModel> {
+        let unique_pair = IndexOptions::builder()
+            .name(String::from("unique_pair"))
+            .unique(true)
+            .partial_filter_expression(doc! { "member_low": { "$exists": true } })
+            .build();
+
+        vec![
+            IndexModel::builder()
+                .keys(doc! { "member_low": 1, "member_high": 1 })
+                .options(unique_pair)
+                .build(),
+            // friends and requests are looked up by either member
+            IndexModel::builder()
+                .keys(doc! { "friend_a": 1, "status": 1 })
+                .options(IndexOptions::builder().name(String::from("friend_a_status")).build())
+                .build(),
+            IndexModel::builder()
+                .keys(doc! { "friend_b": 1, "status": 1 })
+                .options(IndexOptions::builder().name(String::from("friend_b_status")).build())
+                .build()
+        ]
+    }
+
+    async fn find_many(&self, query: Document) -> Result<Vec<Friendship>, FetchFriendshipError> {
         let mut friendships: Vec<Friendship> = vec![];
 
-        let query = doc! {
-            "$or": [
-                { "_id": &data.id },
-                { "friend_b": &data.id }
-            ]
-        };
+        // oldest first, so pages stay stable
+        let options = FindOptions::builder()
+            .sort(doc! { "_id": 1 })
+            .build();
 
-        if let Ok(mut cursor) = self.context.find(query, None).await {
+        if let Ok(mut cursor) = self.context.find(query, options).await {
             while let Some(document) = cursor.next().await {
                 let friendship: Friendship = document?;
                 friendships.push(friendship);
@@ -42,27 +70,205 @@ impl SelectRepository<SelectUserById, Vec<Friendship>, FetchFriendshipError> for
         }
 
 
-        return Err(FetchFriendshipError::UserNotFound)
+        Err(FetchFriendshipError::UserNotFound)
+    }
+
+    async fn inserted_friendship(&self, data: Friendship, result: mongodb::error::Result<InsertOneResult>) -> Result<Friendship, CreateFriendshipError> {
+        match result {
+            Ok(_) => Ok(data),
+            Err(err) => match *err.kind {
+                // the pair was inserted in the meantime. It is looked up outside of the session, since the failed
+                // write aborted the transaction and the conflicting document is committed already
+                ErrorKind::Write(WriteFailure::WriteError(ref write_error)) if write_error.code == DUPLICATE_KEY => {
+                    match self.context.find_one(pair_query(data.friend_a, data.friend_b), None).await? {
+                        Some(existing) => Err(existing_pair(&existing)),
+                        None => Err(CreateFriendshipError::AlreadyFriends)
+                    }
+                },
+                _ => Err(err.into())
+            }
+        }
     }
 }
 
-#[async_trait]
-impl InsertRepository<Friendship, Friendship, CreateFriendshipError> for FriendshipRepository {
-    async fn insert(&self, data: Friendship) -> Result<Friendship, CreateFriendshipError> {
+fn pair_query(user_a: ObjectId, user_b: ObjectId) -> Document {
+    let (member_low, member_high) = normalized_pair(user_a, user_b);
 
+    doc! { "member_low": member_low, "member_high": member_high }
+}
+
+// only accepted friendships. Documents without a status were created before friend requests existed
+#[async_trait]
+impl SelectRepository<SelectUserById, Vec<Friendship>, FetchFriendshipError> for MongoFriendshipRepository {
+    async fn select(&self, data: &SelectUserById) -> Result<Vec<Friendship>, FetchFriendshipError> {
         let query = doc! {
             "$or": [
-                { "_id": &data.friend_a, "friend_b": &data.friend_b },
-                { "_id": &data.friend_b, "friend_b": &data.friend_a }
-            ]
+                { "friend_a": &data.id },
+                { "friend_b": &data.id }
+            ],
+            "status": { "$ne": "pending" }
         };
 
-        if let Ok(Some(_)) = self.context.find_one(query, None).await {
-            return Err(CreateFriendshipError::AlreadyFriends);
+        self.find_many(query).await
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectFriendRequests, Vec<Friendship>, FetchFriendshipError> for MongoFriendshipRepository {
+    async fn select(&self, data: &SelectFriendRequests) -> Result<Vec<Friendship>, FetchFriendshipError> {
+        let query = match data.direction {
+            RequestDirection::Incoming => doc! { "friend_b": &data.id, "status": "pending" },
+            RequestDirection::Outgoing => doc! { "friend_a": &data.id, "status": "pending" }
+        };
+
+        self.find_many(query).await
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectFriendshipBetween, Option<Friendship>, FetchFriendshipError> for MongoFriendshipRepository {
+    async fn select(&self, data: &SelectFriendshipBetween) -> Result<Option<Friendship>, FetchFriendshipError> {
+        Ok(self.context.find_one(pair_query(data.user_a, data.user_b), None).await?)
+    }
+}
+
+#[async_trait]
+impl TransactionalSelectRepository<SelectFriendshipBetween, Option<Friendship>, FetchFriendshipError> for MongoFriendshipRepository {
+    async fn select_in(&self, unit: &mut UnitOfWork, data: &SelectFriendshipBetween) -> Result<Option<Friendship>, FetchFriendshipError> {
+        let Some(session) = unit.session() else {
+            return self.select(data).await;
+        };
+
+        Ok(self.context.find_one_with_session(pair_query(data.user_a, data.user_b), None, session).await?)
+    }
+}
+
+// ranks every friend of a friend by the amount of friends shared with `data.id`
+#[async_trait]
+impl SelectRepository<SelectSuggestions, Vec<MutualFriends>, FetchFriendshipError> for MongoFriendshipRepository {
+    async fn select(&self, data: &SelectSuggestions) -> Result<Vec<MutualFriends>, FetchFriendshipError> {
+        let pipeline = vec![
+            doc! { "$match": {
+                "$or": [{ "friend_a": &data.id }, { "friend_b": &data.id }],
+                "status": { "$ne": "pending" }
+            }},
+            doc! { "$project": {
+                "friend": { "$cond": [{ "$eq": ["$friend_a", &data.id] }, "$friend_b", "$friend_a"] }
+            }},
+            doc! { "$lookup": {
+                "from": self.context.name(),
+                "let": { "friend": "$friend" },
+                "pipeline": [
+                    { "$match": { "$expr": { "$and": [
+                        { "$or": [{ "$eq": ["$friend_a", "$$friend"] }, { "$eq": ["$friend_b", "$$friend"] }] },
+                        { "$ne": ["$status", "pending"] }
+                    ]}}},
+                    { "$project": {
+                        "candidate": { "$cond": [{ "$eq": ["$friend_a", "$$friend"] }, "$friend_b", "$friend_a"] }
+                    }}
+                ],
+                "as": "friends_of_friend"
+            }},
+            doc! { "$unwind": "$friends_of_friend" },
+            doc! { "$group": { "_id": "$friends_of_friend.candidate", "mutual_friends": { "$sum": 1 } } },
+            doc! { "$match": { "_id": { "$nin": data.excluded.clone() } } },
+            doc! { "$sort": { "mutual_friends": -1, "_id": 1 } },
+            doc! { "$limit": data.limit }
+        ];
+
+        let mut suggestions = vec![];
+        let mut cursor = self.context.aggregate(pipeline, None).await?;
+
+        while let Some(document) = cursor.next().await {
+            suggestions.push(from_document(document?).map_err(|_| FetchFriendshipError::DocumentNotParsable)?);
         }
 
-        self.context.insert_one(&data, None).await?;
+        Ok(suggestions)
+    }
+}
+
+#[async_trait]
+impl InsertRepository<Friendship, Friendship, CreateFriendshipError> for MongoFriendshipRepository {
+    async fn insert(&self, data: Friendship) -> Result<Friendship, CreateFriendshipError> {
+        if let Ok(Some(existing)) = self.context.find_one(pair_query(data.friend_a, data.friend_b), None).await {
+            return Err(existing_pair(&existing));
+        }
+
+        let result = self.context.insert_one(&data, None).await;
+
+        self.inserted_friendship(data, result).await
+    }
+}
+
+#[async_trait]
+impl TransactionalInsertRepository<Friendship, Friendship, CreateFriendshipError> for MongoFriendshipRepository {
+    async fn insert_in(&self, unit: &mut UnitOfWork, data: Friendship) -> Result<Friendship, CreateFriendshipError> {
+        let Some(session) = unit.session() else {
+            return self.insert(data).await;
+        };
+
+        if let Ok(Some(existing)) = self.context.find_one_with_session(pair_query(data.friend_a, data.friend_b), None, &mut *session).await {
+            return Err(existing_pair(&existing));
+        }
+
+        let result = self.context.insert_one_with_session(&data, None, session).await;
+
+        self.inserted_friendship(data, result).await
+    }
+}
+
+fn existing_pair(existing: &Friendship) -> CreateFriendshipError {
+    match existing.status {
+        FriendshipStatus::Pending => CreateFriendshipError::RequestPending,
+        FriendshipStatus::Accepted => CreateFriendshipError::AlreadyFriends
+    }
+}
+
 
-        Ok(data)
+#[async_trait]
+impl UpdateRepository<AcceptFriendRequest, bool, UpdateDatabaseError> for MongoFriendshipRepository {
+    async fn update(&self, data: &AcceptFriendRequest) -> Result<bool, UpdateDatabaseError> {
+        let query = doc! { "friend_a": &data.requester, "friend_b": &data.addressee, "status": "pending" };
+        let result = self.context.update_one(query, doc! { "$set": { "status": "accepted" } }, None).await?;
+
+        Ok(result.modified_count > 0)
+    }
+}
+
+#[async_trait]
+impl TransactionalUpdateRepository<AcceptFriendRequest, bool, UpdateDatabaseError> for MongoFriendshipRepository {
+    async fn update_in(&self, unit: &mut UnitOfWork, data: &AcceptFriendRequest) -> Result<bool, UpdateDatabaseError> {
+        let Some(session) = unit.session() else {
+            return self.update(data).await;
+        };
+
+        let query = doc! { "friend_a": &data.requester, "friend_b": &data.addressee, "status": "pending" };
+        let result = self.context.update_one_with_session(query, doc! { "$set": { "status": "accepted" } }, None, session).await?;
+
+        Ok(result.modified_count > 0)
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<DeleteFriendRequest, bool, DeleteDatabaseError> for MongoFriendshipRepository {
+    async fn delete(&self, data: &DeleteFriendRequest) -> Result<bool, DeleteDatabaseError> {
+        let query = doc! { "friend_a": &data.requester, "friend_b": &data.addressee, "status": "pending" };
+        let result = self.context.delete_one(query, None).await?;
+
+        Ok(result.deleted_count > 0)
     }
-}
\ No newline at end of file
+}
+
+#[async_trait]
+impl DeleteRepository<DeleteFriendship, bool, DeleteDatabaseError> for MongoFriendshipRepository {
+    async fn delete(&self, data: &DeleteFriendship) -> Result<bool, DeleteDatabaseError> {
+        let mut query = pair_query(data.user_a, data.user_b);
+        query.insert("status", doc! { "$ne": "pending" });
+
+        let result = self.context.delete_one(query, None).await?;
+
+        Ok(result.deleted_count > 0)
+    }
+}
+
+impl FriendshipRepository for MongoFriendshipRepository {}
@@ -0,0 +1,266 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use futures_util::StreamExt;
+use mongodb::bson::{doc, Document, to_bson};
+use mongodb::{Collection, IndexModel};
+use mongodb::options::{FindOneAndUpdateOptions, FindOneOptions, FindOptions, IndexOptions, ReturnDocument};
+use crate::database::repositories::{ListRepository, DeleteRepository, InsertRepository, SelectRepository, UpdateRepository};
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError, UpdateDatabaseError};
+use crate::model::list::{DeleteListById, DeleteListEntry, DeleteSubEntry, InsertListEntry, InsertSubEntry, List, ListTarget, ReplaceListEntries, ReplaceListShares, SelectListById, SelectListByOwner, SelectListsByOwner, SelectSharedLists, UpdateListDetails, UpdateListEntry, UpdateSubEntry};
+
+pub struct MongoListRepository {
+    context: Collection<List>
+}
+
+impl MongoListRepository {
+    pub fn new(context: Collection<List>) -> Self {
+        Self {
+            context
+        }
+    }
+
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![
+            IndexModel::builder()
+                .keys(doc! { "owner": 1 })
+                .options(IndexOptions::builder().name(String::from("owner")).build())
+                .build(),
+            IndexModel::builder()
+                .keys(doc! { "shares.user": 1 })
+                .options(IndexOptions::builder().name(String::from("shared_with")).build())
+                .build()
+        ]
+    }
+
+    // oldest first
+    async fn find_many(&self, query: Document) -> Result<Vec<List>, SelectDatabaseError> {
+        let options = FindOptions::builder()
+            .sort(doc! { "_id": 1 })
+            .build();
+
+        let mut lists = vec![];
+        let mut cursor = self.context.find(query, options)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)?;
+
+        while let Some(document) = cursor.next().await {
+            lists.push(document.map_err(SelectDatabaseError::DatabaseError)?);
+        }
+
+        Ok(lists)
+    }
+
+    // every update returns the changed list and increases its version.
+    // None if the list or the targeted entry does not exist or the list has not the expected version
+    async fn update_list(&self, target: &ListTarget, mut query: Document, mut update: Document, array_filters: Vec<Document>) -> Result<Option<List>, UpdateDatabaseError> {
+        query.extend(target_query(target));
+
+        let updated_at = to_bson(&Utc::now()).unwrap_or_default();
+        match update.get_document_mut("$set") {
+            Ok(set) => { set.insert("updated_at", updated_at); },
+            Err(_) => { update.insert("$set", doc! { "updated_at": updated_at }); }
+        }
+        update.insert("$inc", doc! { "version": 1 });
+
+        let mut options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+        if !array_filters.is_empty() {
+            options.array_filters = Some(array_filters);
+        }
+
+        Ok(self.context.find_one_and_update(query, update, options).await?)
+    }
+}
+
+fn target_query(target: &ListTarget) -> Document {
+    let mut query = doc! { "_id": target.id };
+    if let Some(version) = target.expected_version {
+        let version = i64::try_from(version).unwrap_or(i64::MAX);
+        // lists created before versions existed have none
+        query.insert("version", if version == 0 { doc! { "$in": [0, null] } } else { doc! { "$eq": version } });
+    }
+
+    query
+}
+
+fn set_fields(prefix: &str, title: &Option<String>, checked: Option<bool>) -> Document {
+    let mut set = doc! { };
+    if let Some(title) = title {
+        set.insert(format!("{}.title", prefix), title);
+    }
+    if let Some(checked) = checked {
+        set.insert(format!("{}.checked", prefix), checked);
+    }
+
+    set
+}
+
+#[async_trait]
+impl InsertRepository<List, List, InsertDatabaseError> for MongoListRepository {
+    async fn insert(&self, data: List) -> Result<List, InsertDatabaseError> {
+        self.context.insert_one(&data, None).await?;
+
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectListByOwner, Option<List>, SelectDatabaseError> for MongoListRepository {
+    async fn select(&self, data: &SelectListByOwner) -> Result<Option<List>, SelectDatabaseError> {
+        let options = FindOneOptions::builder()
+            .sort(doc! { "_id": 1 })
+            .build();
+
+        self.context.find_one(doc! { "owner": &data.owner }, options)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectListsByOwner, Vec<List>, SelectDatabaseError> for MongoListRepository {
+    async fn select(&self, data: &SelectListsByOwner) -> Result<Vec<List>, SelectDatabaseError> {
+        self.find_many(doc! { "owner": &data.owner }).await
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectListById, Option<List>, SelectDatabaseError> for MongoListRepository {
+    async fn select(&self, data: &SelectListById) -> Result<Option<List>, SelectDatabaseError> {
+        self.context.find_one(doc! { "_id": &data.id, "owner": &data.owner }, None)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectSharedLists, Vec<List>, SelectDatabaseError> for MongoListRepository {
+    async fn select(&self, data: &SelectSharedLists) -> Result<Vec<List>, SelectDatabaseError> {
+        self.find_many(doc! { "shares.user": &data.user }).await
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<ReplaceListShares, Option<List>, UpdateDatabaseError> for MongoListRepository {
+    async fn update(&self, data: &ReplaceListShares) -> Result<Option<List>, UpdateDatabaseError> {
+        let shares = to_bson(&data.shares).unwrap_or_default();
+
+        self.update_list(&data.target, doc! { }, doc! { "$set": { "shares": shares } }, vec![]).await
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<UpdateListDetails, Option<List>, UpdateDatabaseError> for MongoListRepository {
+    async fn update(&self, data: &UpdateListDetails) -> Result<Option<List>, UpdateDatabaseError> {
+        let mut set = doc! { };
+        if let Some(name) = &data.name {
+            set.insert("name", name);
+        }
+        if let Some(archived) = data.archived {
+            set.insert("archived", archived);
+        }
+
+        self.update_list(&data.target, doc! { }, doc! { "$set": set }, vec![]).await
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<DeleteListById, bool, DeleteDatabaseError> for MongoListRepository {
+    async fn delete(&self, data: &DeleteListById) -> Result<bool, DeleteDatabaseError> {
+        let result = self.context.delete_one(target_query(&data.target), None).await?;
+
+        Ok(result.deleted_count > 0)
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<ReplaceListEntries, Option<List>, UpdateDatabaseError> for MongoListRepository {
+    async fn update(&self, data: &ReplaceListEntries) -> Result<Option<List>, UpdateDatabaseError> {
+        let entries = to_bson(&data.entries).unwrap_or_default();
+
+        self.update_list(&data.target, doc! { }, doc! { "$set": { "entries": entries } }, vec![]).await
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<InsertListEntry, Option<List>, UpdateDatabaseError> for MongoListRepository {
+    async fn update(&self, data: &InsertListEntry) -> Result<Option<List>, UpdateDatabaseError> {
+        let entry = to_bson(&data.entry).unwrap_or_default();
+        let update = match data.position {
+            Some(position) => doc! { "$push": { "entries": { "$each": [entry], "$position": i64::try_from(position).unwrap_or(i64::MAX) } } },
+            None => doc! { "$push": { "entries": entry } }
+        };
+
+        self.update_list(&data.target, doc! { }, update, vec![]).await
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<UpdateListEntry, Option<List>, UpdateDatabaseError> for MongoListRepository {
+    async fn update(&self, data: &UpdateListEntry) -> Result<Option<List>, UpdateDatabaseError> {
+        let query = doc! { "entries.id": &data.entry_id };
+        let changes = &data.changes;
+        let mut set = set_fields("entries.$[entry]", &changes.title, changes.checked);
+        if let Some(due) = &changes.due {
+            set.insert("entries.$[entry].due", to_bson(due).unwrap_or_default());
+        }
+        if let Some(notes) = &changes.notes {
+            set.insert("entries.$[entry].notes", to_bson(notes).unwrap_or_default());
+        }
+        if let Some(assignee) = &changes.assignee {
+            set.insert("entries.$[entry].assignee", to_bson(assignee).unwrap_or_default());
+        }
+        if let Some(priority) = &changes.priority {
+            set.insert("entries.$[entry].priority", to_bson(priority).unwrap_or_default());
+        }
+
+        self.update_list(&data.target, query, doc! { "$set": set }, vec![doc! { "entry.id": &data.entry_id }]).await
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<DeleteListEntry, Option<List>, UpdateDatabaseError> for MongoListRepository {
+    async fn update(&self, data: &DeleteListEntry) -> Result<Option<List>, UpdateDatabaseError> {
+        let query = doc! { "entries.id": &data.entry_id };
+
+        self.update_list(&data.target, query, doc! { "$pull": { "entries": { "id": &data.entry_id } } }, vec![]).await
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<InsertSubEntry, Option<List>, UpdateDatabaseError> for MongoListRepository {
+    async fn update(&self, data: &InsertSubEntry) -> Result<Option<List>, UpdateDatabaseError> {
+        let query = doc! { "entries.id": &data.entry_id };
+        let sub_entry = to_bson(&data.sub_entry).unwrap_or_default();
+
+        self.update_list(&data.target, query, doc! { "$push": { "entries.$[entry].sub_entries": sub_entry } }, vec![doc! { "entry.id": &data.entry_id }]).await
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<UpdateSubEntry, Option<List>, UpdateDatabaseError> for MongoListRepository {
+    async fn update(&self, data: &UpdateSubEntry) -> Result<Option<List>, UpdateDatabaseError> {
+        let query = doc! {
+            "entries": { "$elemMatch": { "id": &data.entry_id, "sub_entries.id": &data.sub_entry_id } }
+        };
+        let set = set_fields("entries.$[entry].sub_entries.$[sub_entry]", &data.title, data.checked);
+        let array_filters = vec![doc! { "entry.id": &data.entry_id }, doc! { "sub_entry.id": &data.sub_entry_id }];
+
+        self.update_list(&data.target, query, doc! { "$set": set }, array_filters).await
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<DeleteSubEntry, Option<List>, UpdateDatabaseError> for MongoListRepository {
+    async fn update(&self, data: &DeleteSubEntry) -> Result<Option<List>, UpdateDatabaseError> {
+        let query = doc! {
+            "entries": { "$elemMatch": { "id": &data.entry_id, "sub_entries.id": &data.sub_entry_id } }
+        };
+        let update = doc! { "$pull": { "entries.$[entry].sub_entries": { "id": &data.sub_entry_id } } };
+
+        self.update_list(&data.target, query, update, vec![doc! { "entry.id": &data.entry_id }]).await
+    }
+}
+
+impl ListRepository for MongoListRepository {}
@@ -0,0 +1,111 @@
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use mongodb::bson::doc;
+use mongodb::{Collection, IndexModel};
+use mongodb::options::{FindOneOptions, FindOptions, IndexOptions};
+use crate::database::repositories::{ListRevisionRepository, DeleteRepository, InsertRepository, SelectRepository};
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError};
+use crate::model::list_revision::{DeleteRevisions, DeleteRevisionsBefore, ListRevision, SelectPreviousRevision, SelectRevision, SelectRevisions};
+
+pub struct MongoListRevisionRepository {
+    context: Collection<ListRevision>
+}
+
+impl MongoListRevisionRepository {
+    pub fn new(context: Collection<ListRevision>) -> Self {
+        Self {
+            context
+        }
+    }
+
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![
+            IndexModel::builder()
+                .keys(doc! { "list_id": 1, "version": -1 })
+                .options(IndexOptions::builder().name(String::from("list_id_version")).build())
+                .build()
+        ]
+    }
+}
+
+fn version_bson(version: u64) -> i64 {
+    i64::try_from(version).unwrap_or(i64::MAX)
+}
+
+#[async_trait]
+impl InsertRepository<ListRevision, ListRevision, InsertDatabaseError> for MongoListRevisionRepository {
+    async fn insert(&self, data: ListRevision) -> Result<ListRevision, InsertDatabaseError> {
+        self.context.insert_one(&data, None).await?;
+
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectRevisions, Vec<ListRevision>, SelectDatabaseError> for MongoListRevisionRepository {
+    async fn select(&self, data: &SelectRevisions) -> Result<Vec<ListRevision>, SelectDatabaseError> {
+        // a limit of 0 means no limit to MongoDB
+        if data.limit == Some(0) {
+            return Ok(vec![]);
+        }
+
+        let options = FindOptions::builder()
+            .sort(doc! { "version": -1 })
+            .skip(u64::try_from(data.offset).unwrap_or(u64::MAX))
+            .limit(data.limit.map(|limit| i64::try_from(limit).unwrap_or(i64::MAX)))
+            .build();
+
+        let mut revisions = vec![];
+        let mut cursor = self.context.find(doc! { "list_id": &data.list_id }, options)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)?;
+
+        while let Some(document) = cursor.next().await {
+            revisions.push(document.map_err(SelectDatabaseError::DatabaseError)?);
+        }
+
+        Ok(revisions)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectRevision, Option<ListRevision>, SelectDatabaseError> for MongoListRevisionRepository {
+    async fn select(&self, data: &SelectRevision) -> Result<Option<ListRevision>, SelectDatabaseError> {
+        self.context.find_one(doc! { "list_id": &data.list_id, "version": version_bson(data.version) }, None)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectPreviousRevision, Option<ListRevision>, SelectDatabaseError> for MongoListRevisionRepository {
+    async fn select(&self, data: &SelectPreviousRevision) -> Result<Option<ListRevision>, SelectDatabaseError> {
+        let options = FindOneOptions::builder()
+            .sort(doc! { "version": -1 })
+            .build();
+
+        self.context.find_one(doc! { "list_id": &data.list_id, "version": { "$lt": version_bson(data.version) } }, options)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<DeleteRevisions, (), DeleteDatabaseError> for MongoListRevisionRepository {
+    async fn delete(&self, data: &DeleteRevisions) -> Result<(), DeleteDatabaseError> {
+        self.context.delete_many(doc! { "list_id": &data.list_id }, None).await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<DeleteRevisionsBefore, (), DeleteDatabaseError> for MongoListRevisionRepository {
+    async fn delete(&self, data: &DeleteRevisionsBefore) -> Result<(), DeleteDatabaseError> {
+        self.context.delete_many(doc! { "list_id": &data.list_id, "version": { "$lt": version_bson(data.version) } }, None).await?;
+
+        Ok(())
+    }
+}
+
+impl ListRevisionRepository for MongoListRevisionRepository {}
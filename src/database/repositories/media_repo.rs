@@ -0,0 +1,70 @@
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use mongodb::bson::{doc, to_bson};
+use mongodb::{Collection, IndexModel};
+use mongodb::options::IndexOptions;
+use crate::database::repositories::{MediaRepository, DeleteRepository, InsertRepository, SelectRepository};
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError};
+use crate::model::media::{DeleteMediaEntry, MediaEntry, SelectMediaByOwner};
+
+pub struct MongoMediaRepository {
+    context: Collection<MediaEntry>
+}
+
+impl MongoMediaRepository {
+    pub fn new(context: Collection<MediaEntry>) -> Self {
+        Self {
+            context
+        }
+    }
+
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![
+            IndexModel::builder()
+                .keys(doc! { "owner": 1, "created_at": -1 })
+                .options(IndexOptions::builder().name(String::from("owner_created_at")).build())
+                .build()
+        ]
+    }
+}
+
+#[async_trait]
+impl InsertRepository<MediaEntry, MediaEntry, InsertDatabaseError> for MongoMediaRepository {
+    async fn insert(&self, data: MediaEntry) -> Result<MediaEntry, InsertDatabaseError> {
+        self.context.insert_one(&data, None).await?;
+
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectMediaByOwner, Vec<MediaEntry>, SelectDatabaseError> for MongoMediaRepository {
+    async fn select(&self, data: &SelectMediaByOwner) -> Result<Vec<MediaEntry>, SelectDatabaseError> {
+        let mut query = doc! { "owner": &data.owner, "kind": to_bson(&data.kind).unwrap_or_default() };
+        if let Some(visibility) = &data.visibility {
+            query.insert("visibility", to_bson(visibility).unwrap_or_default());
+        }
+
+        let mut entries = vec![];
+        let mut cursor = self.context.find(query, None)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)?;
+
+        while let Some(document) = cursor.next().await {
+            entries.push(document.map_err(SelectDatabaseError::DatabaseError)?);
+        }
+
+        Ok(entries)
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<DeleteMediaEntry, bool, DeleteDatabaseError> for MongoMediaRepository {
+    async fn delete(&self, data: &DeleteMediaEntry) -> Result<bool, DeleteDatabaseError> {
+        let result = self.context.delete_one(doc! { "owner": &data.owner, "kind": to_bson(&data.kind).unwrap_or_default(), "file_name": &data.file_name }, None).await?;
+
+        Ok(result.deleted_count > 0)
+    }
+}
+
+impl MediaRepository for MongoMediaRepository {}
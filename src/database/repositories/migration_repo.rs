@@ -0,0 +1,91 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use futures_util::StreamExt;
+use mongodb::bson::{doc, to_bson};
+use mongodb::Collection;
+use mongodb::error::{ErrorKind, WriteFailure};
+use mongodb::options::FindOneAndUpdateOptions;
+use crate::database::repositories::{DUPLICATE_KEY, DeleteRepository, InsertRepository, SelectRepository, UpdateRepository};
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError, UpdateDatabaseError};
+use crate::model::migration::{AcquireMigrationLock, AppliedMigration, MigrationLock, ReleaseMigrationLock, SelectAppliedMigrations};
+
+// there is only one lock, every instance migrates the same database
+const LOCK_ID: &str = "migrations";
+
+pub struct MongoMigrationRepository {
+    ledger: Collection<AppliedMigration>,
+    locks: Collection<MigrationLock>
+}
+
+impl MongoMigrationRepository {
+    pub fn new(ledger: Collection<AppliedMigration>, locks: Collection<MigrationLock>) -> Self {
+        Self {
+            ledger,
+            locks
+        }
+    }
+}
+
+#[async_trait]
+impl InsertRepository<AppliedMigration, AppliedMigration, InsertDatabaseError> for MongoMigrationRepository {
+    async fn insert(&self, data: AppliedMigration) -> Result<AppliedMigration, InsertDatabaseError> {
+        self.ledger.insert_one(&data, None).await?;
+
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectAppliedMigrations, Vec<AppliedMigration>, SelectDatabaseError> for MongoMigrationRepository {
+    async fn select(&self, _: &SelectAppliedMigrations) -> Result<Vec<AppliedMigration>, SelectDatabaseError> {
+        let mut applied = vec![];
+        let mut cursor = self.ledger.find(doc! { }, None)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)?;
+
+        while let Some(document) = cursor.next().await {
+            applied.push(document.map_err(SelectDatabaseError::DatabaseError)?);
+        }
+
+        // versions are stored as strings, which do not sort like versions
+        applied.sort_by(|a, b| a.version.cmp(&b.version));
+
+        Ok(applied)
+    }
+}
+
+// false while another instance holds the lock
+#[async_trait]
+impl UpdateRepository<AcquireMigrationLock, bool, UpdateDatabaseError> for MongoMigrationRepository {
+    async fn update(&self, data: &AcquireMigrationLock) -> Result<bool, UpdateDatabaseError> {
+        let now = Utc::now();
+        let update = doc! { "$set": {
+            "owner": &data.owner,
+            "locked_at": to_bson(&now).unwrap_or_default(),
+            "expires_at": mongodb::bson::DateTime::from_chrono(data.expires_at)
+        } };
+        let options = FindOneAndUpdateOptions::builder()
+            .upsert(true)
+            .build();
+
+        // only an expired lock matches, a held one makes the upsert collide with its id
+        let expired = doc! { "_id": LOCK_ID, "expires_at": { "$lt": mongodb::bson::DateTime::from_chrono(now) } };
+        match self.locks.find_one_and_update(expired, update, options).await {
+            Ok(_) => Ok(true),
+            Err(err) => match *err.kind {
+                ErrorKind::Command(ref command_error) if command_error.code == DUPLICATE_KEY => Ok(false),
+                ErrorKind::Write(WriteFailure::WriteError(ref write_error)) if write_error.code == DUPLICATE_KEY => Ok(false),
+                _ => Err(err.into())
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<ReleaseMigrationLock, (), DeleteDatabaseError> for MongoMigrationRepository {
+    async fn delete(&self, data: &ReleaseMigrationLock) -> Result<(), DeleteDatabaseError> {
+        self.locks.delete_one(doc! { "_id": LOCK_ID, "owner": &data.owner }, None).await?;
+
+        Ok(())
+    }
+}
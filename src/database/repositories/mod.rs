@@ -1,24 +1,183 @@
 use async_trait::async_trait;
+use mongodb::bson::oid::ObjectId;
+use mongodb::error::{ErrorKind, WriteFailure};
+use crate::database::unit_of_work::UnitOfWork;
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError, UpdateDatabaseError};
+use crate::model::changelog::{ChangelogEntry, DeleteChangelogDraft, PublishChangelog, SelectChangelog, SelectChangelogs, UpdateChangelog};
+use crate::model::close_friend::{CloseFriend, DeleteCloseFriend, SelectCloseFriend};
+use crate::model::export::{DeleteExportById, ExportJob, SelectExpiredExports, SelectExportById, SelectExportsByUser, UpdateExportStatus};
+use crate::model::follow::{CountFollows, DeleteFollow, Follow, SelectFollow, SelectFollows};
+use crate::model::friend::{CreateFriendshipError, FetchFriendshipError};
+use crate::model::friendship::{AcceptFriendRequest, DeleteFriendRequest, DeleteFriendship, Friendship, SelectFriendRequests, SelectFriendshipBetween};
+use crate::model::list::{DeleteListById, DeleteListEntry, DeleteSubEntry, InsertListEntry, InsertSubEntry, List, ReplaceListEntries, ReplaceListShares, SelectListById, SelectListByOwner, SelectListsByOwner, SelectSharedLists, UpdateListDetails, UpdateListEntry, UpdateSubEntry};
+use crate::model::list_revision::{DeleteRevisions, DeleteRevisionsBefore, ListRevision, SelectPreviousRevision, SelectRevision, SelectRevisions};
+use crate::model::media::{DeleteMediaEntry, MediaEntry, SelectMediaByOwner};
+use crate::model::restriction::{DeleteRestriction, Restriction, SelectBlockBetween, SelectBlockedUsers, SelectRestriction, SelectRestrictions};
+use crate::model::suggestion::{MutualFriends, SelectSuggestions, SuggestionDismissal};
+use crate::model::user::{CreateUser, CreateUserError, FetchUserError, RestoreUserProfile, SelectUserByFeedToken, SelectUserById, SelectUserByName, SelectUsersByIds, SetPostCount, UpdateFeedToken, UpdateLastSeenChangelog, UpdatePostCount, UpdateUser, User};
 
 pub mod user_repo;
 pub mod friendship_repo;
+pub mod export_repo;
+pub mod suggestion_repo;
+pub mod follow_repo;
+pub mod media_repo;
+pub mod close_friend_repo;
+pub mod restriction_repo;
+pub mod list_repo;
+pub mod list_revision_repo;
+pub mod changelog_repo;
+pub mod migration_repo;
+
+// error code of writes that violate a unique index
+pub const DUPLICATE_KEY: i32 = 11000;
+
+pub fn is_duplicate_key(err: &mongodb::error::Error) -> bool {
+    matches!(*err.kind, ErrorKind::Write(WriteFailure::WriteError(ref write_error)) if write_error.code == DUPLICATE_KEY)
+}
 
 #[async_trait]
-pub trait InsertRepository<T, K, E>: Sized {
+pub trait InsertRepository<T, K, E> {
     async fn insert(&self, data: T) -> Result<K, E>;
 }
 
 #[async_trait]
-pub trait SelectRepository<T, K, E>: Sized {
+pub trait SelectRepository<T, K, E> {
     async fn select(&self, data: &T) -> Result<K, E>;
 }
 
 #[async_trait]
-pub trait UpdateRepository<T, K, E>: Sized {
+pub trait UpdateRepository<T, K, E> {
     async fn update(&self, data: &T) -> Result<K, E>;
 }
 
 #[async_trait]
-pub trait DeleteRepository<T, K, E>: Sized {
+pub trait DeleteRepository<T, K, E> {
     async fn delete(&self, data: &T) -> Result<K, E>;
-}
\ No newline at end of file
+}
+
+// the same operations as part of a unit of work, committed or rolled back with it
+#[async_trait]
+pub trait TransactionalInsertRepository<T, K, E> {
+    async fn insert_in(&self, unit: &mut UnitOfWork, data: T) -> Result<K, E>;
+}
+
+#[async_trait]
+pub trait TransactionalSelectRepository<T, K, E> {
+    async fn select_in(&self, unit: &mut UnitOfWork, data: &T) -> Result<K, E>;
+}
+
+#[async_trait]
+pub trait TransactionalUpdateRepository<T, K, E> {
+    async fn update_in(&self, unit: &mut UnitOfWork, data: &T) -> Result<K, E>;
+}
+
+// the operations of each collection, so handlers work with the mongo and the in memory backend alike
+pub trait UserRepository:
+    for<'a> SelectRepository<SelectUserByName<'a>, User, FetchUserError>
+    + SelectRepository<SelectUserById, User, FetchUserError>
+    + SelectRepository<SelectUsersByIds, Vec<User>, SelectDatabaseError>
+    + for<'a> SelectRepository<SelectUserByFeedToken<'a>, User, FetchUserError>
+    + InsertRepository<CreateUser, User, CreateUserError>
+    + TransactionalInsertRepository<CreateUser, User, CreateUserError>
+    + UpdateRepository<UpdateUser, User, FetchUserError>
+    + UpdateRepository<RestoreUserProfile, User, FetchUserError>
+    + UpdateRepository<UpdatePostCount, bool, UpdateDatabaseError>
+    + UpdateRepository<SetPostCount, bool, UpdateDatabaseError>
+    + UpdateRepository<UpdateLastSeenChangelog, bool, UpdateDatabaseError>
+    + UpdateRepository<UpdateFeedToken, bool, UpdateDatabaseError>
+    + Send + Sync {}
+
+pub trait FriendshipRepository:
+    SelectRepository<SelectUserById, Vec<Friendship>, FetchFriendshipError>
+    + SelectRepository<SelectFriendRequests, Vec<Friendship>, FetchFriendshipError>
+    + SelectRepository<SelectFriendshipBetween, Option<Friendship>, FetchFriendshipError>
+    + TransactionalSelectRepository<SelectFriendshipBetween, Option<Friendship>, FetchFriendshipError>
+    + SelectRepository<SelectSuggestions, Vec<MutualFriends>, FetchFriendshipError>
+    + InsertRepository<Friendship, Friendship, CreateFriendshipError>
+    + TransactionalInsertRepository<Friendship, Friendship, CreateFriendshipError>
+    + UpdateRepository<AcceptFriendRequest, bool, UpdateDatabaseError>
+    + TransactionalUpdateRepository<AcceptFriendRequest, bool, UpdateDatabaseError>
+    + DeleteRepository<DeleteFriendRequest, bool, DeleteDatabaseError>
+    + DeleteRepository<DeleteFriendship, bool, DeleteDatabaseError>
+    + Send + Sync {}
+
+pub trait ExportRepository:
+    InsertRepository<ExportJob, ExportJob, InsertDatabaseError>
+    + SelectRepository<SelectExportById, Option<ExportJob>, SelectDatabaseError>
+    + SelectRepository<SelectExpiredExports, Vec<ExportJob>, SelectDatabaseError>
+    + SelectRepository<SelectExportsByUser, Vec<ExportJob>, SelectDatabaseError>
+    + UpdateRepository<UpdateExportStatus, (), UpdateDatabaseError>
+    + DeleteRepository<DeleteExportById, (), DeleteDatabaseError>
+    + Send + Sync {}
+
+pub trait SuggestionRepository:
+    InsertRepository<SuggestionDismissal, SuggestionDismissal, InsertDatabaseError>
+    + SelectRepository<SelectUserById, Vec<SuggestionDismissal>, SelectDatabaseError>
+    + Send + Sync {}
+
+pub trait FollowRepository:
+    InsertRepository<Follow, bool, InsertDatabaseError>
+    + SelectRepository<SelectFollow, Option<Follow>, SelectDatabaseError>
+    + SelectRepository<SelectFollows, Vec<Follow>, SelectDatabaseError>
+    + SelectRepository<CountFollows, u64, SelectDatabaseError>
+    + DeleteRepository<DeleteFollow, bool, DeleteDatabaseError>
+    + Send + Sync {}
+
+pub trait MediaRepository:
+    InsertRepository<MediaEntry, MediaEntry, InsertDatabaseError>
+    + SelectRepository<SelectMediaByOwner, Vec<MediaEntry>, SelectDatabaseError>
+    + DeleteRepository<DeleteMediaEntry, bool, DeleteDatabaseError>
+    + Send + Sync {}
+
+pub trait CloseFriendRepository:
+    InsertRepository<CloseFriend, CloseFriend, InsertDatabaseError>
+    + SelectRepository<SelectCloseFriend, Option<CloseFriend>, SelectDatabaseError>
+    + SelectRepository<SelectUserById, Vec<CloseFriend>, SelectDatabaseError>
+    + DeleteRepository<DeleteCloseFriend, bool, DeleteDatabaseError>
+    + Send + Sync {}
+
+pub trait RestrictionRepository:
+    InsertRepository<Restriction, Restriction, InsertDatabaseError>
+    + SelectRepository<SelectRestrictions, Vec<Restriction>, SelectDatabaseError>
+    + SelectRepository<SelectRestriction, Option<Restriction>, SelectDatabaseError>
+    + SelectRepository<SelectBlockBetween, Option<Restriction>, SelectDatabaseError>
+    + SelectRepository<SelectBlockedUsers, Vec<ObjectId>, SelectDatabaseError>
+    + DeleteRepository<DeleteRestriction, bool, DeleteDatabaseError>
+    + Send + Sync {}
+
+pub trait ListRepository:
+    InsertRepository<List, List, InsertDatabaseError>
+    + SelectRepository<SelectListByOwner, Option<List>, SelectDatabaseError>
+    + SelectRepository<SelectListsByOwner, Vec<List>, SelectDatabaseError>
+    + SelectRepository<SelectListById, Option<List>, SelectDatabaseError>
+    + SelectRepository<SelectSharedLists, Vec<List>, SelectDatabaseError>
+    + UpdateRepository<ReplaceListShares, Option<List>, UpdateDatabaseError>
+    + UpdateRepository<UpdateListDetails, Option<List>, UpdateDatabaseError>
+    + UpdateRepository<ReplaceListEntries, Option<List>, UpdateDatabaseError>
+    + UpdateRepository<InsertListEntry, Option<List>, UpdateDatabaseError>
+    + UpdateRepository<UpdateListEntry, Option<List>, UpdateDatabaseError>
+    + UpdateRepository<DeleteListEntry, Option<List>, UpdateDatabaseError>
+    + UpdateRepository<InsertSubEntry, Option<List>, UpdateDatabaseError>
+    + UpdateRepository<UpdateSubEntry, Option<List>, UpdateDatabaseError>
+    + UpdateRepository<DeleteSubEntry, Option<List>, UpdateDatabaseError>
+    + DeleteRepository<DeleteListById, bool, DeleteDatabaseError>
+    + Send + Sync {}
+
+pub trait ListRevisionRepository:
+    InsertRepository<ListRevision, ListRevision, InsertDatabaseError>
+    + SelectRepository<SelectRevisions, Vec<ListRevision>, SelectDatabaseError>
+    + SelectRepository<SelectRevision, Option<ListRevision>, SelectDatabaseError>
+    + SelectRepository<SelectPreviousRevision, Option<ListRevision>, SelectDatabaseError>
+    + DeleteRepository<DeleteRevisions, (), DeleteDatabaseError>
+    + DeleteRepository<DeleteRevisionsBefore, (), DeleteDatabaseError>
+    + Send + Sync {}
+
+pub trait ChangelogRepository:
+    InsertRepository<ChangelogEntry, ChangelogEntry, InsertDatabaseError>
+    + SelectRepository<SelectChangelogs, Vec<ChangelogEntry>, SelectDatabaseError>
+    + SelectRepository<SelectChangelog, Option<ChangelogEntry>, SelectDatabaseError>
+    + UpdateRepository<UpdateChangelog, Option<ChangelogEntry>, UpdateDatabaseError>
+    + UpdateRepository<PublishChangelog, Option<ChangelogEntry>, UpdateDatabaseError>
+    + DeleteRepository<DeleteChangelogDraft, bool, DeleteDatabaseError>
+    + Send + Sync {}
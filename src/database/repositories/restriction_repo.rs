@@ -0,0 +1,127 @@
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use mongodb::bson::{doc, Document, to_bson};
+use mongodb::bson::oid::ObjectId;
+use mongodb::{Collection, IndexModel};
+use mongodb::options::IndexOptions;
+use crate::database::repositories::{is_duplicate_key, RestrictionRepository, DeleteRepository, InsertRepository, SelectRepository};
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError};
+use crate::model::restriction::{DeleteRestriction, Restriction, RestrictionKind, SelectBlockBetween, SelectBlockedUsers, SelectRestriction, SelectRestrictions};
+
+pub struct MongoRestrictionRepository {
+    context: Collection<Restriction>
+}
+
+impl MongoRestrictionRepository {
+    pub fn new(context: Collection<Restriction>) -> Self {
+        Self {
+            context
+        }
+    }
+
+    // blocks are also looked up by the blocked user
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![
+            IndexModel::builder()
+                .keys(doc! { "owner": 1, "target": 1, "kind": 1 })
+                .options(IndexOptions::builder().name(String::from("unique_restriction")).unique(true).build())
+                .build(),
+            IndexModel::builder()
+                .keys(doc! { "target": 1, "kind": 1 })
+                .options(IndexOptions::builder().name(String::from("target_kind")).build())
+                .build()
+        ]
+    }
+
+    async fn find_many(&self, query: Document) -> Result<Vec<Restriction>, SelectDatabaseError> {
+        let mut restrictions = vec![];
+        let mut cursor = self.context.find(query, None)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)?;
+
+        while let Some(document) = cursor.next().await {
+            restrictions.push(document.map_err(SelectDatabaseError::DatabaseError)?);
+        }
+
+        Ok(restrictions)
+    }
+}
+
+fn restriction_query(owner: &ObjectId, target: &ObjectId, kind: RestrictionKind) -> Document {
+    doc! { "owner": owner, "target": target, "kind": to_bson(&kind).unwrap_or_default() }
+}
+
+#[async_trait]
+impl InsertRepository<Restriction, Restriction, InsertDatabaseError> for MongoRestrictionRepository {
+    async fn insert(&self, data: Restriction) -> Result<Restriction, InsertDatabaseError> {
+        if let Some(existing) = self.context.find_one(restriction_query(&data.owner, &data.target, data.kind), None).await? {
+            return Ok(existing);
+        }
+
+        match self.context.insert_one(&data, None).await {
+            Ok(_) => Ok(data),
+            // inserted by a concurrent request in the meantime
+            Err(err) if is_duplicate_key(&err) => Ok(self.context.find_one(restriction_query(&data.owner, &data.target, data.kind), None).await?.unwrap_or(data)),
+            Err(err) => Err(err.into())
+        }
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectRestrictions, Vec<Restriction>, SelectDatabaseError> for MongoRestrictionRepository {
+    async fn select(&self, data: &SelectRestrictions) -> Result<Vec<Restriction>, SelectDatabaseError> {
+        self.find_many(doc! { "owner": &data.owner, "kind": to_bson(&data.kind).unwrap_or_default() }).await
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectRestriction, Option<Restriction>, SelectDatabaseError> for MongoRestrictionRepository {
+    async fn select(&self, data: &SelectRestriction) -> Result<Option<Restriction>, SelectDatabaseError> {
+        self.context.find_one(restriction_query(&data.owner, &data.target, data.kind), None)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectBlockBetween, Option<Restriction>, SelectDatabaseError> for MongoRestrictionRepository {
+    async fn select(&self, data: &SelectBlockBetween) -> Result<Option<Restriction>, SelectDatabaseError> {
+        let query = doc! {
+            "$or": [
+                restriction_query(&data.user_a, &data.user_b, RestrictionKind::Block),
+                restriction_query(&data.user_b, &data.user_a, RestrictionKind::Block)
+            ]
+        };
+
+        self.context.find_one(query, None)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectBlockedUsers, Vec<ObjectId>, SelectDatabaseError> for MongoRestrictionRepository {
+    async fn select(&self, data: &SelectBlockedUsers) -> Result<Vec<ObjectId>, SelectDatabaseError> {
+        let query = doc! {
+            "$or": [{ "owner": &data.id }, { "target": &data.id }],
+            "kind": to_bson(&RestrictionKind::Block).unwrap_or_default()
+        };
+
+        Ok(self.find_many(query)
+            .await?
+            .into_iter()
+            .map(|block| if block.owner == data.id { block.target } else { block.owner })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<DeleteRestriction, bool, DeleteDatabaseError> for MongoRestrictionRepository {
+    async fn delete(&self, data: &DeleteRestriction) -> Result<bool, DeleteDatabaseError> {
+        let result = self.context.delete_one(restriction_query(&data.owner, &data.target, data.kind), None).await?;
+
+        Ok(result.deleted_count > 0)
+    }
+}
+
+impl RestrictionRepository for MongoRestrictionRepository {}
@@ -0,0 +1,66 @@
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use mongodb::bson::doc;
+use mongodb::{Collection, IndexModel};
+use mongodb::options::IndexOptions;
+use crate::database::repositories::{is_duplicate_key, SuggestionRepository, InsertRepository, SelectRepository};
+use crate::model::{InsertDatabaseError, SelectDatabaseError};
+use crate::model::suggestion::SuggestionDismissal;
+use crate::model::user::SelectUserById;
+
+pub struct MongoSuggestionRepository {
+    context: Collection<SuggestionDismissal>
+}
+
+impl MongoSuggestionRepository {
+    pub fn new(context: Collection<SuggestionDismissal>) -> Self {
+        Self {
+            context
+        }
+    }
+
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![
+            IndexModel::builder()
+                .keys(doc! { "user_id": 1, "dismissed_id": 1 })
+                .options(IndexOptions::builder().name(String::from("unique_dismissal")).unique(true).build())
+                .build()
+        ]
+    }
+}
+
+#[async_trait]
+impl InsertRepository<SuggestionDismissal, SuggestionDismissal, InsertDatabaseError> for MongoSuggestionRepository {
+    async fn insert(&self, data: SuggestionDismissal) -> Result<SuggestionDismissal, InsertDatabaseError> {
+        let query = doc! { "user_id": &data.user_id, "dismissed_id": &data.dismissed_id };
+
+        if let Some(existing) = self.context.find_one(query.clone(), None).await? {
+            return Ok(existing);
+        }
+
+        match self.context.insert_one(&data, None).await {
+            Ok(_) => Ok(data),
+            // inserted by a concurrent request in the meantime
+            Err(err) if is_duplicate_key(&err) => Ok(self.context.find_one(query, None).await?.unwrap_or(data)),
+            Err(err) => Err(err.into())
+        }
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectUserById, Vec<SuggestionDismissal>, SelectDatabaseError> for MongoSuggestionRepository {
+    async fn select(&self, data: &SelectUserById) -> Result<Vec<SuggestionDismissal>, SelectDatabaseError> {
+        let mut dismissals = vec![];
+        let mut cursor = self.context.find(doc! { "user_id": &data.id }, None)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)?;
+
+        while let Some(document) = cursor.next().await {
+            dismissals.push(document.map_err(SelectDatabaseError::DatabaseError)?);
+        }
+
+        Ok(dismissals)
+    }
+}
+
+impl SuggestionRepository for MongoSuggestionRepository {}
@@ -1,30 +1,51 @@
 use async_trait::async_trait;
+use futures_util::StreamExt;
 use mongodb::bson::doc;
-use mongodb::Collection;
-use crate::database::repositories::{SelectRepository, InsertRepository, UpdateRepository};
-use crate::model::user::{CreateUser, CreateUserError, FetchUserError, SelectUserById, SelectUserByName, UpdateUser, User};
+use mongodb::{Collection, IndexModel};
+use mongodb::error::{ErrorKind, WriteFailure};
+use mongodb::options::{FindOneAndUpdateOptions, IndexOptions, ReturnDocument};
+use mongodb::results::InsertOneResult;
+use crate::database::repositories::{UserRepository, DUPLICATE_KEY, SelectRepository, InsertRepository, TransactionalInsertRepository, UpdateRepository};
+use crate::database::unit_of_work::UnitOfWork;
+use crate::model::{SelectDatabaseError, UpdateDatabaseError};
+use crate::model::user::{CreateUser, CreateUserError, FetchUserError, RestoreUserProfile, SelectUserByFeedToken, SelectUserById, SelectUserByName, SelectUsersByIds, SetPostCount, UpdateFeedToken, UpdateLastSeenChangelog, UpdatePostCount, UpdateUser, User};
 
-pub struct UserRepository {
+pub struct MongoUserRepository {
     context: Collection<User>,
 }
 
 
-impl UserRepository {
+impl MongoUserRepository {
     pub fn new(context: Collection<User>) -> Self {
         Self {
             context,
         }
     }
-}
 
-impl UserRepository {
-    pub fn get_context(&self) -> &Collection<User> {
-        &self.context
+    // the name is unique, so users created at the same time can not take the same one. Users without a feed
+    // token store it as null, which a sparse index would still include, so only set tokens are indexed
+    pub fn indexes() -> Vec<IndexModel> {
+        let unique_feed_token = IndexOptions::builder()
+            .name(String::from("unique_feed_token"))
+            .unique(true)
+            .partial_filter_expression(doc! { "feed_token": { "$type": "string" } })
+            .build();
+
+        vec![
+            IndexModel::builder()
+                .keys(doc! { "name": 1 })
+                .options(IndexOptions::builder().name(String::from("unique_name")).unique(true).build())
+                .build(),
+            IndexModel::builder()
+                .keys(doc! { "feed_token": 1 })
+                .options(unique_feed_token)
+                .build()
+        ]
     }
 }
 
 #[async_trait]
-impl SelectRepository<SelectUserByName<'_>, User, FetchUserError> for UserRepository {
+impl SelectRepository<SelectUserByName<'_>, User, FetchUserError> for MongoUserRepository {
     async fn select(&self, data: &SelectUserByName) -> Result<User, FetchUserError> {
         if let Ok(Some(user)) = self.context.find_one(doc! { "name": &data.username }, None).await {
             return Ok(user);
@@ -35,7 +56,7 @@ impl SelectRepository<SelectUserByName<'_>, User, FetchUserError> for UserReposi
 }
 
 #[async_trait]
-impl SelectRepository<SelectUserById, User, FetchUserError> for UserRepository {
+impl SelectRepository<SelectUserById, User, FetchUserError> for MongoUserRepository {
     async fn select(&self, data: &SelectUserById) -> Result<User, FetchUserError> {
         if let Ok(Some(user)) = self.context.find_one(doc! { "_id": &data.id }, None).await {
             return Ok(user);
@@ -46,26 +67,149 @@ impl SelectRepository<SelectUserById, User, FetchUserError> for UserRepository {
 }
 
 #[async_trait]
-impl InsertRepository<CreateUser, User, CreateUserError> for UserRepository {
+impl SelectRepository<SelectUsersByIds, Vec<User>, SelectDatabaseError> for MongoUserRepository {
+    async fn select(&self, data: &SelectUsersByIds) -> Result<Vec<User>, SelectDatabaseError> {
+        let mut users = vec![];
+        let mut cursor = self.context.find(doc! { "_id": { "$in": data.ids.clone() } }, None)
+            .await
+            .map_err(SelectDatabaseError::DatabaseError)?;
+
+        while let Some(document) = cursor.next().await {
+            users.push(document.map_err(SelectDatabaseError::DatabaseError)?);
+        }
+
+        Ok(users)
+    }
+}
+
+#[async_trait]
+impl InsertRepository<CreateUser, User, CreateUserError> for MongoUserRepository {
     async fn insert(&self, create_user: CreateUser) -> Result<User, CreateUserError> {
         if let Ok(Some(_)) = self.context.find_one(doc! { "name": &create_user.username }, None).await {
             return Err(CreateUserError::UserNameTaken);
         }
 
         let user = User::try_from(create_user)?;
-        self.context.insert_one(&user, None).await?;
+        let result = self.context.insert_one(&user, None).await;
+
+        inserted_user(user, result)
+    }
+}
+
+#[async_trait]
+impl TransactionalInsertRepository<CreateUser, User, CreateUserError> for MongoUserRepository {
+    async fn insert_in(&self, unit: &mut UnitOfWork, create_user: CreateUser) -> Result<User, CreateUserError> {
+        let Some(session) = unit.session() else {
+            return self.insert(create_user).await;
+        };
+
+        if let Ok(Some(_)) = self.context.find_one_with_session(doc! { "name": &create_user.username }, None, &mut *session).await {
+            return Err(CreateUserError::UserNameTaken);
+        }
+
+        let user = User::try_from(create_user)?;
+        let result = self.context.insert_one_with_session(&user, None, session).await;
 
-        Ok(user)
+        inserted_user(user, result)
+    }
+}
+
+fn inserted_user(user: User, result: mongodb::error::Result<InsertOneResult>) -> Result<User, CreateUserError> {
+    match result {
+        Ok(_) => Ok(user),
+        // the name was taken in the meantime
+        Err(err) => match *err.kind {
+            ErrorKind::Write(WriteFailure::WriteError(ref write_error)) if write_error.code == DUPLICATE_KEY => Err(CreateUserError::UserNameTaken),
+            _ => Err(err.into())
+        }
     }
 }
 
 #[async_trait]
-impl UpdateRepository<UpdateUser, User, FetchUserError> for UserRepository {
+impl UpdateRepository<UpdateUser, User, FetchUserError> for MongoUserRepository {
     async fn update(&self, data: &UpdateUser) -> Result<User, FetchUserError> {
-        if let Ok(Some(user)) = self.context.find_one_and_update( doc! { "_id": &data.target_id }, doc! { "$set": { "description": &data.new_description }}, None).await {
+        let mut update = doc! { "description": &data.new_description };
+        if let Some(is_public) = data.new_is_public {
+            update.insert("is_public", is_public);
+        }
+
+        let options = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+
+        if let Ok(Some(user)) = self.context.find_one_and_update( doc! { "_id": &data.target_id }, doc! { "$set": update }, options).await {
             return Ok(user);
         };
 
         Err(FetchUserError::UserNotFound)
     }
 }
+
+#[async_trait]
+impl UpdateRepository<RestoreUserProfile, User, FetchUserError> for MongoUserRepository {
+    async fn update(&self, data: &RestoreUserProfile) -> Result<User, FetchUserError> {
+        let update = doc! { "$set": { "description": &data.description, "is_bot": data.is_bot, "is_public": data.is_public }};
+
+        if let Ok(Some(user)) = self.context.find_one_and_update(doc! { "_id": &data.target_id }, update, None).await {
+            return Ok(user);
+        };
+
+        Err(FetchUserError::UserNotFound)
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<UpdatePostCount, bool, UpdateDatabaseError> for MongoUserRepository {
+    async fn update(&self, data: &UpdatePostCount) -> Result<bool, UpdateDatabaseError> {
+        let mut query = doc! { "_id": &data.target_id };
+        // never drop below zero, when the counter and the disk disagree
+        if data.change < 0 {
+            query.insert("amount_posts", doc! { "$gte": -data.change });
+        }
+
+        let result = self.context.update_one(query, doc! { "$inc": { "amount_posts": data.change } }, None).await?;
+
+        Ok(result.matched_count > 0)
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<SetPostCount, bool, UpdateDatabaseError> for MongoUserRepository {
+    async fn update(&self, data: &SetPostCount) -> Result<bool, UpdateDatabaseError> {
+        let amount_posts = i64::try_from(data.amount_posts).unwrap_or(i64::MAX);
+        let result = self.context.update_one(doc! { "_id": &data.target_id }, doc! { "$set": { "amount_posts": amount_posts } }, None).await?;
+
+        Ok(result.matched_count > 0)
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<UpdateLastSeenChangelog, bool, UpdateDatabaseError> for MongoUserRepository {
+    async fn update(&self, data: &UpdateLastSeenChangelog) -> Result<bool, UpdateDatabaseError> {
+        let result = self.context.update_one(doc! { "_id": &data.target_id }, doc! { "$set": { "last_seen_changelog": data.version.to_string() } }, None).await?;
+
+        Ok(result.matched_count > 0)
+    }
+}
+
+#[async_trait]
+impl SelectRepository<SelectUserByFeedToken<'_>, User, FetchUserError> for MongoUserRepository {
+    async fn select(&self, data: &SelectUserByFeedToken) -> Result<User, FetchUserError> {
+        if let Ok(Some(user)) = self.context.find_one(doc! { "feed_token": data.token }, None).await {
+            return Ok(user);
+        }
+
+        Err(FetchUserError::UserNotFound)
+    }
+}
+
+#[async_trait]
+impl UpdateRepository<UpdateFeedToken, bool, UpdateDatabaseError> for MongoUserRepository {
+    async fn update(&self, data: &UpdateFeedToken) -> Result<bool, UpdateDatabaseError> {
+        let result = self.context.update_one(doc! { "_id": &data.target_id }, doc! { "$set": { "feed_token": &data.token } }, None).await?;
+
+        Ok(result.matched_count > 0)
+    }
+}
+
+impl UserRepository for MongoUserRepository {}
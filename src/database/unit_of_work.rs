@@ -0,0 +1,84 @@
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use mongodb::ClientSession;
+
+// the work of a unit, it borrows the unit for as long as it runs
+pub type UnitFuture<'u, T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + 'u>>;
+
+// repository operations given the unit share its session and transaction
+pub struct UnitOfWork {
+    // backends without sessions, like the in memory one, run the operations on their own
+    session: Option<ClientSession>,
+    transaction: bool,
+    // the filesystem can not be part of a transaction, so created directories are removed by hand
+    created_directories: Vec<PathBuf>
+}
+
+impl UnitOfWork {
+    pub fn new(session: Option<ClientSession>) -> Self {
+        Self {
+            session,
+            transaction: false,
+            created_directories: vec![]
+        }
+    }
+
+    pub fn session(&mut self) -> Option<&mut ClientSession> {
+        self.session.as_mut()
+    }
+
+    // does nothing without a session
+    pub async fn start_transaction(&mut self) -> mongodb::error::Result<()> {
+        if let Some(session) = &mut self.session {
+            session.start_transaction(None).await?;
+            self.transaction = true;
+        }
+
+        Ok(())
+    }
+
+    pub async fn commit(&mut self) -> mongodb::error::Result<()> {
+        match &mut self.session {
+            Some(session) if self.transaction => session.commit_transaction().await,
+            _ => Ok(())
+        }
+    }
+
+    pub async fn abort(&mut self) {
+        if let Some(session) = &mut self.session {
+            if self.transaction {
+                if let Err(err) = session.abort_transaction().await {
+                    log::warn!("Could not abort a transaction: {}", err);
+                }
+            }
+        }
+    }
+
+    // removed again when the unit fails. Directories that already existed are kept
+    pub fn create_dir_all(&mut self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let path = path.as_ref();
+        // the outermost directory that is created, removing it removes the ones below
+        let created = path.ancestors()
+            .take_while(|ancestor| !ancestor.as_os_str().is_empty() && !ancestor.exists())
+            .last()
+            .map(Path::to_path_buf);
+
+        std::fs::create_dir_all(path)?;
+
+        if let Some(created) = created {
+            self.created_directories.push(created);
+        }
+
+        Ok(())
+    }
+
+    // newest first, in case one was created inside another
+    pub fn compensate(self) {
+        for path in self.created_directories.into_iter().rev() {
+            if let Err(err) = std::fs::remove_dir_all(&path) {
+                log::warn!("Could not remove {} after a failed unit of work: {}", path.display(), err);
+            }
+        }
+    }
+}
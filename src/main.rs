@@ -1,10 +1,13 @@
 use std::env;
+use std::sync::Arc;
 use actix_cors::Cors;
 use actix_web::{App, HttpServer, web};
 use actix_web::middleware::{Condition, Logger};
 use actix_web::web::Data;
 use actix_web_httpauth::middleware::HttpAuthentication;
 use crate::api::user::create_user;
+use crate::database::mongo_context::MongoDatabaseContext;
+use crate::migrations::runner::MigrationRunner;
 
 use crate::model::states::app_state::AppState;
 
@@ -22,20 +25,56 @@ async fn main() -> std::io::Result<()> {
     dotenv::dotenv().ok();
     env_logger::init();
 
+    let args = env::args().skip(1).collect::<Vec<_>>();
     let mut debug_mode = false;
 
-    if env::args().skip(1).collect::<Vec<_>>().contains(&String::from("--debug")) {
+    if args.contains(&String::from("--debug")) {
         log::info!("Debug mode. Enabled CORS and \"User create service\"");
         debug_mode = true;
     }
 
-    let app_state = AppState::from_env().await.unwrap_or_else(|e| {
+    let database = Arc::new(MongoDatabaseContext::new().await.unwrap_or_else(|e| {
         log::error!("{:?}", e);
         std::process::exit(1);
+    }));
+
+    let app_state = AppState::from_env(database.clone()).unwrap_or_else(|e| {
+        log::error!("{}", e);
+        std::process::exit(1);
     });
 
+    let runner = MigrationRunner::new(&database, migrations::registry(&app_state.data_directory));
+
+    // `migrate [--dry-run]` only migrates the database, without starting the server
+    if args.first().is_some_and(|command| command == "migrate") {
+        let dry_run = args.contains(&String::from("--dry-run"));
+        let versions = runner.run(dry_run).await.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+
+        match (dry_run, versions.is_empty()) {
+            (_, true) => println!("The database is up to date"),
+            (true, false) => println!("Pending migrations: {}", versions.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")),
+            (false, false) => println!("Applied migrations: {}", versions.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))
+        }
+
+        return Ok(());
+    }
+
+    let applied = runner.run_when_unlocked().await.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+    if !applied.is_empty() {
+        log::info!("Applied {} migrations", applied.len());
+    }
+    drop(runner);
+
     // create or do nothing, when created
     std::fs::create_dir_all(&app_state.data_directory)?;
+    std::fs::create_dir_all(&app_state.export_directory)?;
+
+    // changelog files only seed the database, afterwards they are published by admins
+    match api::changelog::seed_changelogs(&*app_state.db).await {
+        Ok(0) => {},
+        Ok(seeded) => log::info!("Seeded {} changelogs", seeded),
+        Err(e) => log::warn!("Could not seed the changelogs: {}", e)
+    }
 
     let (ip, port) = app_state.ip_port_tuple.clone();
 
@@ -58,6 +97,7 @@ async fn main() -> std::io::Result<()> {
                 .service(api::authentication::cookie_revalidate)
                 .service(api::authentication::cookie_auth)
                 .service(api::authentication::logout_cookie)
+                .service(api::changelog::changelog_feed)
                 .service(web::scope("/media")
                     .wrap(cookie_middleware.clone())
                     .service(api::media::list)
@@ -65,23 +105,84 @@ async fn main() -> std::io::Result<()> {
                     .service(api::media::stories)
                     .service(api::media::story)
                     .service(api::media::upload_story)
-                    .service(actix_files::Files::new("/", &app_state.data_directory))
+                    .service(api::media::delete_post)
+                    // after the stories, which would match it as well
+                    .service(api::media::post)
                 )
                 .service(web::scope("/user")
                     .wrap(cookie_middleware.clone())
                     .service(api::user::get_friends)
                     .service(api::user::post_friendship)
+                    .service(api::user::delete_friendship)
+                    .service(api::user::accept_friendship)
+                    .service(api::user::decline_friendship)
+                    .service(api::user::cancel_friendship)
+                    .service(api::user::incoming_friend_requests)
+                    .service(api::user::outgoing_friend_requests)
+                    .service(api::suggestion::suggestions)
+                    .service(api::suggestion::dismiss_suggestion)
+                    .service(api::follow::follow)
+                    .service(api::follow::unfollow)
+                    .service(api::follow::followers)
+                    .service(api::follow::following)
+                    .service(api::close_friend::close_friends)
+                    .service(api::close_friend::add_close_friend)
+                    .service(api::close_friend::remove_close_friend)
+                    .service(api::restriction::block)
+                    .service(api::restriction::unblock)
+                    .service(api::restriction::blocked)
+                    .service(api::restriction::mute)
+                    .service(api::restriction::unmute)
+                    .service(api::restriction::muted)
                     .service(api::user::upload_avatar)
                     .service(api::user::put_user_information)
-                    .service(api::user::list)
-                    .service(api::user::put_list)
+                    .service(api::list::lists)
+                    .service(api::list::create_list)
+                    .service(api::list::get_list_by_id)
+                    .service(api::list::update_list)
+                    .service(api::list::delete_list)
+                    .service(api::list::get_list)
+                    .service(api::list::put_list)
+                    .service(api::list::add_entry)
+                    .service(api::list::update_entry)
+                    .service(api::list::delete_entry)
+                    .service(api::list::reorder_entries)
+                    .service(api::list::add_sub_entry)
+                    .service(api::list::update_sub_entry)
+                    .service(api::list::delete_sub_entry)
+                    .service(api::list::reorder_sub_entries)
+                    .service(api::list::list_shares)
+                    .service(api::list::share_list)
+                    .service(api::list::unshare_list)
+                    .service(api::list::shared_lists)
+                    .service(api::list::due_entries)
+                    .service(api::list::export_list)
+                    .service(api::list::import_list)
+                    .service(api::list_sync::sync_list)
+                    .service(api::list_revision::get_revisions)
+                    .service(api::list_revision::get_revision)
+                    .service(api::list_revision::restore_revision)
                     .service(api::user::full_profile_information)
                     .service(api::user::avatar)
+                    .service(api::takeout::export)
+                    .service(api::takeout::export_status)
+                    .service(api::takeout::download_export)
+                    .service(api::takeout::import)
                 )
                 .service(web::scope("")
                     .wrap(cookie_middleware)
                     .service(api::changelog::changelog)
                     .service(api::changelog::changelog_version)
+                    .service(api::changelog::changelog_history)
+                    .service(api::changelog::unread_changelog)
+                    .service(api::changelog::mark_changelog_seen)
+                    .service(api::changelog::create_feed_token)
+                    .service(api::changelog::delete_feed_token)
+                    .service(api::changelog::admin_changelogs)
+                    .service(api::changelog::create_changelog)
+                    .service(api::changelog::update_changelog)
+                    .service(api::changelog::publish_changelog)
+                    .service(api::changelog::delete_changelog)
                     .service(api::user::who_am_i)
                 )
             )
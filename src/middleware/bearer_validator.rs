@@ -7,7 +7,6 @@ use hmac::digest::KeyInit;
 use hmac::Hmac;
 use jwt::VerifyWithKey;
 use sha2::Sha256;
-use crate::database::repositories::SelectRepository;
 use crate::middleware::TokenClaims;
 use crate::model::states::app_state::AppState;
 use crate::model::user::{SelectUserById};
@@ -2,7 +2,6 @@ use actix_web::dev::{ServiceRequest};
 use actix_web::{Error};
 use actix_web::error::ErrorUnauthorized;
 use actix_web::web::{Data};
-use crate::database::repositories::SelectRepository;
 use crate::middleware::TokenClaims;
 use crate::model::states::app_state::AppState;
 use crate::model::user::SelectUserById;
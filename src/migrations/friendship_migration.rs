@@ -0,0 +1,57 @@
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use mongodb::bson::{doc, Document};
+use crate::database::database_context::DatabaseContext;
+use crate::database::mongo_context::MongoDatabaseContext;
+use crate::migrations::DatabaseMigration;
+use crate::model::friend::CreateFriendshipError;
+use crate::model::friendship::{Friendship, FriendshipStatus};
+use crate::utils::version::Version;
+
+// friendships used to store friend_a as "_id", so every user could only request a single friendship
+pub struct FriendshipMigration;
+
+#[async_trait]
+impl DatabaseMigration for FriendshipMigration {
+    fn version(&self) -> Version {
+        Version::new(1, 1, 0)
+    }
+
+    fn description(&self) -> &'static str {
+        "Giving friendships their own id and a normalized member pair"
+    }
+
+    async fn migrate(&self, context: &MongoDatabaseContext) -> anyhow::Result<()> {
+        let friendship_repo = context.friendship();
+        let documents = context.friendships().clone_with_type::<Document>();
+
+        let old_schema = doc! { "member_low": { "$exists": false } };
+        let mut cursor = documents.find(old_schema.clone(), None).await?;
+        let mut old_friendships = vec![];
+
+        while let Some(document) = cursor.next().await {
+            old_friendships.push(document?);
+        }
+
+        for document in old_friendships {
+            let friend_a = document.get_object_id("_id")?;
+            let friend_b = document.get_object_id("friend_b")?;
+            let status = match document.get_str("status") {
+                Ok("pending") => FriendshipStatus::Pending,
+                _ => FriendshipStatus::Accepted
+            };
+
+            match friendship_repo.insert(Friendship::new(friend_a, friend_b, status)).await {
+                // the pair was stored in both directions, the first one wins
+                Ok(_) | Err(CreateFriendshipError::AlreadyFriends) | Err(CreateFriendshipError::RequestPending) => {},
+                Err(err) => return Err(err.into())
+            }
+
+            let mut old_document = old_schema.clone();
+            old_document.insert("_id", friend_a);
+            documents.delete_one(old_document, None).await?;
+        }
+
+        Ok(())
+    }
+}
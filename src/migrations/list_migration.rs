@@ -0,0 +1,63 @@
+use std::fs::File;
+use std::io::BufReader;
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use mongodb::bson::doc;
+use crate::database::database_context::DatabaseContext;
+use crate::database::mongo_context::MongoDatabaseContext;
+use crate::migrations::DatabaseMigration;
+use crate::model::list::{default_list_name, List, ListContent, SelectListByOwner};
+use crate::utils::version::Version;
+
+// lists used to be stored in information/list.json, which was also served by the static file service
+pub struct ListMigration {
+    pub data_directory: String
+}
+
+#[async_trait]
+impl DatabaseMigration for ListMigration {
+    fn version(&self) -> Version {
+        Version::new(1, 3, 0)
+    }
+
+    fn description(&self) -> &'static str {
+        "Importing list.json files into the lists collection"
+    }
+
+    async fn migrate(&self, context: &MongoDatabaseContext) -> anyhow::Result<()> {
+        let list_repo = context.list();
+
+        let mut cursor = context.users().find(doc! { }, None).await?;
+        let mut users = vec![];
+
+        while let Some(document) = cursor.next().await {
+            users.push(document?);
+        }
+
+        for user in users {
+            let path = format!("{}{}/information/list.json", self.data_directory, user.name);
+            let file = match File::open(&path) {
+                Ok(file) => file,
+                // users who never opened their list have no file
+                Err(_) => continue
+            };
+
+            let content: ListContent = match serde_json::from_reader(BufReader::new(file)) {
+                Ok(content) => content,
+                Err(err) => {
+                    log::warn!("Skipping unparsable list of {}: {}", user.name, err);
+                    continue;
+                }
+            };
+
+            // the migration already ran for this user
+            if list_repo.select(&SelectListByOwner { owner: user.id }).await?.is_none() {
+                list_repo.insert(List::new(user.id, default_list_name(), content.entries)).await?;
+            }
+
+            std::fs::remove_file(&path)?;
+        }
+
+        Ok(())
+    }
+}
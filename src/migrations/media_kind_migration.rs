@@ -0,0 +1,56 @@
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use mongodb::bson::{doc, to_bson};
+use crate::database::mongo_context::MongoDatabaseContext;
+use crate::migrations::DatabaseMigration;
+use crate::model::media::MediaKind;
+use crate::utils::read_files_in_directory;
+use crate::utils::version::Version;
+
+// media entries used to have no kind, and the entries of expired stories were never removed
+pub struct MediaKindMigration {
+    pub data_directory: String
+}
+
+#[async_trait]
+impl DatabaseMigration for MediaKindMigration {
+    fn version(&self) -> Version {
+        Version::new(1, 4, 0)
+    }
+
+    fn description(&self) -> &'static str {
+        "Storing the \"kind\" of media entries and removing the entries of expired stories"
+    }
+
+    async fn migrate(&self, context: &MongoDatabaseContext) -> anyhow::Result<()> {
+        let mut cursor = context.users().find(doc! { }, None).await?;
+        let mut users = vec![];
+
+        while let Some(document) = cursor.next().await {
+            users.push(document?);
+        }
+
+        for user in users {
+            let directory = format!("{}{}", self.data_directory, user.name);
+            for (kind, path) in [(MediaKind::Post, directory.clone()), (MediaKind::Story, format!("{}/stories", directory))] {
+                // users without a directory did not upload anything yet
+                let file_names = read_files_in_directory(&path, false)
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|file| file.file_name().into_string().ok())
+                    .collect::<Vec<String>>();
+
+                context.media_entries().update_many(
+                    doc! { "owner": user.id, "kind": { "$exists": false }, "file_name": { "$in": file_names } },
+                    doc! { "$set": { "kind": to_bson(&kind)? } },
+                    None
+                ).await?;
+            }
+
+            // neither a post nor a story on disk anymore
+            context.media_entries().delete_many(doc! { "owner": user.id, "kind": { "$exists": false } }, None).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,11 +1,32 @@
 use async_trait::async_trait;
-use crate::database::database_context::DatabaseContext;
+use crate::database::mongo_context::MongoDatabaseContext;
 use crate::utils::version::Version;
 
 pub mod user_migration;
+pub mod friendship_migration;
+pub mod post_count_migration;
+pub mod list_migration;
+pub mod media_kind_migration;
+pub mod runner;
 
 #[async_trait]
 pub trait DatabaseMigration {
-    fn version(&self) -> &Version;
-    async fn migrate(&self, context: &DatabaseContext) -> anyhow::Result<()>;
-}
\ No newline at end of file
+    // recorded in the ledger once the migration ran, so every version can only be used once
+    fn version(&self) -> Version;
+    fn description(&self) -> &'static str;
+    async fn migrate(&self, context: &MongoDatabaseContext) -> anyhow::Result<()>;
+}
+
+// every migration, new ones have to be added here
+pub fn registry(data_directory: &str) -> Vec<Box<dyn DatabaseMigration>> {
+    let mut migrations: Vec<Box<dyn DatabaseMigration>> = vec![
+        Box::new(user_migration::UserMigration),
+        Box::new(friendship_migration::FriendshipMigration),
+        Box::new(post_count_migration::PostCountMigration { data_directory: data_directory.to_string() }),
+        Box::new(list_migration::ListMigration { data_directory: data_directory.to_string() }),
+        Box::new(media_kind_migration::MediaKindMigration { data_directory: data_directory.to_string() })
+    ];
+    migrations.sort_by_key(|migration| migration.version());
+
+    migrations
+}
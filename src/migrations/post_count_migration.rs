@@ -0,0 +1,44 @@
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use mongodb::bson::doc;
+use crate::database::database_context::DatabaseContext;
+use crate::database::mongo_context::MongoDatabaseContext;
+use crate::migrations::DatabaseMigration;
+use crate::model::user::SetPostCount;
+use crate::utils::count_files_in_directory;
+use crate::utils::version::Version;
+
+// post counts used to be read from disk on every request
+pub struct PostCountMigration {
+    pub data_directory: String
+}
+
+#[async_trait]
+impl DatabaseMigration for PostCountMigration {
+    fn version(&self) -> Version {
+        Version::new(1, 2, 0)
+    }
+
+    fn description(&self) -> &'static str {
+        "Storing \"amount_posts\" in user documents"
+    }
+
+    async fn migrate(&self, context: &MongoDatabaseContext) -> anyhow::Result<()> {
+        let user_repo = context.user();
+
+        let mut cursor = context.users().find(doc! { }, None).await?;
+        let mut users = vec![];
+
+        while let Some(document) = cursor.next().await {
+            users.push(document?);
+        }
+
+        for user in users {
+            // users without a directory did not upload anything yet
+            let amount_posts = count_files_in_directory(&format!("{}{}", self.data_directory, user.name)).unwrap_or(0);
+            user_repo.update(&SetPostCount { target_id: user.id, amount_posts }).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+use chrono::Utc;
+use uuid::Uuid;
+use crate::database::mongo_context::MongoDatabaseContext;
+use crate::database::repositories::{DeleteRepository, InsertRepository, SelectRepository, UpdateRepository};
+use crate::migrations::DatabaseMigration;
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError, UpdateDatabaseError};
+use crate::model::migration::{AcquireMigrationLock, AppliedMigration, ReleaseMigrationLock, SelectAppliedMigrations};
+use crate::utils::version::Version;
+
+// longer than any migration should take, a crashed instance blocks the others at most this long
+const LOCK_DURATION_MINUTES: i64 = 30;
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+pub enum MigrationError {
+    // another instance is migrating
+    Locked,
+    DuplicateVersion(Version),
+    Failed(Version, anyhow::Error),
+    Database
+}
+
+impl Display for MigrationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrationError::Locked => write!(f, "Another instance is migrating the database"),
+            MigrationError::DuplicateVersion(version) => write!(f, "Several migrations use version {}", version),
+            MigrationError::Failed(version, err) => write!(f, "Migration for version {} failed: {:?}", version, err),
+            MigrationError::Database => write!(f, "Could not read or write the migration ledger")
+        }
+    }
+}
+
+impl std::error::Error for MigrationError { }
+
+pub struct MigrationRunner<'a> {
+    context: &'a MongoDatabaseContext,
+    migrations: Vec<Box<dyn DatabaseMigration>>
+}
+
+impl<'a> MigrationRunner<'a> {
+    pub fn new(context: &'a MongoDatabaseContext, migrations: Vec<Box<dyn DatabaseMigration>>) -> Self {
+        Self {
+            context,
+            migrations
+        }
+    }
+
+    // the migrations the ledger does not know yet, oldest first
+    pub async fn pending(&self) -> Result<Vec<&dyn DatabaseMigration>, MigrationError> {
+        let applied = self.context.migration().select(&SelectAppliedMigrations).await?;
+
+        let mut pending: Vec<&dyn DatabaseMigration> = vec![];
+        for migration in &self.migrations {
+            let version = migration.version();
+            if pending.last().is_some_and(|previous| previous.version() == version) {
+                return Err(MigrationError::DuplicateVersion(version));
+            }
+            if !applied.iter().any(|applied| applied.version == version) {
+                pending.push(migration.as_ref());
+            }
+        }
+
+        Ok(pending)
+    }
+
+    // runs the pending migrations in order and records each one. A dry run only returns the versions that would run
+    pub async fn run(&self, dry_run: bool) -> Result<Vec<Version>, MigrationError> {
+        if dry_run {
+            let pending = self.pending().await?;
+            for migration in &pending {
+                log::info!("Would run the migration for version {}. {}", migration.version(), migration.description());
+            }
+
+            return Ok(pending.iter().map(|migration| migration.version()).collect());
+        }
+
+        let owner = Uuid::new_v4().to_string();
+        let lock = AcquireMigrationLock {
+            owner: owner.clone(),
+            expires_at: Utc::now() + chrono::Duration::minutes(LOCK_DURATION_MINUTES),
+        };
+        if !self.context.migration().update(&lock).await? {
+            return Err(MigrationError::Locked);
+        }
+
+        let result = self.run_locked().await;
+        self.context.migration().delete(&ReleaseMigrationLock { owner }).await?;
+
+        result
+    }
+
+    // waits while another instance is migrating, afterwards there is usually nothing left to do
+    pub async fn run_when_unlocked(&self) -> Result<Vec<Version>, MigrationError> {
+        loop {
+            match self.run(false).await {
+                Err(MigrationError::Locked) => {
+                    log::info!("Another instance is migrating the database, waiting");
+                    actix_web::rt::time::sleep(LOCK_RETRY_INTERVAL).await;
+                },
+                result => return result
+            }
+        }
+    }
+
+    // the ledger is read after locking, so migrations another instance just applied are skipped
+    async fn run_locked(&self) -> Result<Vec<Version>, MigrationError> {
+        let mut applied = vec![];
+
+        for migration in self.pending().await? {
+            let version = migration.version();
+            log::info!("Migration for version {}. {}", version, migration.description());
+
+            migration.migrate(self.context)
+                .await
+                .map_err(|err| MigrationError::Failed(version.clone(), err))?;
+
+            self.context.migration().insert(AppliedMigration {
+                version: version.clone(),
+                description: migration.description().to_string(),
+                applied_at: Utc::now(),
+            }).await?;
+            applied.push(version);
+        }
+
+        Ok(applied)
+    }
+}
+
+impl From<SelectDatabaseError> for MigrationError {
+    fn from(_: SelectDatabaseError) -> Self {
+        MigrationError::Database
+    }
+}
+
+impl From<InsertDatabaseError> for MigrationError {
+    fn from(_: InsertDatabaseError) -> Self {
+        MigrationError::Database
+    }
+}
+
+impl From<UpdateDatabaseError> for MigrationError {
+    fn from(_: UpdateDatabaseError) -> Self {
+        MigrationError::Database
+    }
+}
+
+impl From<DeleteDatabaseError> for MigrationError {
+    fn from(_: DeleteDatabaseError) -> Self {
+        MigrationError::Database
+    }
+}
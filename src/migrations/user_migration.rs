@@ -1,30 +1,27 @@
 use async_trait::async_trait;
 use mongodb::bson::doc;
-use crate::database::database_context::DatabaseContext;
+use crate::database::mongo_context::MongoDatabaseContext;
 use crate::migrations::DatabaseMigration;
 use crate::utils::version::Version;
 
-pub struct UserMigration {
-    pub version: Version
-}
+pub struct UserMigration;
 
 #[async_trait]
 impl DatabaseMigration for UserMigration {
-    fn version(&self) -> &Version {
-        &self.version
+    fn version(&self) -> Version {
+        Version::new(1, 0, 0)
     }
 
-    async fn migrate(&self, context: &DatabaseContext) -> anyhow::Result<()> {
-        println!("Migration for version {:?}. Applying \"is_bot\"-field to user documents", self.version);
-        let user_repo = context.user();
-        let context = user_repo.get_context();
+    fn description(&self) -> &'static str {
+        "Applying \"is_bot\"-field to user documents"
+    }
 
-        let update = doc! { "$set": { "is_bot": false } };
-        let options = mongodb::options::UpdateOptions::builder()
-            .upsert(true)
-            .build();
+    async fn migrate(&self, context: &MongoDatabaseContext) -> anyhow::Result<()> {
+        let context = context.users();
 
-        context.update_many(doc! { }, update, options).await?;
+        // users that already have the field keep it, so running it again does not demote bots
+        let update = doc! { "$set": { "is_bot": false } };
+        context.update_many(doc! { "is_bot": { "$exists": false } }, update, None).await?;
 
         Ok(())
     }
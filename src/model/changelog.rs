@@ -0,0 +1,276 @@
+use std::fmt::{Display, Formatter};
+use actix_web::http::StatusCode;
+use actix_web::ResponseError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError, UpdateDatabaseError};
+use crate::model::user::FetchUserError;
+use crate::utils::version::Version;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangelogSection {
+    Added,
+    Fixed,
+    Changed
+}
+
+impl ChangelogSection {
+    // headings like `## Added`, `Fixed:` or `Bugfixes`
+    fn from_heading(line: &str) -> Option<Self> {
+        let heading = line.trim_start_matches('#').trim().trim_end_matches(':').trim().to_lowercase();
+
+        match heading.as_str() {
+            "added" | "new" | "features" => Some(ChangelogSection::Added),
+            "fixed" | "fixes" | "bugfixes" => Some(ChangelogSection::Fixed),
+            "changed" | "changes" | "improvements" => Some(ChangelogSection::Changed),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangelogStatus {
+    // only visible to admins
+    Draft,
+    Published
+}
+
+// the version is the id, so every version exists only once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangelogEntry {
+    #[serde(rename="_id")]
+    pub version: Version,
+    pub status: ChangelogStatus,
+    pub added: Vec<String>,
+    pub fixed: Vec<String>,
+    pub changed: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub published_at: Option<DateTime<Utc>>
+}
+
+impl ChangelogEntry {
+    pub fn draft(changelog: ChangelogVersion) -> Self {
+        let now = Utc::now();
+
+        Self {
+            version: changelog.version,
+            status: ChangelogStatus::Draft,
+            added: changelog.added,
+            fixed: changelog.fixed,
+            changed: changelog.changed,
+            created_at: now,
+            updated_at: now,
+            published_at: None,
+        }
+    }
+
+    pub fn published(changelog: ChangelogVersion) -> Self {
+        let entry = Self::draft(changelog);
+
+        Self {
+            status: ChangelogStatus::Published,
+            published_at: Some(entry.created_at),
+            ..entry
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangelogVersion {
+    pub version: Version,
+    pub added: Vec<String>,
+    pub fixed: Vec<String>,
+    pub changed: Vec<String>
+}
+
+impl From<ChangelogEntry> for ChangelogVersion {
+    fn from(value: ChangelogEntry) -> Self {
+        Self {
+            version: value.version,
+            added: value.added,
+            fixed: value.fixed,
+            changed: value.changed,
+        }
+    }
+}
+
+impl ChangelogVersion {
+    // lines before the first heading are changes, other headings are skipped
+    pub fn parse(version: Version, content: &str) -> Self {
+        let mut changelog = Self {
+            version,
+            added: vec![],
+            fixed: vec![],
+            changed: vec![],
+        };
+        let mut section = ChangelogSection::Changed;
+
+        for line in content.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if let Some(heading) = ChangelogSection::from_heading(line) {
+                section = heading;
+                continue;
+            }
+            if line.starts_with('#') {
+                continue;
+            }
+
+            let item = ["- ", "* ", "+ "].iter()
+                .find_map(|marker| line.strip_prefix(marker))
+                .unwrap_or(line)
+                .trim()
+                .to_string();
+
+            match section {
+                ChangelogSection::Added => changelog.added.push(item),
+                ChangelogSection::Fixed => changelog.fixed.push(item),
+                ChangelogSection::Changed => changelog.changed.push(item)
+            }
+        }
+
+        changelog
+    }
+
+    // the sections as headings with their items, like a changelog file
+    pub fn lines(&self) -> Vec<String> {
+        [("Added", &self.added), ("Fixed", &self.fixed), ("Changed", &self.changed)]
+            .into_iter()
+            .filter(|(_, items)| !items.is_empty())
+            .flat_map(|(heading, items)| std::iter::once(heading.to_string()).chain(items.iter().map(|item| format!("- {}", item))))
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct UnreadChangelog {
+    pub last_seen: Option<Version>,
+    // newest first
+    pub versions: Vec<ChangelogVersion>
+}
+
+// what admins see, drafts included
+#[derive(Debug, Serialize)]
+pub struct ChangelogInformation {
+    pub version: Version,
+    pub status: ChangelogStatus,
+    pub added: Vec<String>,
+    pub fixed: Vec<String>,
+    pub changed: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub published_at: Option<DateTime<Utc>>
+}
+
+impl From<ChangelogEntry> for ChangelogInformation {
+    fn from(value: ChangelogEntry) -> Self {
+        Self {
+            version: value.version,
+            status: value.status,
+            added: value.added,
+            fixed: value.fixed,
+            changed: value.changed,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+            published_at: value.published_at,
+        }
+    }
+}
+
+// newest first, every status without one
+pub struct SelectChangelogs {
+    pub status: Option<ChangelogStatus>
+}
+
+pub struct SelectChangelog {
+    pub version: Version
+}
+
+// sections that are None stay as they are
+pub struct UpdateChangelog {
+    pub version: Version,
+    pub added: Option<Vec<String>>,
+    pub fixed: Option<Vec<String>>,
+    pub changed: Option<Vec<String>>
+}
+
+// only drafts can be published
+pub struct PublishChangelog {
+    pub version: Version
+}
+
+// only drafts can be deleted
+pub struct DeleteChangelogDraft {
+    pub version: Version
+}
+
+#[derive(Debug)]
+pub enum ChangelogError {
+    UserNotFound,
+    Unauthorized,
+    NotFound,
+    InvalidVersion,
+    AlreadyExists,
+    AlreadyPublished,
+    Internal
+}
+
+impl Display for ChangelogError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            ChangelogError::UserNotFound => "User not found",
+            ChangelogError::Unauthorized => "Unauthorized",
+            ChangelogError::NotFound => "Changelog not found",
+            ChangelogError::InvalidVersion => "Invalid version",
+            ChangelogError::AlreadyExists => "A changelog for this version already exists",
+            ChangelogError::AlreadyPublished => "The changelog is already published",
+            ChangelogError::Internal => "Internal"
+        })
+    }
+}
+
+impl std::error::Error for ChangelogError { }
+
+impl ResponseError for ChangelogError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ChangelogError::UserNotFound => StatusCode::NOT_FOUND,
+            ChangelogError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ChangelogError::NotFound => StatusCode::NOT_FOUND,
+            ChangelogError::InvalidVersion => StatusCode::BAD_REQUEST,
+            ChangelogError::AlreadyExists => StatusCode::CONFLICT,
+            ChangelogError::AlreadyPublished => StatusCode::CONFLICT,
+            ChangelogError::Internal => StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+impl From<FetchUserError> for ChangelogError {
+    fn from(_: FetchUserError) -> Self {
+        ChangelogError::UserNotFound
+    }
+}
+
+impl From<InsertDatabaseError> for ChangelogError {
+    fn from(_: InsertDatabaseError) -> Self {
+        ChangelogError::Internal
+    }
+}
+
+impl From<SelectDatabaseError> for ChangelogError {
+    fn from(_: SelectDatabaseError) -> Self {
+        ChangelogError::Internal
+    }
+}
+
+impl From<UpdateDatabaseError> for ChangelogError {
+    fn from(_: UpdateDatabaseError) -> Self {
+        ChangelogError::Internal
+    }
+}
+
+impl From<DeleteDatabaseError> for ChangelogError {
+    fn from(_: DeleteDatabaseError) -> Self {
+        ChangelogError::Internal
+    }
+}
@@ -0,0 +1,34 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+// `friend` is one of the close friends of `owner`. Only valid, while both are friends
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloseFriend {
+    #[serde(rename="_id")]
+    pub id: ObjectId,
+    pub owner: ObjectId,
+    pub friend: ObjectId,
+    pub created_at: DateTime<Utc>
+}
+
+impl CloseFriend {
+    pub fn new(owner: ObjectId, friend: ObjectId) -> Self {
+        Self {
+            id: ObjectId::new(),
+            owner,
+            friend,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+pub struct SelectCloseFriend {
+    pub owner: ObjectId,
+    pub friend: ObjectId
+}
+
+pub struct DeleteCloseFriend {
+    pub owner: ObjectId,
+    pub friend: ObjectId
+}
@@ -0,0 +1,88 @@
+use chrono::{DateTime, Duration, Utc};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime_optional;
+use serde::{Deserialize, Serialize};
+
+// how long a finished archive can be downloaded
+pub const EXPORT_LIFETIME_HOURS: i64 = 24;
+// a job still pending after this was lost, e.g. by a restart of the server
+pub const EXPORT_TIMEOUT_HOURS: i64 = 6;
+// archives a user can keep at once, the oldest one is removed for a new export
+pub const MAX_EXPORTS_PER_USER: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportStatus {
+    Pending,
+    Finished,
+    Failed
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportJob {
+    #[serde(rename="_id")]
+    pub id: ObjectId,
+    pub user_id: ObjectId,
+    pub status: ExportStatus,
+    pub created_at: DateTime<Utc>,
+    // stored as a bson date, so the cleanup can query for it
+    #[serde(default, with = "chrono_datetime_as_bson_datetime_optional")]
+    pub expires_at: Option<DateTime<Utc>>
+}
+
+impl ExportJob {
+    pub fn new(user_id: ObjectId) -> Self {
+        let now = Utc::now();
+
+        Self {
+            id: ObjectId::new(),
+            user_id,
+            status: ExportStatus::Pending,
+            created_at: now,
+            expires_at: Some(now + Duration::hours(EXPORT_TIMEOUT_HOURS)),
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        matches!(self.expires_at, Some(expires_at) if expires_at < Utc::now())
+    }
+
+    // a pending job, which expired, is never finished
+    pub fn current_status(&self) -> ExportStatus {
+        match self.status {
+            ExportStatus::Pending if self.is_expired() => ExportStatus::Failed,
+            status => status
+        }
+    }
+}
+
+pub struct UpdateExportStatus {
+    pub target_id: ObjectId,
+    pub status: ExportStatus
+}
+
+impl UpdateExportStatus {
+    // failed jobs are removed with the finished ones
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        match self.status {
+            ExportStatus::Pending => Some(Utc::now() + Duration::hours(EXPORT_TIMEOUT_HOURS)),
+            ExportStatus::Finished | ExportStatus::Failed => Some(Utc::now() + Duration::hours(EXPORT_LIFETIME_HOURS))
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SelectExportById {
+    pub id: ObjectId
+}
+
+pub struct SelectExpiredExports;
+
+// oldest first
+pub struct SelectExportsByUser {
+    pub user_id: ObjectId
+}
+
+pub struct DeleteExportById {
+    pub id: ObjectId
+}
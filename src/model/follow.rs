@@ -0,0 +1,116 @@
+use std::fmt::{Display, Formatter};
+use actix_web::http::StatusCode;
+use actix_web::ResponseError;
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError};
+use crate::model::user::FetchUserError;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Follow {
+    #[serde(rename="_id")]
+    pub id: ObjectId,
+    pub follower: ObjectId,
+    pub followee: ObjectId,
+    pub created_at: DateTime<Utc>
+}
+
+impl Follow {
+    pub fn new(follower: ObjectId, followee: ObjectId) -> Self {
+        Self {
+            id: ObjectId::new(),
+            follower,
+            followee,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FollowDirection {
+    // users following `id`
+    Followers,
+    // users `id` follows
+    Following
+}
+
+pub struct SelectFollows {
+    pub id: ObjectId,
+    pub direction: FollowDirection
+}
+
+pub struct CountFollows {
+    pub id: ObjectId,
+    pub direction: FollowDirection
+}
+
+pub struct SelectFollow {
+    pub follower: ObjectId,
+    pub followee: ObjectId
+}
+
+pub struct DeleteFollow {
+    pub follower: ObjectId,
+    pub followee: ObjectId
+}
+
+#[derive(Debug)]
+pub enum FollowError {
+    UserNotFound,
+    Unauthorized,
+    NotFollowable,
+    AlreadyFollowing,
+    NotFollowing,
+    Internal
+}
+
+impl Display for FollowError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            FollowError::UserNotFound => "User not found",
+            FollowError::Unauthorized => "Unauthorized",
+            FollowError::NotFollowable => "Only public and bot accounts can be followed",
+            FollowError::AlreadyFollowing => "Already following",
+            FollowError::NotFollowing => "Not following",
+            FollowError::Internal => "Internal"
+        })
+    }
+}
+
+impl ResponseError for FollowError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            FollowError::UserNotFound => StatusCode::NOT_FOUND,
+            FollowError::Unauthorized => StatusCode::UNAUTHORIZED,
+            FollowError::NotFollowable => StatusCode::FORBIDDEN,
+            FollowError::AlreadyFollowing => StatusCode::CONFLICT,
+            FollowError::NotFollowing => StatusCode::NOT_FOUND,
+            FollowError::Internal => StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+impl From<FetchUserError> for FollowError {
+    fn from(_: FetchUserError) -> Self {
+        FollowError::UserNotFound
+    }
+}
+
+impl From<InsertDatabaseError> for FollowError {
+    fn from(_: InsertDatabaseError) -> Self {
+        FollowError::Internal
+    }
+}
+
+impl From<SelectDatabaseError> for FollowError {
+    fn from(_: SelectDatabaseError) -> Self {
+        FollowError::Internal
+    }
+}
+
+impl From<DeleteDatabaseError> for FollowError {
+    fn from(_: DeleteDatabaseError) -> Self {
+        FollowError::Internal
+    }
+}
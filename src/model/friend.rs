@@ -2,14 +2,18 @@ use std::fmt::{Display, Formatter};
 use actix_web::http::StatusCode;
 use actix_web::ResponseError;
 use serde::{Serialize};
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError, UpdateDatabaseError};
+use crate::model::user::FetchUserError;
 
 #[derive(Serialize)]
 pub struct Friend {
+    // entries of lists are assigned by id
+    pub id: String,
     pub user_name: String,
     pub profile_image: String,
     pub description: String,
     pub is_bot: bool,
-    pub amount_posts: usize
+    pub amount_posts: u64
 }
 
 #[derive(Debug)]
@@ -20,23 +24,40 @@ pub enum FetchFriendshipError {
 
 #[derive(Debug)]
 pub enum CreateFriendshipError {
-    DatabaseError(mongodb::error::Error),
-    AlreadyFriends
+    DatabaseError,
+    AlreadyFriends,
+    RequestPending
+}
+
+#[derive(Debug)]
+pub enum FriendRequestError {
+    UserNotFound,
+    Unauthorized,
+    RequestNotFound,
+    NotFriends,
+    AlreadyFriends,
+    RequestPending,
+    Blocked,
+    SelfRequest,
+    Internal
 }
 
 
 impl From<mongodb::error::Error> for CreateFriendshipError {
-    fn from(value: mongodb::error::Error) -> Self {
-        CreateFriendshipError::DatabaseError(value)
+    fn from(_: mongodb::error::Error) -> Self {
+        CreateFriendshipError::DatabaseError
     }
 }
 
 
+impl std::error::Error for CreateFriendshipError { }
+
 impl Display for CreateFriendshipError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", match self {
-            CreateFriendshipError::DatabaseError(_) => "Internal",
-            CreateFriendshipError::AlreadyFriends => "Conflict"
+            CreateFriendshipError::DatabaseError => "Internal",
+            CreateFriendshipError::AlreadyFriends => "Conflict",
+            CreateFriendshipError::RequestPending => "Conflict"
         })
     }
 }
@@ -44,8 +65,100 @@ impl Display for CreateFriendshipError {
 impl ResponseError for CreateFriendshipError {
     fn status_code(&self) -> StatusCode {
         match self {
-            CreateFriendshipError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
-            CreateFriendshipError::AlreadyFriends => StatusCode::CONFLICT
+            CreateFriendshipError::DatabaseError => StatusCode::INTERNAL_SERVER_ERROR,
+            CreateFriendshipError::AlreadyFriends => StatusCode::CONFLICT,
+            CreateFriendshipError::RequestPending => StatusCode::CONFLICT
+        }
+    }
+}
+
+impl Display for FriendRequestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            FriendRequestError::UserNotFound => "User not found",
+            FriendRequestError::Unauthorized => "Unauthorized",
+            FriendRequestError::RequestNotFound => "Friend request not found",
+            FriendRequestError::NotFriends => "Not friends",
+            FriendRequestError::AlreadyFriends => "Already friends",
+            FriendRequestError::RequestPending => "Friend request already pending",
+            FriendRequestError::Blocked => "Blocked",
+            FriendRequestError::SelfRequest => "Users can not be friends with themselves",
+            FriendRequestError::Internal => "Internal"
+        })
+    }
+}
+
+impl ResponseError for FriendRequestError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            FriendRequestError::UserNotFound => StatusCode::NOT_FOUND,
+            FriendRequestError::Unauthorized => StatusCode::UNAUTHORIZED,
+            FriendRequestError::RequestNotFound => StatusCode::NOT_FOUND,
+            FriendRequestError::NotFriends => StatusCode::NOT_FOUND,
+            FriendRequestError::AlreadyFriends => StatusCode::CONFLICT,
+            FriendRequestError::RequestPending => StatusCode::CONFLICT,
+            FriendRequestError::Blocked => StatusCode::FORBIDDEN,
+            FriendRequestError::SelfRequest => StatusCode::BAD_REQUEST,
+            FriendRequestError::Internal => StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+impl From<mongodb::error::Error> for FriendRequestError {
+    fn from(_: mongodb::error::Error) -> Self {
+        FriendRequestError::Internal
+    }
+}
+
+impl From<FetchUserError> for FriendRequestError {
+    fn from(_: FetchUserError) -> Self {
+        FriendRequestError::UserNotFound
+    }
+}
+
+impl From<CreateFriendshipError> for FriendRequestError {
+    fn from(value: CreateFriendshipError) -> Self {
+        match value {
+            CreateFriendshipError::DatabaseError => FriendRequestError::Internal,
+            CreateFriendshipError::AlreadyFriends => FriendRequestError::AlreadyFriends,
+            CreateFriendshipError::RequestPending => FriendRequestError::RequestPending
+        }
+    }
+}
+
+impl From<FetchFriendshipError> for FriendRequestError {
+    fn from(value: FetchFriendshipError) -> Self {
+        match value {
+            FetchFriendshipError::UserNotFound => FriendRequestError::UserNotFound,
+            FetchFriendshipError::DocumentNotParsable => FriendRequestError::Internal
+        }
+    }
+}
+
+impl From<UpdateDatabaseError> for FriendRequestError {
+    fn from(value: UpdateDatabaseError) -> Self {
+        match value {
+            UpdateDatabaseError::DatabaseError(_) => FriendRequestError::Internal
+        }
+    }
+}
+
+impl From<InsertDatabaseError> for FriendRequestError {
+    fn from(_: InsertDatabaseError) -> Self {
+        FriendRequestError::Internal
+    }
+}
+
+impl From<SelectDatabaseError> for FriendRequestError {
+    fn from(_: SelectDatabaseError) -> Self {
+        FriendRequestError::Internal
+    }
+}
+
+impl From<DeleteDatabaseError> for FriendRequestError {
+    fn from(value: DeleteDatabaseError) -> Self {
+        match value {
+            DeleteDatabaseError::DatabaseError(_) => FriendRequestError::Internal
         }
     }
 }
@@ -60,6 +173,8 @@ impl Display for FetchFriendshipError {
 }
 
 
+impl std::error::Error for FetchFriendshipError { }
+
 impl From<mongodb::error::Error> for FetchFriendshipError {
     fn from(_: mongodb::error::Error) -> Self {
         FetchFriendshipError::DocumentNotParsable
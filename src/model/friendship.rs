@@ -1,9 +1,78 @@
+use chrono::{DateTime, Utc};
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FriendshipStatus {
+    Pending,
+    // friendships created before friend requests existed are accepted
+    #[default]
+    Accepted
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Friendship {
     #[serde(rename="_id")]
+    pub id: ObjectId,
+    // friend_a is the requesting user, friend_b the requested one
     pub friend_a: ObjectId,
-    pub friend_b: ObjectId
-}
\ No newline at end of file
+    pub friend_b: ObjectId,
+    // friend_a and friend_b ordered, so the unique index covers both directions
+    pub member_low: ObjectId,
+    pub member_high: ObjectId,
+    #[serde(default)]
+    pub status: FriendshipStatus,
+    pub created_at: DateTime<Utc>
+}
+
+impl Friendship {
+    pub fn new(friend_a: ObjectId, friend_b: ObjectId, status: FriendshipStatus) -> Self {
+        let (member_low, member_high) = normalized_pair(friend_a, friend_b);
+
+        Self {
+            id: ObjectId::new(),
+            friend_a,
+            friend_b,
+            member_low,
+            member_high,
+            status,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+pub fn normalized_pair(a: ObjectId, b: ObjectId) -> (ObjectId, ObjectId) {
+    if a < b { (a, b) } else { (b, a) }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestDirection {
+    Incoming,
+    Outgoing
+}
+
+pub struct SelectFriendRequests {
+    pub id: ObjectId,
+    pub direction: RequestDirection
+}
+
+pub struct SelectFriendshipBetween {
+    pub user_a: ObjectId,
+    pub user_b: ObjectId
+}
+
+pub struct AcceptFriendRequest {
+    pub requester: ObjectId,
+    pub addressee: ObjectId
+}
+
+pub struct DeleteFriendRequest {
+    pub requester: ObjectId,
+    pub addressee: ObjectId
+}
+
+pub struct DeleteFriendship {
+    pub user_a: ObjectId,
+    pub user_b: ObjectId
+}
@@ -0,0 +1,471 @@
+use std::fmt::{Display, Formatter};
+use actix_web::http::StatusCode;
+use actix_web::ResponseError;
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Deserializer, Serialize};
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError, UpdateDatabaseError};
+use crate::model::friend::FetchFriendshipError;
+use crate::model::user::FetchUserError;
+
+// entries are embedded, so every change of a list is a single atomic update
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct List {
+    #[serde(rename="_id")]
+    pub id: ObjectId,
+    pub owner: ObjectId,
+    // lists created before users could have several lists have no name
+    #[serde(default = "default_list_name")]
+    pub name: String,
+    #[serde(default)]
+    pub archived: bool,
+    pub entries: Vec<ListEntry>,
+    // friends the owner shared the list with
+    #[serde(default)]
+    pub shares: Vec<ListShare>,
+    // increased by every change, sent as ETag
+    #[serde(default)]
+    pub version: u64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>
+}
+
+impl List {
+    pub fn new(owner: ObjectId, name: String, entries: Vec<ListEntry>) -> Self {
+        let now = Utc::now();
+
+        Self {
+            id: ObjectId::new(),
+            owner,
+            name,
+            archived: false,
+            entries,
+            shares: vec![],
+            version: 0,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn share_of(&self, user: &ObjectId) -> Option<ListPermission> {
+        self.shares.iter()
+            .find(|share| share.user == *user)
+            .map(|share| share.permission)
+    }
+
+    pub fn target(&self) -> ListTarget {
+        ListTarget { id: self.id, expected_version: None }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListPermission {
+    View,
+    Edit
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListShare {
+    pub user: ObjectId,
+    pub permission: ListPermission
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High
+}
+
+// entries of list.json files have no ids, they get one while being parsed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListEntry {
+    #[serde(default = "new_entry_id")]
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub checked: bool,
+    #[serde(default)]
+    pub due: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub notes: Option<String>,
+    // the id of the owner or of a friend of the owner
+    #[serde(default)]
+    pub assignee: Option<String>,
+    #[serde(default)]
+    pub priority: Priority,
+    // the index of the entry, set whenever entries are sent. The order of `entries` is authoritative
+    #[serde(default, skip_deserializing)]
+    pub position: usize,
+    #[serde(default)]
+    pub sub_entries: Vec<SubEntry>
+}
+
+impl ListEntry {
+    pub fn new(title: String) -> Self {
+        Self {
+            id: new_entry_id(),
+            title,
+            checked: false,
+            due: None,
+            notes: None,
+            assignee: None,
+            priority: Priority::default(),
+            position: 0,
+            sub_entries: vec![],
+        }
+    }
+}
+
+// fields which are None stay untouched, optional fields are removed with null
+#[derive(Debug, Default, Deserialize)]
+pub struct EntryChanges {
+    pub title: Option<String>,
+    pub checked: Option<bool>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub due: Option<Option<DateTime<Utc>>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub notes: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub assignee: Option<Option<String>>,
+    pub priority: Option<Priority>
+}
+
+impl EntryChanges {
+    pub fn apply(&self, entry: &mut ListEntry) {
+        if let Some(title) = &self.title {
+            entry.title = title.clone();
+        }
+        if let Some(checked) = self.checked {
+            entry.checked = checked;
+        }
+        if let Some(due) = self.due {
+            entry.due = due;
+        }
+        if let Some(notes) = &self.notes {
+            entry.notes = notes.clone();
+        }
+        if let Some(assignee) = &self.assignee {
+            entry.assignee = assignee.clone();
+        }
+        if let Some(priority) = self.priority {
+            entry.priority = priority;
+        }
+    }
+}
+
+// tells a missing field (None) apart from null (Some(None))
+fn nullable<'de, D: Deserializer<'de>, T: Deserialize<'de>>(deserializer: D) -> Result<Option<Option<T>>, D::Error> {
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+pub fn numbered(mut entries: Vec<ListEntry>) -> Vec<ListEntry> {
+    for (position, entry) in entries.iter_mut().enumerate() {
+        entry.position = position;
+    }
+
+    entries
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubEntry {
+    #[serde(default = "new_entry_id")]
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub checked: bool
+}
+
+impl SubEntry {
+    pub fn new(title: String) -> Self {
+        Self {
+            id: new_entry_id(),
+            title,
+            checked: false,
+        }
+    }
+}
+
+fn new_entry_id() -> String {
+    ObjectId::new().to_hex()
+}
+
+pub fn default_list_name() -> String {
+    String::from("List")
+}
+
+// format of the old list.json files and of a whole list sent by a client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListContent {
+    pub entries: Vec<ListEntry>
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ListInformation {
+    pub id: String,
+    pub name: String,
+    pub archived: bool,
+    pub entries: Vec<ListEntry>,
+    pub version: u64,
+    pub updated_at: DateTime<Utc>
+}
+
+impl From<List> for ListInformation {
+    fn from(list: List) -> Self {
+        Self {
+            id: list.id.to_hex(),
+            name: list.name,
+            archived: list.archived,
+            entries: numbered(list.entries),
+            version: list.version,
+            updated_at: list.updated_at,
+        }
+    }
+}
+
+// a list without its entries, but with the progress of them
+#[derive(Debug, Serialize)]
+pub struct ListSummary {
+    pub id: String,
+    pub name: String,
+    pub archived: bool,
+    pub entries: usize,
+    pub checked_entries: usize,
+    pub sub_entries: usize,
+    pub checked_sub_entries: usize,
+    pub version: u64,
+    pub updated_at: DateTime<Utc>
+}
+
+impl From<&List> for ListSummary {
+    fn from(list: &List) -> Self {
+        let sub_entries = list.entries.iter().flat_map(|entry| &entry.sub_entries);
+
+        Self {
+            id: list.id.to_hex(),
+            name: list.name.clone(),
+            archived: list.archived,
+            entries: list.entries.len(),
+            checked_entries: list.entries.iter().filter(|entry| entry.checked).count(),
+            sub_entries: sub_entries.clone().count(),
+            checked_sub_entries: sub_entries.filter(|sub_entry| sub_entry.checked).count(),
+            version: list.version,
+            updated_at: list.updated_at,
+        }
+    }
+}
+
+// a list of another user, shared with the requesting one
+#[derive(Debug, Serialize)]
+pub struct SharedListSummary {
+    pub owner: String,
+    pub permission: ListPermission,
+    #[serde(flatten)]
+    pub list: ListSummary
+}
+
+// an unchecked entry of a list of the user, which is due soon or overdue
+#[derive(Debug, Serialize)]
+pub struct DueEntry {
+    pub list_id: String,
+    pub list_name: String,
+    #[serde(flatten)]
+    pub entry: ListEntry
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListShareInformation {
+    pub user_name: String,
+    pub permission: ListPermission
+}
+
+// the list a change is applied to. With an expected version the change is only applied, if nobody changed the list in the meantime
+#[derive(Debug, Clone, Copy)]
+pub struct ListTarget {
+    pub id: ObjectId,
+    pub expected_version: Option<u64>
+}
+
+// the oldest list of `owner`. It is the one used by the single list endpoints
+pub struct SelectListByOwner {
+    pub owner: ObjectId
+}
+
+pub struct SelectListsByOwner {
+    pub owner: ObjectId
+}
+
+pub struct SelectListById {
+    pub id: ObjectId,
+    pub owner: ObjectId
+}
+
+// fields which are None stay untouched
+pub struct UpdateListDetails {
+    pub target: ListTarget,
+    pub name: Option<String>,
+    pub archived: Option<bool>
+}
+
+pub struct ReplaceListShares {
+    pub target: ListTarget,
+    pub shares: Vec<ListShare>
+}
+
+pub struct SelectSharedLists {
+    pub user: ObjectId
+}
+
+pub struct DeleteListById {
+    pub target: ListTarget
+}
+
+pub struct ReplaceListEntries {
+    pub target: ListTarget,
+    pub entries: Vec<ListEntry>
+}
+
+// appended without a position
+pub struct InsertListEntry {
+    pub target: ListTarget,
+    pub entry: ListEntry,
+    pub position: Option<usize>
+}
+
+pub struct UpdateListEntry {
+    pub target: ListTarget,
+    pub entry_id: String,
+    pub changes: EntryChanges
+}
+
+pub struct DeleteListEntry {
+    pub target: ListTarget,
+    pub entry_id: String
+}
+
+pub struct InsertSubEntry {
+    pub target: ListTarget,
+    pub entry_id: String,
+    pub sub_entry: SubEntry
+}
+
+pub struct UpdateSubEntry {
+    pub target: ListTarget,
+    pub entry_id: String,
+    pub sub_entry_id: String,
+    pub title: Option<String>,
+    pub checked: Option<bool>
+}
+
+pub struct DeleteSubEntry {
+    pub target: ListTarget,
+    pub entry_id: String,
+    pub sub_entry_id: String
+}
+
+#[derive(Debug)]
+pub enum ListError {
+    UserNotFound,
+    Unauthorized,
+    ListNotFound,
+    EntryNotFound,
+    InvalidName,
+    // a reorder has to name every entry exactly once
+    InvalidOrder,
+    NotFriends,
+    NotShared,
+    InvalidAssignee,
+    InvalidImport,
+    RevisionNotFound,
+    // the list changed since the version the client sent with If-Match
+    VersionMismatch,
+    // replacing all entries without If-Match would overwrite changes of other devices
+    VersionRequired,
+    Internal
+}
+
+impl Display for ListError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            ListError::UserNotFound => "User not found",
+            ListError::Unauthorized => "Unauthorized",
+            ListError::ListNotFound => "List not found",
+            ListError::EntryNotFound => "Entry not found",
+            ListError::InvalidName => "The name of a list can not be empty",
+            ListError::NotFriends => "Lists can only be shared with friends",
+            ListError::NotShared => "The list is not shared with this user",
+            ListError::InvalidAssignee => "Entries can only be assigned to the owner of the list or its friends",
+            ListError::InvalidImport => "The content is not a valid list",
+            ListError::RevisionNotFound => "Revision not found",
+            ListError::VersionMismatch => "The list was changed in the meantime",
+            ListError::VersionRequired => "Replacing all entries requires If-Match",
+            ListError::InvalidOrder => "The order has to contain every entry exactly once",
+            ListError::Internal => "Internal"
+        })
+    }
+}
+
+impl std::error::Error for ListError { }
+
+impl ResponseError for ListError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ListError::UserNotFound => StatusCode::NOT_FOUND,
+            ListError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ListError::ListNotFound => StatusCode::NOT_FOUND,
+            ListError::EntryNotFound => StatusCode::NOT_FOUND,
+            ListError::InvalidName => StatusCode::BAD_REQUEST,
+            ListError::NotFriends => StatusCode::FORBIDDEN,
+            ListError::NotShared => StatusCode::NOT_FOUND,
+            ListError::InvalidAssignee => StatusCode::BAD_REQUEST,
+            ListError::InvalidImport => StatusCode::BAD_REQUEST,
+            ListError::RevisionNotFound => StatusCode::NOT_FOUND,
+            ListError::VersionMismatch => StatusCode::PRECONDITION_FAILED,
+            ListError::VersionRequired => StatusCode::PRECONDITION_REQUIRED,
+            ListError::InvalidOrder => StatusCode::BAD_REQUEST,
+            ListError::Internal => StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+impl From<FetchUserError> for ListError {
+    fn from(_: FetchUserError) -> Self {
+        ListError::UserNotFound
+    }
+}
+
+impl From<FetchFriendshipError> for ListError {
+    fn from(value: FetchFriendshipError) -> Self {
+        match value {
+            FetchFriendshipError::UserNotFound => ListError::UserNotFound,
+            FetchFriendshipError::DocumentNotParsable => ListError::Internal
+        }
+    }
+}
+
+impl From<InsertDatabaseError> for ListError {
+    fn from(_: InsertDatabaseError) -> Self {
+        ListError::Internal
+    }
+}
+
+impl From<SelectDatabaseError> for ListError {
+    fn from(_: SelectDatabaseError) -> Self {
+        ListError::Internal
+    }
+}
+
+impl From<UpdateDatabaseError> for ListError {
+    fn from(_: UpdateDatabaseError) -> Self {
+        ListError::Internal
+    }
+}
+
+impl From<DeleteDatabaseError> for ListError {
+    fn from(_: DeleteDatabaseError) -> Self {
+        ListError::Internal
+    }
+}
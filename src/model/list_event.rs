@@ -0,0 +1,166 @@
+use std::collections::{HashMap, HashSet};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use crate::model::list::{List, ListEntry, ListInformation, Priority, SubEntry};
+
+// sent to the WebSocket subscribers of a list
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ListMessage {
+    // the changes which turned the previous version into `version`
+    Changed { version: u64, events: Vec<ListEvent> },
+    // the whole list, whenever the changes since the version of a client are unknown
+    Snapshot { list: ListInformation },
+    Deleted
+}
+
+// a change of a single entry. Events of sub entries carry the id of their entry as `entry_id`.
+// Applied in order, the events turn the previous version into the new one
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ListEvent {
+    Removed {
+        entry_id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sub_entry_id: Option<String>
+    },
+    // the new order of the entries, or of the sub entries of `entry_id`, which were there before
+    Reordered {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        entry_id: Option<String>,
+        ids: Vec<String>
+    },
+    Added {
+        entry_id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sub_entry_id: Option<String>,
+        position: usize,
+        title: String,
+        checked: bool
+    },
+    Renamed {
+        entry_id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sub_entry_id: Option<String>,
+        title: String
+    },
+    Checked {
+        entry_id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sub_entry_id: Option<String>,
+        checked: bool
+    },
+    // the due date, notes, assignee or priority of an entry changed
+    Edited {
+        entry_id: String,
+        due: Option<DateTime<Utc>>,
+        notes: Option<String>,
+        assignee: Option<String>,
+        priority: Priority
+    },
+    // the list was renamed or (un)archived
+    Details { name: String, archived: bool }
+}
+
+struct Item<'a> {
+    id: &'a str,
+    title: &'a str,
+    checked: bool
+}
+
+pub fn list_events(before: &List, after: &List) -> Vec<ListEvent> {
+    let mut events = vec![];
+
+    if before.name != after.name || before.archived != after.archived {
+        events.push(ListEvent::Details { name: after.name.clone(), archived: after.archived });
+    }
+
+    item_events(&mut events, None, &entry_items(&before.entries), &entry_items(&after.entries));
+
+    let entries_before = before.entries.iter()
+        .map(|entry| (entry.id.as_str(), entry))
+        .collect::<HashMap<_, _>>();
+
+    // new entries only get an event, if they have details
+    let no_details = ListEntry::new(String::new());
+    for entry in &after.entries {
+        let previous = entries_before.get(entry.id.as_str()).copied().unwrap_or(&no_details);
+        if details(previous) != details(entry) {
+            events.push(ListEvent::Edited {
+                entry_id: entry.id.clone(),
+                due: entry.due,
+                notes: entry.notes.clone(),
+                assignee: entry.assignee.clone(),
+                priority: entry.priority
+            });
+        }
+    }
+
+    for entry in &after.entries {
+        // every sub entry of a new entry is added
+        let before = entries_before.get(entry.id.as_str())
+            .map(|previous| sub_entry_items(&previous.sub_entries))
+            .unwrap_or_default();
+
+        item_events(&mut events, Some(&entry.id), &before, &sub_entry_items(&entry.sub_entries));
+    }
+
+    events
+}
+
+fn details(entry: &ListEntry) -> (Option<DateTime<Utc>>, &Option<String>, &Option<String>, Priority) {
+    (entry.due, &entry.notes, &entry.assignee, entry.priority)
+}
+
+fn entry_items(entries: &[ListEntry]) -> Vec<Item<'_>> {
+    entries.iter()
+        .map(|entry| Item { id: &entry.id, title: &entry.title, checked: entry.checked })
+        .collect()
+}
+
+fn sub_entry_items(sub_entries: &[SubEntry]) -> Vec<Item<'_>> {
+    sub_entries.iter()
+        .map(|sub_entry| Item { id: &sub_entry.id, title: &sub_entry.title, checked: sub_entry.checked })
+        .collect()
+}
+
+// the events of the entries of a list, or of the sub entries of `parent`
+fn item_events(events: &mut Vec<ListEvent>, parent: Option<&str>, before: &[Item], after: &[Item]) {
+    let ids = |item: &Item| match parent {
+        Some(parent) => (parent.to_string(), Some(item.id.to_string())),
+        None => (item.id.to_string(), None)
+    };
+
+    let before_by_id = before.iter().map(|item| (item.id, item)).collect::<HashMap<_, _>>();
+    let after_ids = after.iter().map(|item| item.id).collect::<HashSet<_>>();
+
+    for item in before.iter().filter(|item| !after_ids.contains(item.id)) {
+        let (entry_id, sub_entry_id) = ids(item);
+        events.push(ListEvent::Removed { entry_id, sub_entry_id });
+    }
+
+    let kept_before = before.iter().filter(|item| after_ids.contains(item.id)).map(|item| item.id);
+    let kept_after = after.iter().filter(|item| before_by_id.contains_key(item.id)).map(|item| item.id);
+    if !kept_before.eq(kept_after.clone()) {
+        events.push(ListEvent::Reordered {
+            entry_id: parent.map(str::to_string),
+            ids: kept_after.map(str::to_string).collect()
+        });
+    }
+
+    for (position, item) in after.iter().enumerate() {
+        let (entry_id, sub_entry_id) = ids(item);
+
+        match before_by_id.get(item.id) {
+            None => events.push(ListEvent::Added { entry_id, sub_entry_id, position, title: item.title.to_string(), checked: item.checked }),
+            Some(previous) => {
+                if previous.title != item.title {
+                    events.push(ListEvent::Renamed { entry_id: entry_id.clone(), sub_entry_id: sub_entry_id.clone(), title: item.title.to_string() });
+                }
+                if previous.checked != item.checked {
+                    events.push(ListEvent::Checked { entry_id, sub_entry_id, checked: item.checked });
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use crate::model::list::{List, ListEntry, ListInformation};
+use crate::model::list_event::ListEvent;
+
+// the state of a list after a change. Revisions are snapshots, diffs are computed between two of them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListRevision {
+    #[serde(rename="_id")]
+    pub id: ObjectId,
+    pub list_id: ObjectId,
+    pub version: u64,
+    // the user who made the change
+    pub author: ObjectId,
+    pub name: String,
+    pub archived: bool,
+    pub entries: Vec<ListEntry>,
+    pub created_at: DateTime<Utc>
+}
+
+impl ListRevision {
+    pub fn new(list: &List, author: ObjectId) -> Self {
+        Self {
+            id: ObjectId::new(),
+            list_id: list.id,
+            version: list.version,
+            author,
+            name: list.name.clone(),
+            archived: list.archived,
+            entries: list.entries.clone(),
+            created_at: Utc::now(),
+        }
+    }
+
+    // the list as it was at this revision
+    pub fn to_list(&self, list: &List) -> List {
+        List {
+            name: self.name.clone(),
+            archived: self.archived,
+            entries: self.entries.clone(),
+            version: self.version,
+            updated_at: self.created_at,
+            ..list.clone()
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RevisionSummary {
+    pub version: u64,
+    pub author: String,
+    pub created_at: DateTime<Utc>
+}
+
+#[derive(Debug, Serialize)]
+pub struct RevisionInformation {
+    pub version: u64,
+    pub author: String,
+    pub created_at: DateTime<Utc>,
+    pub list: ListInformation,
+    // the changes since the compared revision
+    pub compared_to: Option<u64>,
+    pub events: Vec<ListEvent>
+}
+
+// newest first, a page of them
+pub struct SelectRevisions {
+    pub list_id: ObjectId,
+    pub offset: usize,
+    pub limit: Option<usize>
+}
+
+pub struct SelectRevision {
+    pub list_id: ObjectId,
+    pub version: u64
+}
+
+// the newest revision older than `version`
+pub struct SelectPreviousRevision {
+    pub list_id: ObjectId,
+    pub version: u64
+}
+
+pub struct DeleteRevisions {
+    pub list_id: ObjectId
+}
+
+pub struct DeleteRevisionsBefore {
+    pub list_id: ObjectId,
+    pub version: u64
+}
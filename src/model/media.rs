@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+// files uploaded before visibilities existed have no entry and are visible to friends
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+    #[default]
+    Friends,
+    // only the close friends of the owner
+    CloseFriends,
+    // friends and followers
+    Public
+}
+
+// posts and stories are stored in different directories, so a file name is only unique per kind
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaKind {
+    #[default]
+    Post,
+    Story
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaEntry {
+    #[serde(rename="_id")]
+    pub id: ObjectId,
+    pub owner: ObjectId,
+    pub file_name: String,
+    #[serde(default)]
+    pub kind: MediaKind,
+    pub visibility: Visibility,
+    pub created_at: DateTime<Utc>
+}
+
+impl MediaEntry {
+    pub fn new(owner: ObjectId, file_name: String, kind: MediaKind, visibility: Visibility) -> Self {
+        Self {
+            id: ObjectId::new(),
+            owner,
+            file_name,
+            kind,
+            visibility,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+pub struct SelectMediaByOwner {
+    pub owner: ObjectId,
+    pub kind: MediaKind,
+    pub visibility: Option<Visibility>
+}
+
+pub struct DeleteMediaEntry {
+    pub owner: ObjectId,
+    pub kind: MediaKind,
+    pub file_name: String
+}
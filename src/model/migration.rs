@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::serde_helpers::chrono_datetime_as_bson_datetime;
+use serde::{Deserialize, Serialize};
+use crate::utils::version::Version;
+
+// a migration that ran, the version is the id so it can only be recorded once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedMigration {
+    #[serde(rename="_id")]
+    pub version: Version,
+    pub description: String,
+    pub applied_at: DateTime<Utc>
+}
+
+// held by the instance that is migrating. Expires, so a crashed instance does not block the others forever
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationLock {
+    #[serde(rename="_id")]
+    pub id: String,
+    pub owner: String,
+    pub locked_at: DateTime<Utc>,
+    // compared in the query, so it is stored as a date instead of a string
+    #[serde(with = "chrono_datetime_as_bson_datetime")]
+    pub expires_at: DateTime<Utc>
+}
+
+pub struct SelectAppliedMigrations;
+
+pub struct AcquireMigrationLock {
+    pub owner: String,
+    pub expires_at: DateTime<Utc>
+}
+
+pub struct ReleaseMigrationLock {
+    pub owner: String
+}
@@ -7,6 +7,18 @@ pub mod states;
 pub mod user;
 pub mod friend;
 pub mod friendship;
+pub mod export;
+pub mod takeout;
+pub mod suggestion;
+pub mod follow;
+pub mod media;
+pub mod close_friend;
+pub mod restriction;
+pub mod list;
+pub mod list_event;
+pub mod list_revision;
+pub mod changelog;
+pub mod migration;
 
 
 #[derive(Debug)]
@@ -74,6 +86,8 @@ impl Display for InsertDatabaseError {
     }
 }
 
+impl std::error::Error for InsertDatabaseError { }
+
 impl Display for DeleteDatabaseError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", match self {
@@ -85,11 +99,13 @@ impl Display for DeleteDatabaseError {
 impl Display for SelectDatabaseError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", match self {
-            SelectDatabaseError::DatabaseError(_) => "Internal selection error"
+            SelectDatabaseError::DatabaseError(d) => d
         })
     }
 }
 
+impl std::error::Error for SelectDatabaseError { }
+
 impl ResponseError for SelectDatabaseError {
     fn status_code(&self) -> StatusCode {
         match self {
@@ -107,11 +123,13 @@ impl From<mongodb::error::Error> for UpdateDatabaseError {
 impl Display for UpdateDatabaseError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", match self {
-            UpdateDatabaseError::DatabaseError(_) => "Internal",
+            UpdateDatabaseError::DatabaseError(d) => d
         })
     }
 }
 
+impl std::error::Error for UpdateDatabaseError { }
+
 impl ResponseError for UpdateDatabaseError {
     fn status_code(&self) -> StatusCode {
         match self {
@@ -0,0 +1,125 @@
+use std::fmt::{Display, Formatter};
+use actix_web::http::StatusCode;
+use actix_web::ResponseError;
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+use crate::model::{DeleteDatabaseError, InsertDatabaseError, SelectDatabaseError};
+use crate::model::user::FetchUserError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestrictionKind {
+    // no friendship, follows or requests, both users are hidden from each other
+    Block,
+    // the content of `target` is hidden from `owner`, the friendship stays
+    Mute
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Restriction {
+    #[serde(rename="_id")]
+    pub id: ObjectId,
+    pub owner: ObjectId,
+    pub target: ObjectId,
+    pub kind: RestrictionKind,
+    pub created_at: DateTime<Utc>
+}
+
+impl Restriction {
+    pub fn new(owner: ObjectId, target: ObjectId, kind: RestrictionKind) -> Self {
+        Self {
+            id: ObjectId::new(),
+            owner,
+            target,
+            kind,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+// restrictions `owner` created
+pub struct SelectRestrictions {
+    pub owner: ObjectId,
+    pub kind: RestrictionKind
+}
+
+pub struct SelectRestriction {
+    pub owner: ObjectId,
+    pub target: ObjectId,
+    pub kind: RestrictionKind
+}
+
+// blocks in both directions
+pub struct SelectBlockBetween {
+    pub user_a: ObjectId,
+    pub user_b: ObjectId
+}
+
+// every user `id` blocked or got blocked by
+pub struct SelectBlockedUsers {
+    pub id: ObjectId
+}
+
+pub struct DeleteRestriction {
+    pub owner: ObjectId,
+    pub target: ObjectId,
+    pub kind: RestrictionKind
+}
+
+#[derive(Debug)]
+pub enum RestrictionError {
+    UserNotFound,
+    Unauthorized,
+    SelfRestriction,
+    NotRestricted,
+    Internal
+}
+
+impl Display for RestrictionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            RestrictionError::UserNotFound => "User not found",
+            RestrictionError::Unauthorized => "Unauthorized",
+            RestrictionError::SelfRestriction => "Users can not block or mute themselves",
+            RestrictionError::NotRestricted => "User is not blocked or muted",
+            RestrictionError::Internal => "Internal"
+        })
+    }
+}
+
+impl ResponseError for RestrictionError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RestrictionError::UserNotFound => StatusCode::NOT_FOUND,
+            RestrictionError::Unauthorized => StatusCode::UNAUTHORIZED,
+            RestrictionError::SelfRestriction => StatusCode::BAD_REQUEST,
+            RestrictionError::NotRestricted => StatusCode::NOT_FOUND,
+            RestrictionError::Internal => StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+impl From<FetchUserError> for RestrictionError {
+    fn from(_: FetchUserError) -> Self {
+        RestrictionError::UserNotFound
+    }
+}
+
+impl From<InsertDatabaseError> for RestrictionError {
+    fn from(_: InsertDatabaseError) -> Self {
+        RestrictionError::Internal
+    }
+}
+
+impl From<SelectDatabaseError> for RestrictionError {
+    fn from(_: SelectDatabaseError) -> Self {
+        RestrictionError::Internal
+    }
+}
+
+impl From<DeleteDatabaseError> for RestrictionError {
+    fn from(_: DeleteDatabaseError) -> Self {
+        RestrictionError::Internal
+    }
+}
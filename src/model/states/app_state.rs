@@ -1,27 +1,34 @@
 use std::env::VarError;
+use std::fmt::{Display, Formatter};
 use std::num::ParseIntError;
+use std::sync::Arc;
 use crate::database::database_context::DatabaseContext;
-use crate::database::database_context::Error as DBError;
+use crate::model::states::list_sync::ListSync;
 #[derive(Clone)]
 pub struct AppState {
     pub ip_port_tuple: (String, u16),
     pub jwt_secret: String,
     pub data_directory: String,
-    pub db: DatabaseContext
+    pub export_directory: String,
+    // grants access to the changelog feed, disabled without one
+    pub feed_api_key: Option<String>,
+    pub db: Arc<dyn DatabaseContext>,
+    pub list_sync: ListSync
 }
 
 impl AppState {
-    pub async fn from_env() -> Result<Self, AppStateError> {
+    pub fn from_env(db: Arc<dyn DatabaseContext>) -> Result<Self, AppStateError> {
         let server_ip = std::env::var("SERVERIP")?;
         let server_port: u16 = std::env::var("SERVERPORT")?.parse()?;
 
-        let database = DatabaseContext::new().await?;
-
         Ok(AppState {
             ip_port_tuple: (server_ip, server_port),
             jwt_secret: std::env::var("JWT_SECRET")?,
             data_directory: std::env::var("DATADIRECTORY")?,
-            db: database,
+            export_directory: std::env::var("EXPORTDIRECTORY").unwrap_or_else(|_| String::from("./exports/")),
+            feed_api_key: std::env::var("FEED_API_KEY").ok().filter(|key| !key.is_empty()),
+            db,
+            list_sync: ListSync::default(),
         })
     }
 }
@@ -31,12 +38,19 @@ impl AppState {
 pub enum AppStateError {
     Var(VarError),
     ParseInt(ParseIntError),
-    IO(std::io::Error),
-    Database(DBError)
+    IO(std::io::Error)
 }
 
 
-impl From<DBError> for AppStateError { fn from(value: DBError) -> Self { AppStateError::Database(value) } }
+impl Display for AppStateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppStateError::Var(err) => write!(f, "Missing environment variable: {}", err),
+            AppStateError::ParseInt(err) => write!(f, "SERVERPORT is not a port: {}", err),
+            AppStateError::IO(err) => write!(f, "{}", err)
+        }
+    }
+}
 
 impl From<std::io::Error> for AppStateError { fn from(value: std::io::Error) -> Self { AppStateError::IO(value) } }
 
@@ -0,0 +1,93 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use mongodb::bson::oid::ObjectId;
+use tokio::sync::broadcast;
+use crate::model::list::List;
+use crate::model::list_event::{list_events, ListMessage};
+
+// messages a subscriber can fall behind, before it gets a snapshot instead
+const CHANNEL_CAPACITY: usize = 64;
+// changes kept for clients resuming from an older version
+const RECENT_CHANGES: usize = 64;
+// channels without subscribers are dropped after this time, resuming clients get a snapshot afterwards
+const IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+// fans the changes of a list out to its WebSocket subscribers.
+// Only lists with subscribers have a channel, changes of other lists are not kept
+#[derive(Clone, Default)]
+pub struct ListSync {
+    channels: Arc<Mutex<HashMap<ObjectId, ListChannel>>>
+}
+
+struct ListChannel {
+    sender: broadcast::Sender<ListMessage>,
+    // consecutive changes with their version, the newest last
+    recent: VecDeque<(u64, ListMessage)>,
+    last_used: Instant
+}
+
+impl ListSync {
+    pub fn subscribe(&self, list_id: ObjectId) -> broadcast::Receiver<ListMessage> {
+        let mut channels = self.channels.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        channels.retain(|_, channel| channel.sender.receiver_count() > 0 || channel.last_used.elapsed() < IDLE_TIMEOUT);
+
+        let channel = channels.entry(list_id).or_insert_with(|| ListChannel {
+            sender: broadcast::channel(CHANNEL_CAPACITY).0,
+            recent: VecDeque::new(),
+            last_used: Instant::now(),
+        });
+        channel.last_used = Instant::now();
+
+        channel.sender.subscribe()
+    }
+
+    // the changes turning `version` into `current`, None if some of them are unknown
+    pub fn changes_since(&self, list_id: &ObjectId, version: u64, current: u64) -> Option<Vec<ListMessage>> {
+        let channels = self.channels.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let channel = channels.get(list_id)?;
+
+        let changes = channel.recent.iter()
+            .filter(|(change_version, _)| *change_version > version && *change_version <= current)
+            .collect::<Vec<_>>();
+
+        match changes.first() {
+            Some((first, _)) if *first == version + 1 && changes.len() as u64 == current - version => {
+                Some(changes.into_iter().map(|(_, message)| message.clone()).collect())
+            },
+            _ => None
+        }
+    }
+
+    // `before` has to be the list the change was applied to, otherwise the subscribers get a snapshot
+    pub fn publish_change(&self, before: &List, after: &List) {
+        let mut channels = self.channels.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let Some(channel) = channels.get_mut(&after.id) else {
+            return;
+        };
+
+        let message = if after.version == before.version + 1 {
+            let message = ListMessage::Changed { version: after.version, events: list_events(before, after) };
+            channel.recent.push_back((after.version, message.clone()));
+            if channel.recent.len() > RECENT_CHANGES {
+                channel.recent.pop_front();
+            }
+
+            message
+        } else {
+            channel.recent.clear();
+            ListMessage::Snapshot { list: after.clone().into() }
+        };
+
+        // without subscribers the message is only kept for resuming clients
+        let _ = channel.sender.send(message);
+    }
+
+    pub fn publish_deletion(&self, list_id: &ObjectId) {
+        let mut channels = self.channels.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if let Some(channel) = channels.remove(list_id) {
+            let _ = channel.sender.send(ListMessage::Deleted);
+        }
+    }
+}
@@ -1 +1 @@
-pub mod app_state;
\ No newline at end of file
+pub mod app_state;pub mod list_sync;
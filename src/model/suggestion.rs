@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+use mongodb::bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestionDismissal {
+    #[serde(rename="_id")]
+    pub id: ObjectId,
+    pub user_id: ObjectId,
+    pub dismissed_id: ObjectId,
+    pub created_at: DateTime<Utc>
+}
+
+impl SuggestionDismissal {
+    pub fn new(user_id: ObjectId, dismissed_id: ObjectId) -> Self {
+        Self {
+            id: ObjectId::new(),
+            user_id,
+            dismissed_id,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Suggestion {
+    pub user_name: String,
+    pub profile_image: String,
+    pub description: String,
+    pub is_bot: bool,
+    pub mutual_friends: u32
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MutualFriends {
+    #[serde(rename="_id")]
+    pub user_id: ObjectId,
+    pub mutual_friends: u32
+}
+
+pub struct SelectSuggestions {
+    pub id: ObjectId,
+    // the user itself, friends, pending requests, dismissed and blocked users
+    pub excluded: Vec<ObjectId>,
+    pub limit: i64
+}
@@ -0,0 +1,65 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::model::list::{default_list_name, List, ListContent, ListEntry};
+
+pub const PROFILE_MANIFEST: &str = "profile.json";
+pub const FRIENDS_MANIFEST: &str = "friends.json";
+pub const LISTS_MANIFEST: &str = "lists.json";
+// single list of older archives
+pub const LIST_MANIFEST: &str = "list.json";
+pub const POSTS_DIRECTORY: &str = "posts";
+pub const STORIES_DIRECTORY: &str = "stories";
+pub const INFORMATION_DIRECTORY: &str = "information";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileManifest {
+    pub user_name: String,
+    pub description: String,
+    pub is_bot: bool,
+    #[serde(default)]
+    pub is_public: bool,
+    pub created_at: DateTime<Utc>
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FriendManifest {
+    pub user_name: String
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListManifest {
+    pub name: String,
+    #[serde(default)]
+    pub archived: bool,
+    pub entries: Vec<ListEntry>
+}
+
+impl From<List> for ListManifest {
+    fn from(list: List) -> Self {
+        Self {
+            name: list.name,
+            archived: list.archived,
+            entries: list.entries,
+        }
+    }
+}
+
+impl From<ListContent> for ListManifest {
+    fn from(content: ListContent) -> Self {
+        Self {
+            name: default_list_name(),
+            archived: false,
+            entries: content.entries,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportReport {
+    pub restored_files: usize,
+    // already friends or a request of them was pending
+    pub linked_friends: Vec<String>,
+    // were sent a friend request
+    pub requested_friends: Vec<String>,
+    pub unmatched_friends: Vec<String>
+}
@@ -7,6 +7,7 @@ use argon2::password_hash::{Error, SaltString};
 use chrono::{DateTime, Utc};
 use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
+use crate::utils::version::Version;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
@@ -15,7 +16,19 @@ pub struct User {
     pub name: String,
     pub password_hash: String,
     pub is_bot: bool,
+    // public accounts can be followed without being friends
+    #[serde(default)]
+    pub is_public: bool,
     pub description: String,
+    // files in the posts directory, kept up to date on upload and delete
+    #[serde(default)]
+    pub amount_posts: u64,
+    // newer changelog versions are unread
+    #[serde(default)]
+    pub last_seen_changelog: Option<Version>,
+    // lets feed readers, which can not send the cookie, fetch the changelog feed
+    #[serde(default)]
+    pub feed_token: Option<String>,
 
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -26,6 +39,10 @@ impl User {
     pub fn is_admin(&self) -> bool {
         self.name == "admin"
     }
+
+    pub fn is_followable(&self) -> bool {
+        self.is_public || self.is_bot
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,7 +55,42 @@ pub struct CreateUser {
 
 pub struct UpdateUser {
     pub target_id: ObjectId,
-    pub new_description: String
+    pub new_description: String,
+    pub new_is_public: Option<bool>
+}
+
+pub struct RestoreUserProfile {
+    pub target_id: ObjectId,
+    pub description: String,
+    pub is_bot: bool,
+    pub is_public: bool
+}
+
+// adds `change` to the stored post counter
+pub struct UpdatePostCount {
+    pub target_id: ObjectId,
+    pub change: i64
+}
+
+// overwrites the stored post counter, after posts were restored or counted from disk
+pub struct SetPostCount {
+    pub target_id: ObjectId,
+    pub amount_posts: u64
+}
+
+pub struct UpdateLastSeenChangelog {
+    pub target_id: ObjectId,
+    pub version: Version
+}
+
+pub struct SelectUserByFeedToken<'a> {
+    pub token: &'a str
+}
+
+// None revokes the token
+pub struct UpdateFeedToken {
+    pub target_id: ObjectId,
+    pub token: Option<String>
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +103,11 @@ pub struct SelectUserById {
     pub id: ObjectId
 }
 
+// users which do not exist are left out
+pub struct SelectUsersByIds {
+    pub ids: Vec<ObjectId>
+}
+
 #[derive(Debug)]
 pub enum FetchUserError {
     UserNotFound
@@ -58,8 +115,9 @@ pub enum FetchUserError {
 
 #[derive(Debug)]
 pub enum CreateUserError {
-    NotHashable(Error),
-    DatabaseError(mongodb::error::Error),
+    NotHashable,
+    DatabaseError,
+    DirectoryNotCreatable,
     UserNameTaken
 }
 
@@ -80,7 +138,11 @@ impl TryFrom<CreateUser> for User {
             name: create_user.username,
             password_hash,
             is_bot: create_user.is_bot,
+            is_public: false,
             description: create_user.description,
+            amount_posts: 0,
+            last_seen_changelog: None,
+            feed_token: None,
             created_at: now,
             updated_at: now,
             deleted_at: None,
@@ -91,8 +153,9 @@ impl TryFrom<CreateUser> for User {
 impl Display for CreateUserError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", match self {
-            CreateUserError::NotHashable(_) => "Something went wrong hashing",
-            CreateUserError::DatabaseError(_) => "Internal",
+            CreateUserError::NotHashable => "Something went wrong hashing",
+            CreateUserError::DatabaseError => "Internal",
+            CreateUserError::DirectoryNotCreatable => "Could not create the directory of the user",
             CreateUserError::UserNameTaken => "Username taken"
         })
     }
@@ -108,14 +171,20 @@ impl ResponseError for CreateUserError {
 }
 
 impl From<mongodb::error::Error> for CreateUserError {
-    fn from(value: mongodb::error::Error) -> Self {
-        CreateUserError::DatabaseError(value)
+    fn from(_: mongodb::error::Error) -> Self {
+        CreateUserError::DatabaseError
+    }
+}
+
+impl From<std::io::Error> for CreateUserError {
+    fn from(_: std::io::Error) -> Self {
+        CreateUserError::DirectoryNotCreatable
     }
 }
 
 impl From<Error> for CreateUserError {
-    fn from(value: Error) -> Self {
-        CreateUserError::NotHashable(value)
+    fn from(_: Error) -> Self {
+        CreateUserError::NotHashable
     }
 }
 
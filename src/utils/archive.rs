@@ -0,0 +1,172 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Component, Path};
+
+use serde::Serialize;
+use zip::result::ZipError;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::model::list::ListContent;
+use crate::model::takeout::{FRIENDS_MANIFEST, FriendManifest, INFORMATION_DIRECTORY, LIST_MANIFEST, ListManifest, LISTS_MANIFEST, POSTS_DIRECTORY, PROFILE_MANIFEST, ProfileManifest, STORIES_DIRECTORY};
+
+// the media of an archive may not grow larger than this, when it is extracted
+pub const MAX_EXTRACTED_SIZE: u64 = 4_000_000_000; // 4gb
+pub const ARCHIVE_TOO_LARGE: &str = "extracted archive too large";
+
+pub struct TakeoutContent {
+    pub user_directory: String,
+    pub profile: ProfileManifest,
+    pub friends: Vec<FriendManifest>,
+    pub lists: Vec<ListManifest>
+}
+
+pub struct RestoredTakeout {
+    pub profile: Option<ProfileManifest>,
+    pub friends: Vec<FriendManifest>,
+    pub lists: Vec<ListManifest>,
+    pub restored_files: usize
+}
+
+// media is copied file by file, so memory usage does not grow with the size of the media folders
+// blocking, call it with spawn_blocking
+pub fn write_takeout_archive(content: &TakeoutContent, destination: &str) -> zip::result::ZipResult<()> {
+    let mut archive = ZipWriter::new(BufWriter::new(File::create(destination)?));
+
+    write_manifest(&mut archive, PROFILE_MANIFEST, &content.profile)?;
+    write_manifest(&mut archive, FRIENDS_MANIFEST, &content.friends)?;
+    write_manifest(&mut archive, LISTS_MANIFEST, &content.lists)?;
+
+    let user_directory = Path::new(&content.user_directory);
+    write_directory(&mut archive, user_directory, POSTS_DIRECTORY)?;
+    write_directory(&mut archive, &user_directory.join(STORIES_DIRECTORY), STORIES_DIRECTORY)?;
+    write_directory(&mut archive, &user_directory.join(INFORMATION_DIRECTORY), INFORMATION_DIRECTORY)?;
+
+    archive.finish()?.flush()?;
+
+    Ok(())
+}
+
+fn write_manifest<W: Write + std::io::Seek>(archive: &mut ZipWriter<W>, name: &str, value: &impl Serialize) -> zip::result::ZipResult<()> {
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    archive.start_file(name, options)?;
+    serde_json::to_writer_pretty(archive, value)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+
+    Ok(())
+}
+
+// only the files directly inside `directory` are written, sub folders are handled by the caller
+fn write_directory<W: Write + std::io::Seek>(archive: &mut ZipWriter<W>, directory: &Path, archive_directory: &str) -> zip::result::ZipResult<()> {
+    let entries = match std::fs::read_dir(directory) {
+        Ok(entries) => entries,
+        // user never uploaded anything of this kind
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err.into())
+    };
+
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let metadata = entry.metadata()?;
+        if !metadata.is_file() {
+            continue;
+        }
+
+        if let Ok(file_name) = entry.file_name().into_string() {
+            // media is already compressed, deflating it again only costs time
+            let options = FileOptions::default()
+                .compression_method(CompressionMethod::Stored)
+                .large_file(metadata.len() >= u32::MAX as u64);
+
+            archive.start_file(format!("{}/{}", archive_directory, file_name), options)?;
+            std::io::copy(&mut File::open(entry.path())?, archive)?;
+        }
+    }
+
+    Ok(())
+}
+
+// counterpart of write_takeout_archive. Media is extracted into `user_directory`, the manifests are parsed and returned
+// blocking, call it with spawn_blocking
+pub fn read_takeout_archive(source: &str, user_directory: &str) -> zip::result::ZipResult<RestoredTakeout> {
+    let mut archive = ZipArchive::new(BufReader::new(File::open(source)?))?;
+    let user_directory = Path::new(user_directory);
+
+    // the sizes in the archive are checked before anything is written
+    let mut declared_size = 0u64;
+    for index in 0..archive.len() {
+        declared_size = declared_size.saturating_add(archive.by_index(index)?.size());
+    }
+    if declared_size > MAX_EXTRACTED_SIZE {
+        return Err(ZipError::InvalidArchive(ARCHIVE_TOO_LARGE));
+    }
+    // the declared sizes can lie, so the written bytes are counted as well
+    let mut remaining_size = MAX_EXTRACTED_SIZE;
+
+    let mut restored = RestoredTakeout {
+        profile: None,
+        friends: vec![],
+        lists: vec![],
+        restored_files: 0,
+    };
+
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index)?;
+        if file.is_dir() {
+            continue;
+        }
+
+        // enclosed_name rejects absolute paths and paths escaping the archive
+        let components = match file.enclosed_name() {
+            Some(path) => path.components()
+                .filter_map(|component| match component {
+                    Component::Normal(part) => part.to_str().map(|part| part.to_string()),
+                    _ => None
+                })
+                .collect::<Vec<_>>(),
+            None => continue
+        };
+
+        let destination = match &components.iter().map(|c| c.as_str()).collect::<Vec<_>>()[..] {
+            [PROFILE_MANIFEST] => {
+                restored.profile = serde_json::from_reader(&mut file).ok();
+                continue;
+            },
+            [FRIENDS_MANIFEST] => {
+                restored.friends = serde_json::from_reader(&mut file).unwrap_or_default();
+                continue;
+            },
+            [LISTS_MANIFEST] => {
+                restored.lists.extend(serde_json::from_reader::<_, Vec<ListManifest>>(&mut file).unwrap_or_default());
+                continue;
+            },
+            // archives of older versions had a single list, kept inside the information directory
+            [LIST_MANIFEST] | [INFORMATION_DIRECTORY, LIST_MANIFEST] => {
+                if let Ok(content) = serde_json::from_reader::<_, ListContent>(&mut file) {
+                    restored.lists.push(content.into());
+                }
+                continue;
+            },
+            [POSTS_DIRECTORY, file_name] => user_directory.join(file_name),
+            [STORIES_DIRECTORY, file_name] => user_directory.join(STORIES_DIRECTORY).join(file_name),
+            [INFORMATION_DIRECTORY, file_name] => user_directory.join(INFORMATION_DIRECTORY).join(file_name),
+            _ => {
+                log::warn!("Skipping unknown takeout entry: {}", file.name());
+                continue;
+            }
+        };
+
+        if let Some(parent) = destination.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let written = std::io::copy(&mut (&mut file).take(remaining_size + 1), &mut BufWriter::new(File::create(&destination)?))?;
+        if written > remaining_size {
+            let _ = std::fs::remove_file(&destination);
+            return Err(ZipError::InvalidArchive(ARCHIVE_TOO_LARGE));
+        }
+        remaining_size -= written;
+        restored.restored_files += 1;
+    }
+
+    Ok(restored)
+}
@@ -0,0 +1,57 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use crate::model::changelog::ChangelogEntry;
+
+pub const CONTENT_TYPE: &str = "application/atom+xml; charset=utf-8";
+
+// an entry per published version, `self_url` is where the feed was requested from
+pub fn changelog_feed(self_url: &str, changelogs: &[ChangelogEntry]) -> String {
+    let updated = changelogs.iter()
+        .map(|changelog| changelog.updated_at)
+        .max()
+        .unwrap_or_else(Utc::now);
+
+    let mut feed = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    feed.push_str("  <title>Changelog</title>\n");
+    feed.push_str(&format!("  <id>{}</id>\n", escape(self_url)));
+    feed.push_str(&format!("  <link rel=\"self\" href=\"{}\"/>\n", escape(self_url)));
+    feed.push_str(&format!("  <updated>{}</updated>\n", timestamp(updated)));
+
+    for changelog in changelogs {
+        feed.push_str("  <entry>\n");
+        feed.push_str(&format!("    <title>Version {}</title>\n", changelog.version));
+        feed.push_str(&format!("    <id>urn:changelog:{}</id>\n", escape(&changelog.version.to_string())));
+        if let Some(published_at) = changelog.published_at {
+            feed.push_str(&format!("    <published>{}</published>\n", timestamp(published_at)));
+        }
+        feed.push_str(&format!("    <updated>{}</updated>\n", timestamp(changelog.updated_at)));
+        feed.push_str(&format!("    <content type=\"html\">{}</content>\n", escape(&content(changelog))));
+        feed.push_str("  </entry>\n");
+    }
+
+    feed.push_str("</feed>\n");
+    feed
+}
+
+// the sections as html, escaped once more when put into the feed
+fn content(changelog: &ChangelogEntry) -> String {
+    [("Added", &changelog.added), ("Fixed", &changelog.fixed), ("Changed", &changelog.changed)]
+        .into_iter()
+        .filter(|(_, items)| !items.is_empty())
+        .map(|(heading, items)| {
+            let items = items.iter().map(|item| format!("<li>{}</li>", escape(item))).collect::<String>();
+            format!("<h3>{}</h3><ul>{}</ul>", heading, items)
+        })
+        .collect()
+}
+
+fn timestamp(date: DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -0,0 +1,284 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::model::list::{List, ListEntry, ListError, Priority, SubEntry};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListFormat {
+    // a GitHub task list, sub entries are indented
+    Markdown,
+    // a row per entry followed by rows for its sub entries
+    Csv
+}
+
+impl ListFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ListFormat::Markdown => "text/markdown; charset=utf-8",
+            ListFormat::Csv => "text/csv; charset=utf-8"
+        }
+    }
+
+    // the format asked for with the Accept header, None for JSON
+    pub fn from_accept(accept: &str) -> Option<Self> {
+        accept.split(',')
+            .map(|media_type| media_type.split(';').next().unwrap_or_default().trim())
+            .find_map(|media_type| match media_type {
+                "text/markdown" => Some(ListFormat::Markdown),
+                "text/csv" => Some(ListFormat::Csv),
+                _ => None
+            })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RowKind {
+    Entry,
+    SubEntry
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CsvRow {
+    kind: RowKind,
+    title: String,
+    checked: bool,
+    due: Option<DateTime<Utc>>,
+    notes: Option<String>,
+    assignee: Option<String>,
+    priority: Option<Priority>
+}
+
+pub fn render(list: &List, format: ListFormat) -> Result<String, ListError> {
+    match format {
+        ListFormat::Markdown => Ok(render_markdown(list)),
+        ListFormat::Csv => render_csv(list)
+    }
+}
+
+// entries without a title are left out
+pub fn parse(content: &str, format: ListFormat) -> Result<Vec<ListEntry>, ListError> {
+    match format {
+        ListFormat::Markdown => Ok(parse_markdown(content)),
+        ListFormat::Csv => parse_csv(content)
+    }
+}
+
+fn render_markdown(list: &List) -> String {
+    let mut markdown = format!("# {}\n\n", single_line(&list.name));
+
+    for entry in &list.entries {
+        markdown.push_str(&task(&entry.title, entry.checked));
+        for sub_entry in &entry.sub_entries {
+            markdown.push_str("  ");
+            markdown.push_str(&task(&sub_entry.title, sub_entry.checked));
+        }
+    }
+
+    markdown
+}
+
+fn task(title: &str, checked: bool) -> String {
+    format!("- [{}] {}\n", if checked { "x" } else { " " }, single_line(title))
+}
+
+fn single_line(text: &str) -> String {
+    text.lines().map(str::trim).collect::<Vec<_>>().join(" ")
+}
+
+// lenient, so lists pasted from notes apps work: headings are skipped, lines without a checkbox are unchecked
+// and indented lines are sub entries of the entry above
+fn parse_markdown(content: &str) -> Vec<ListEntry> {
+    let mut entries: Vec<ListEntry> = vec![];
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let indented = trimmed.len() != line.len();
+        let text = ["- ", "* ", "+ "].iter()
+            .find_map(|marker| trimmed.strip_prefix(marker))
+            .unwrap_or(trimmed)
+            .trim_start();
+
+        let (checked, title) = match text.get(..3) {
+            Some("[x]" | "[X]") => (true, &text[3..]),
+            Some("[ ]") => (false, &text[3..]),
+            _ => (false, text)
+        };
+        let title = title.trim();
+        if title.is_empty() {
+            continue;
+        }
+
+        match entries.last_mut() {
+            Some(entry) if indented => {
+                let mut sub_entry = SubEntry::new(title.to_string());
+                sub_entry.checked = checked;
+                entry.sub_entries.push(sub_entry);
+            },
+            _ => {
+                let mut entry = ListEntry::new(title.to_string());
+                entry.checked = checked;
+                entries.push(entry);
+            }
+        }
+    }
+
+    entries
+}
+
+fn render_csv(list: &List) -> Result<String, ListError> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+
+    for entry in &list.entries {
+        writer.serialize(CsvRow {
+            kind: RowKind::Entry,
+            title: entry.title.clone(),
+            checked: entry.checked,
+            due: entry.due,
+            notes: entry.notes.clone(),
+            assignee: entry.assignee.clone(),
+            priority: Some(entry.priority),
+        }).map_err(|_| ListError::Internal)?;
+
+        for sub_entry in &entry.sub_entries {
+            writer.serialize(CsvRow {
+                kind: RowKind::SubEntry,
+                title: sub_entry.title.clone(),
+                checked: sub_entry.checked,
+                due: None,
+                notes: None,
+                assignee: None,
+                priority: None,
+            }).map_err(|_| ListError::Internal)?;
+        }
+    }
+
+    let bytes = writer.into_inner().map_err(|_| ListError::Internal)?;
+    String::from_utf8(bytes).map_err(|_| ListError::Internal)
+}
+
+// sub entry rows belong to the entry row above them
+fn parse_csv(content: &str) -> Result<Vec<ListEntry>, ListError> {
+    let mut entries: Vec<ListEntry> = vec![];
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+
+    for row in reader.deserialize::<CsvRow>() {
+        let row = row.map_err(|_| ListError::InvalidImport)?;
+        let title = row.title.trim();
+        if title.is_empty() {
+            continue;
+        }
+
+        match (row.kind, entries.last_mut()) {
+            (RowKind::SubEntry, Some(entry)) => {
+                let mut sub_entry = SubEntry::new(title.to_string());
+                sub_entry.checked = row.checked;
+                entry.sub_entries.push(sub_entry);
+            },
+            (RowKind::SubEntry, None) => return Err(ListError::InvalidImport),
+            (RowKind::Entry, _) => {
+                let mut entry = ListEntry::new(title.to_string());
+                entry.checked = row.checked;
+                entry.due = row.due;
+                entry.notes = row.notes.filter(|notes| !notes.is_empty());
+                entry.assignee = row.assignee.filter(|assignee| !assignee.is_empty());
+                entry.priority = row.priority.unwrap_or_default();
+                entries.push(entry);
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use mongodb::bson::oid::ObjectId;
+    use crate::model::list::{List, ListEntry, ListError, Priority, SubEntry};
+    use super::{ListFormat, parse, render};
+
+    fn list() -> List {
+        let mut groceries = ListEntry::new(String::from("Groceries"));
+        groceries.due = Some(Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap());
+        groceries.notes = Some(String::from("before the weekend, \"organic\" if possible"));
+        groceries.assignee = Some(ObjectId::new().to_hex());
+        groceries.priority = Priority::High;
+        let mut milk = SubEntry::new(String::from("Milk"));
+        milk.checked = true;
+        groceries.sub_entries = vec![milk, SubEntry::new(String::from("Bread"))];
+
+        let mut laundry = ListEntry::new(String::from("Laundry"));
+        laundry.checked = true;
+
+        List::new(ObjectId::new(), String::from("Weekend"), vec![groceries, laundry])
+    }
+
+    fn sub_entries(entry: &ListEntry) -> Vec<(&str, bool)> {
+        entry.sub_entries.iter().map(|sub_entry| (sub_entry.title.as_str(), sub_entry.checked)).collect()
+    }
+
+    #[test]
+    fn csv_round_trips_entries() {
+        let list = list();
+        let entries = parse(&render(&list, ListFormat::Csv).unwrap(), ListFormat::Csv).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        for (parsed, original) in entries.iter().zip(&list.entries) {
+            assert_eq!(parsed.title, original.title);
+            assert_eq!(parsed.checked, original.checked);
+            assert_eq!(parsed.due, original.due);
+            assert_eq!(parsed.notes, original.notes);
+            assert_eq!(parsed.assignee, original.assignee);
+            assert_eq!(parsed.priority, original.priority);
+            assert_eq!(sub_entries(parsed), sub_entries(original));
+        }
+    }
+
+    #[test]
+    fn markdown_round_trips_titles_and_sub_entries() {
+        let list = list();
+        let markdown = render(&list, ListFormat::Markdown).unwrap();
+        let entries = parse(&markdown, ListFormat::Markdown).unwrap();
+
+        assert!(markdown.starts_with("# Weekend\n"));
+        assert_eq!(entries.iter().map(|entry| (entry.title.as_str(), entry.checked)).collect::<Vec<_>>(), vec![("Groceries", false), ("Laundry", true)]);
+        assert_eq!(sub_entries(&entries[0]), vec![("Milk", true), ("Bread", false)]);
+    }
+
+    #[test]
+    fn malformed_csv_rows_are_rejected() {
+        let missing_field = "kind,title,checked,due,notes,assignee,priority\nentry,Groceries,false\n";
+        let invalid_checked = "kind,title,checked,due,notes,assignee,priority\nentry,Groceries,maybe,,,,\n";
+        let orphaned_sub_entry = "kind,title,checked,due,notes,assignee,priority\nsub_entry,Milk,false,,,,\n";
+
+        for content in [missing_field, invalid_checked, orphaned_sub_entry] {
+            assert!(matches!(parse(content, ListFormat::Csv), Err(ListError::InvalidImport)), "{}", content);
+        }
+    }
+
+    #[test]
+    fn unknown_csv_columns_are_ignored() {
+        let content = "kind,title,checked,due,notes,assignee,priority,color\nentry,Groceries,true,,,,low,green\nsub_entry,Milk,false,,,,,blue\n";
+        let entries = parse(content, ListFormat::Csv).unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].title, "Groceries");
+        assert!(entries[0].checked);
+        assert_eq!(entries[0].priority, Priority::Low);
+        assert_eq!(sub_entries(&entries[0]), vec![("Milk", false)]);
+    }
+
+    #[test]
+    fn indented_items_without_a_parent_become_entries() {
+        let entries = parse("# Weekend\n\n  - [x] Milk\n- Laundry\n    * Socks\n", ListFormat::Markdown).unwrap();
+
+        assert_eq!(entries.iter().map(|entry| (entry.title.as_str(), entry.checked)).collect::<Vec<_>>(), vec![("Milk", true), ("Laundry", false)]);
+        assert!(entries[0].sub_entries.is_empty());
+        assert_eq!(sub_entries(&entries[1]), vec![("Socks", false)]);
+    }
+}
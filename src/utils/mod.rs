@@ -13,6 +13,9 @@ use crate::api::shared::UploadError;
 use crate::model::states::app_state::AppState;
 
 pub mod version;
+pub mod archive;
+pub mod list_format;
+pub mod atom;
 
 pub fn read_files_in_directory(path: &str, reversed: bool) -> std::io::Result<Vec<DirEntry>> {
     let directory = std::fs::read_dir(path)?;
@@ -45,7 +48,16 @@ pub fn read_files_in_directory(path: &str, reversed: bool) -> std::io::Result<Ve
     Ok(all_files)
 }
 
-pub fn validate_stories(path: &str) -> std::io::Result<()> {
+// files only, sub directories like stories and information are not counted
+pub fn count_files_in_directory(path: &str) -> std::io::Result<u64> {
+    Ok(std::fs::read_dir(path)?
+        .filter_map(|file| file.ok())
+        .filter(|a| a.path().is_file())
+        .count() as u64)
+}
+
+// removes the stories older than 24 hours and returns their file names
+pub fn validate_stories(path: &str) -> std::io::Result<Vec<String>> {
     let directory = std::fs::read_dir(path)?;
 
     let all_files_older_than_24_hours = directory
@@ -62,11 +74,13 @@ pub fn validate_stories(path: &str) -> std::io::Result<()> {
         })
         .collect::<Vec<_>>();
 
+    let mut removed = vec![];
     for file in &all_files_older_than_24_hours {
         std::fs::remove_file(file.path().display().to_string())?;
+        removed.extend(file.file_name().into_string().ok());
     }
 
-    Ok(())
+    Ok(removed)
 }
 
 fn is_older_than_24_hours(target_time: SystemTime) -> bool {
@@ -86,17 +100,18 @@ pub struct UploadOptions {
     pub legal_file_types: Vec<Mime>,
 }
 
-pub async fn write_files_in_directory(req: &HttpRequest, mut payload: Multipart, upload_options: UploadOptions, state: &Data<AppState>, file_name_delegate: impl Fn(&Data<AppState>, &str) -> String) -> Result<(), UploadError> {
+pub async fn write_files_in_directory(req: &HttpRequest, mut payload: Multipart, upload_options: UploadOptions, state: &Data<AppState>, file_name_delegate: impl Fn(&Data<AppState>, &str) -> String) -> Result<Vec<String>, UploadError> {
     let content_length: usize = match req.headers().get(actix_web::http::header::CONTENT_LENGTH) {
         Some(header_value) => header_value
             .to_str()
             .unwrap_or("0")
             .parse::<usize>()
-            .map_err(UploadError::CorruptedHeaderLength)?,
+            .map_err(|_| UploadError::CorruptedHeaderLength)?,
         None => 0
     };
 
     let mut current_count = 0;
+    let mut written_files = vec![];
     if content_length > upload_options.max_file_size { return Err(UploadError::FileSizeTooBig); }
 
     loop {
@@ -117,10 +132,12 @@ pub async fn write_files_in_directory(req: &HttpRequest, mut payload: Multipart,
                     return Err(UploadError::WritingError);
                 }
             }
+
+            written_files.push(destination);
         }
 
         current_count += 1;
     }
 
-    Ok(())
+    Ok(written_files)
 }
\ No newline at end of file
@@ -1,41 +1,83 @@
 use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
-use std::num::ParseFloatError;
+use std::num::ParseIntError;
 use std::str::FromStr;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 #[derive(Debug)]
 pub enum Error {
     UndefinedSequence(char),
-    F32Parsing(ParseFloatError)
+    InvalidNumber(ParseIntError),
+    // more than major.minor.patch or an empty part
+    InvalidFormat(String)
 }
-#[derive(Debug, Clone)]
+
+// a semantic version. Missing minor or patch parts are 0, build metadata is ignored
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Version {
-    pub version: f32
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub pre_release: Vec<PreRelease>
 }
 
-impl From<ParseFloatError> for Error {
-    fn from(value: ParseFloatError) -> Self {
-        Error::F32Parsing(value)
-    }
+// an identifier of the pre-release, `beta.2` has two
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PreRelease {
+    Numeric(u64),
+    Alphanumeric(String)
 }
 
-impl Eq for Version {}
+impl Version {
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            pre_release: vec![],
+        }
+    }
+}
 
-impl PartialEq<Self> for Version {
-    fn eq(&self, other: &Self) -> bool {
-        self.version == other.version
+impl From<ParseIntError> for Error {
+    fn from(value: ParseIntError) -> Self {
+        Error::InvalidNumber(value)
     }
 }
 
 impl PartialOrd<Self> for Version {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.version.partial_cmp(&other.version)
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for Version {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.version.total_cmp(&other.version)
+        (self.major, self.minor, self.patch).cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre_release.is_empty(), other.pre_release.is_empty()) {
+                // a pre-release comes before its release
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => self.pre_release.cmp(&other.pre_release)
+            })
+    }
+}
+
+impl PartialOrd<Self> for PreRelease {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PreRelease {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (PreRelease::Numeric(a), PreRelease::Numeric(b)) => a.cmp(b),
+            (PreRelease::Alphanumeric(a), PreRelease::Alphanumeric(b)) => a.cmp(b),
+            (PreRelease::Numeric(_), PreRelease::Alphanumeric(_)) => Ordering::Less,
+            (PreRelease::Alphanumeric(_), PreRelease::Numeric(_)) => Ordering::Greater
+        }
     }
 }
 
@@ -45,47 +87,165 @@ impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", match self {
             Error::UndefinedSequence(c) => format!("Cannot parse character: {}", c),
-            Error::F32Parsing(f) => f.to_string()
+            Error::InvalidNumber(n) => n.to_string(),
+            Error::InvalidFormat(s) => format!("Not a version: {}", s)
         })
     }
 }
 
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+
+        for (index, identifier) in self.pre_release.iter().enumerate() {
+            write!(f, "{}", if index == 0 { "-" } else { "." })?;
+            match identifier {
+                PreRelease::Numeric(number) => write!(f, "{}", number)?,
+                PreRelease::Alphanumeric(text) => write!(f, "{}", text)?
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// accepts `1.2.3-beta.1`, `v1.2` and the changelog file names like `Version 1.2.3`
 impl FromStr for Version {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s.to_lowercase();
+        let s = s.trim();
+        let s = s.strip_prefix("Version ")
+            .or_else(|| s.strip_prefix("version "))
+            .or_else(|| s.strip_prefix('v'))
+            .unwrap_or(s)
+            .trim();
+
+        let without_build = s.split('+').next().unwrap_or_default();
+        let (numbers, pre_release) = match without_build.split_once('-') {
+            Some((numbers, pre_release)) => (numbers, Some(pre_release)),
+            None => (without_build, None)
+        };
+
+        if let Some(char) = numbers.chars().find(|char| !char.is_ascii_digit() && *char != '.') {
+            return Err(Error::UndefinedSequence(char));
+        }
 
-        if let ["version", version] = &s.split(' ').collect::<Vec<&str>>()[..] {
-            let mut iter = version.split('.');
-            let first_part = iter.next().unwrap_or("0");
-            let rest_parts: String = iter.collect();
+        let parts = numbers.split('.').collect::<Vec<_>>();
+        if parts.len() > 3 || parts.iter().any(|part| part.is_empty()) {
+            return Err(Error::InvalidFormat(s.to_string()));
+        }
 
-            let version_str = format!("{}.{}", first_part, rest_parts);
-            let s = version_str.parse()?;
+        let part = |index: usize| parts.get(index).map(|part| part.parse()).unwrap_or(Ok(0));
+        let pre_release = match pre_release {
+            Some(pre_release) => pre_release.split('.')
+                .map(|identifier| parse_pre_release(identifier, s))
+                .collect::<Result<_, _>>()?,
+            None => vec![]
+        };
 
-            return Ok(Self {
-                version: s
-            })
-        }
+        Ok(Self {
+            major: part(0)?,
+            minor: part(1)?,
+            patch: part(2)?,
+            pre_release,
+        })
+    }
+}
 
-        for char in s.chars() {
-            if char.is_ascii_digit() || char == '.' {
-                continue;
-            } else {
-                return Err(Error::UndefinedSequence(char))
-            }
+fn parse_pre_release(identifier: &str, version: &str) -> Result<PreRelease, Error> {
+    if identifier.is_empty() {
+        return Err(Error::InvalidFormat(version.to_string()));
+    }
+    if let Some(char) = identifier.chars().find(|char| !char.is_ascii_alphanumeric() && *char != '-') {
+        return Err(Error::UndefinedSequence(char));
+    }
+
+    Ok(match identifier.parse() {
+        Ok(number) => PreRelease::Numeric(number),
+        Err(_) => PreRelease::Alphanumeric(identifier.to_string())
+    })
+}
+
+// as the string `1.2.3-beta.1`
+impl Serialize for Version {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PreRelease, Version};
+
+    fn version(s: &str) -> Version {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_changelog_names_and_plain_versions() {
+        assert_eq!(version("Version 1.2.3"), Version::new(1, 2, 3));
+        assert_eq!(version("1.2.3"), Version::new(1, 2, 3));
+        assert_eq!(version("v1.2"), Version::new(1, 2, 0));
+        assert_eq!(version("1.2.3+build.5"), Version::new(1, 2, 3));
+    }
+
+    #[test]
+    fn parses_pre_releases() {
+        let beta = version("1.2.3-beta.1");
+
+        assert_eq!((beta.major, beta.minor, beta.patch), (1, 2, 3));
+        assert_eq!(beta.pre_release, vec![PreRelease::Alphanumeric(String::from("beta")), PreRelease::Numeric(1)]);
+        assert_eq!(beta.to_string(), "1.2.3-beta.1");
+    }
+
+    #[test]
+    fn compares_parts_as_numbers() {
+        assert!(version("1.10.0") > version("1.9.0"));
+        assert!(version("1.2.10") > version("1.2.9"));
+        assert!(version("2.0.0") > version("1.99.99"));
+        assert_ne!(version("1.2.3"), version("1.23"));
+    }
+
+    #[test]
+    fn orders_pre_releases_before_their_release() {
+        assert!(version("1.0.0-alpha") < version("1.0.0"));
+        assert!(version("1.0.0") < version("1.0.1-alpha"));
+    }
+
+    // the order of the semver specification
+    #[test]
+    fn orders_pre_release_identifiers() {
+        let ordered = ["1.0.0-alpha", "1.0.0-alpha.1", "1.0.0-alpha.beta", "1.0.0-beta", "1.0.0-beta.2", "1.0.0-beta.11", "1.0.0-rc.1", "1.0.0"]
+            .map(version);
+
+        for pair in ordered.windows(2) {
+            assert!(pair[0] < pair[1], "{} < {}", pair[0], pair[1]);
         }
+    }
 
-        let mut iter = s.split('.');
-        let first_part = iter.next().unwrap_or("0");
-        let rest_parts: String = iter.collect();
+    #[test]
+    fn round_trips_through_serde() {
+        let beta = version("1.2.3-beta.1");
+        let json = serde_json::to_string(&beta).unwrap();
 
-        let version_str = format!("{}.{}", first_part, rest_parts);
-        let s = version_str.parse()?;
+        assert_eq!(json, "\"1.2.3-beta.1\"");
+        assert_eq!(serde_json::from_str::<Version>(&json).unwrap(), beta);
+        assert!(serde_json::from_str::<Version>("\"1.x\"").is_err());
+    }
 
-        Ok(Self {
-            version: s
-        })
+    #[test]
+    fn rejects_malformed_versions() {
+        for malformed in ["", "1..2", "1.2.3.4", "1.x", "1.2.", "1.2.3-", "1.2.3-beta..1", "1.2.3-be_ta", "a.b.c"] {
+            assert!(malformed.parse::<Version>().is_err(), "{} was accepted", malformed);
+        }
     }
 }